@@ -224,15 +224,19 @@ const build = (input: Record<string, unknown>, data: InputData) => {
 					z.object({
 						// TODO: Use a custom zod schema to retain string template literals here
 						// https://github.com/colinhacks/zod?tab=readme-ov-file#custom-schemas
-						field: zod.schema.dynamic_enum(
-							[
-								...SIMPLE_EDGE_SORT_FIELDS,
-								...data.edge_fields.map(
-									(f) => `neighbour-field:${f.label}`,
-								),
-							],
-							"sort",
-						),
+						field: z.union([
+							zod.schema.dynamic_enum(
+								[
+									...SIMPLE_EDGE_SORT_FIELDS,
+									...data.edge_fields.map(
+										(f) => `neighbour-field:${f.label}`,
+									),
+								],
+								"sort",
+							),
+							// Any note property can be sorted by, e.g. `property:priority`
+							z.string().regex(/^property:.+$/),
+						]),
 
 						order: z
 							.union(
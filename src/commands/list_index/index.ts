@@ -132,13 +132,15 @@ export function build_list_index(
 	plugin_settings: BreadcrumbsSettings | undefined,
 	options: ListIndexOptions,
 ): string {
-	const traversal_options = new TraversalOptions(
+	const traversal_options = TraversalOptions.with_defaults(
 		[start_node],
 		options.fields,
 		options.max_depth ?? 100,
-		options.max_count ?? 1000,
 		false,
 	);
+	if (options.max_count !== undefined) {
+		traversal_options.max_traversal_count = options.max_count;
+	}
 
 	const postprocess_options = new TraversalPostprocessOptions(
 		create_edge_sorter(
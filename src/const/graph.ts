@@ -23,10 +23,15 @@ export const SIMPLE_EDGE_SORT_FIELDS = [
 	// Whether the edge is explicit or not
 	// Uses source and implied_kind as tie-breakers for explicit == true and false, respectively
 	"explicit",
+	// The position of the edge among its siblings, e.g. in a list note
+	"order",
 ] as const;
 type SimpleEdgeSortField = (typeof SIMPLE_EDGE_SORT_FIELDS)[number];
 
-export const COMPLEX_EDGE_SORT_FIELD_PREFIXES = ["neighbour-field"] as const;
+export const COMPLEX_EDGE_SORT_FIELD_PREFIXES = [
+	"neighbour-field",
+	"property",
+] as const;
 export type ComplexEdgeSortFieldPrefix =
 	(typeof COMPLEX_EDGE_SORT_FIELD_PREFIXES)[number];
 
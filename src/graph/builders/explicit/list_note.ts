@@ -273,14 +273,15 @@ export const _add_explicit_edges_list_note: ExplicitEdgeBuilder = (
 						return;
 					}
 
-					results.edges.push(
-						new GCEdgeData(
-							list_note_page.file.path,
-							source_path,
-							source_override_field.data?.field ?? list_note_info.data.field,
-							"list_note",
-						),
+					const edge = new GCEdgeData(
+						list_note_page.file.path,
+						source_path,
+						source_override_field.data?.field ?? list_note_info.data.field,
+						"list_note",
 					);
+					edge.order = source_list_item_i;
+
+					results.edges.push(edge);
 				}
 
 				// NOTE: The logic of this function is _just_ complicated enough to warrent a separate function
@@ -296,7 +297,7 @@ export const _add_explicit_edges_list_note: ExplicitEdgeBuilder = (
 					});
 				}
 
-				source_list_item.children.forEach((target_list_item) => {
+				source_list_item.children.forEach((target_list_item, target_item_i) => {
 					const target_link = target_list_item.outlinks.at(0);
 					if (!target_link) return;
 
@@ -336,14 +337,15 @@ export const _add_explicit_edges_list_note: ExplicitEdgeBuilder = (
 						);
 					}
 
-					results.edges.push(
-						new GCEdgeData(
-							source_path,
-							target_path,
-							target_override_field.data?.field ?? list_note_info.data.field,
-							"list_note",
-						),
+					const edge = new GCEdgeData(
+						source_path,
+						target_path,
+						target_override_field.data?.field ?? list_note_info.data.field,
+						"list_note",
 					);
+					edge.order = target_item_i;
+
+					results.edges.push(edge);
 				});
 			},
 		);
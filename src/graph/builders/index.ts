@@ -1,18 +1,42 @@
-import { parseFrontMatterAliases } from "obsidian";
+import { getAllTags, parseFrontMatterAliases } from "obsidian";
 import { EXPLICIT_EDGE_SOURCES } from "src/const/graph";
 import { META_ALIAS } from "src/const/metadata_fields";
 import { log } from "src/logger";
 import type BreadcrumbsPlugin from "src/main";
 import { Timer } from "src/utils/timer";
+import { transitive_rule_steps } from "src/utils/transitive_rules";
 import type { GCEdgeData } from "wasm/pkg/breadcrumbs_graph_wasm";
 import {
 	GCNodeData,
+	NoteGraphError,
 	TransitiveGraphRule,
 } from "wasm/pkg/breadcrumbs_graph_wasm";
 import { add_explicit_edges } from "./explicit";
 import type { AllFiles } from "./explicit/files";
 import { get_all_files } from "./explicit/files";
 
+/** Copy the frontmatter of a note onto its node as typed properties.
+ * Values the graph can't represent (e.g. nested objects) are skipped with a warning.
+ */
+function set_node_properties(
+	node: GCNodeData,
+	path: string,
+	frontmatter: Record<string, unknown> | undefined,
+) {
+	if (!frontmatter) return;
+
+	for (const [key, value] of Object.entries(frontmatter)) {
+		try {
+			node.set_property(key, value);
+		} catch (error) {
+			log.warn(
+				`Skipping frontmatter property '${key}' of '${path}':`,
+				error instanceof NoteGraphError ? error.message : error,
+			);
+		}
+	}
+}
+
 function get_initial_nodes(all_files: AllFiles) {
 	const nodes: GCNodeData[] = [];
 
@@ -34,15 +58,17 @@ function get_initial_nodes(all_files: AllFiles) {
 				ignore_out_edges = true;
 			}
 
-			nodes.push(
-				new GCNodeData(
-					file.path,
-					node_aliases,
-					true,
-					ignore_in_edges,
-					ignore_out_edges,
-				),
+			const node = new GCNodeData(
+				file.path,
+				node_aliases,
+				true,
+				ignore_in_edges,
+				ignore_out_edges,
 			);
+			node.tags = cache ? (getAllTags(cache) ?? []) : [];
+			set_node_properties(node, file.path, cache?.frontmatter);
+
+			nodes.push(node);
 		});
 	} else {
 		all_files.dataview.forEach((page) => {
@@ -62,15 +88,17 @@ function get_initial_nodes(all_files: AllFiles) {
 				ignore_out_edges = true;
 			}
 
-			nodes.push(
-				new GCNodeData(
-					page.file.path,
-					node_aliases,
-					true,
-					ignore_in_edges,
-					ignore_out_edges,
-				),
+			const node = new GCNodeData(
+				page.file.path,
+				node_aliases,
+				true,
+				ignore_in_edges,
+				ignore_out_edges,
 			);
+			node.tags = page.file.etags.values;
+			set_node_properties(node, page.file.path, page.file.frontmatter);
+
+			nodes.push(node);
 		});
 	}
 
@@ -110,9 +138,9 @@ export const rebuild_graph = async (plugin: BreadcrumbsPlugin) => {
 
 	const transitive_rules = plugin.settings.implied_relations.transitive.map(
 		(rule) => {
-			return new TransitiveGraphRule(
+			return TransitiveGraphRule.from_steps(
 				rule.name,
-				rule.chain.map((attr) => attr.field!),
+				transitive_rule_steps(rule.chain),
 				rule.close_field,
 				rule.rounds,
 				false,
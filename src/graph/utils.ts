@@ -42,6 +42,8 @@ export const EDGE_ATTRIBUTES = [
 	"source",
 	"implied_kind",
 	"round",
+	"label",
+	"order",
 ] as const;
 
 export type EdgeAttribute = (typeof EDGE_ATTRIBUTES)[number];
@@ -34,7 +34,10 @@ export interface BreadcrumbsSettings {
 		transitive: {
 			name: string;
 			rounds: number;
-			chain: EdgeAttrFilters[];
+			chain: (EdgeAttrFilters & {
+				/** Follow the edges of the field backwards, from their target to their source */
+				reversed?: boolean;
+			})[];
 			/** The transitive closure field */
 			close_field: string;
 			/** If false, add the edge from start to end. Else from end to start */
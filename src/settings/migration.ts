@@ -259,6 +259,15 @@ export function migrate_old_settings(settings: BreadcrumbsSettings) {
 		delete old.custom_implied_relations;
 	}
 
+	/// Reversed steps of the chain used to be stored as a `~` before the field
+	settings.implied_relations.transitive.forEach((rule) => {
+		rule.chain = rule.chain.map((attr) =>
+			attr.field?.startsWith("~")
+				? { ...attr, field: attr.field.slice(1), reversed: true }
+				: attr,
+		);
+	});
+
 	settings.implied_relations.transitive = remove_duplicates_by(
 		settings.implied_relations.transitive,
 		stringify_transitive_relation,
@@ -1,4 +1,5 @@
 import type { BreadcrumbsSettings } from "src/interfaces/settings";
+import { transitive_rule_steps } from "src/utils/transitive_rules";
 import type { NodeData } from "wasm/pkg/breadcrumbs_graph_wasm";
 import {
 	MermaidGraphOptions,
@@ -77,9 +78,9 @@ function from_transitive_rule(
 		"chain" | "close_field" | "close_reversed" | "name"
 	>,
 ) {
-	const wasm_rule = new TransitiveGraphRule(
+	const wasm_rule = TransitiveGraphRule.from_steps(
 		"",
-		rule.chain.map((attr) => attr.field!),
+		transitive_rule_steps(rule.chain),
 		rule.close_field,
 		1,
 		false,
@@ -89,7 +90,7 @@ function from_transitive_rule(
 	const graph = wasm_rule.create_example_graph();
 
 	return graph.generate_mermaid_graph(
-		new TraversalOptions(["1"], undefined, 100, 1000, false),
+		TraversalOptions.with_defaults(["1"], undefined, 100, false),
 		new MermaidGraphOptions(
 			undefined,
 			"",
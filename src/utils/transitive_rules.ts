@@ -1,6 +1,7 @@
 import type { Result } from "src/interfaces/result";
 import type { BreadcrumbsSettings, EdgeField } from "src/interfaces/settings";
 import { url_search_params } from "src/utils/url";
+import { RuleStep } from "wasm/pkg/breadcrumbs_graph_wasm";
 import { z } from "zod";
 import { fail, succ } from "./result";
 import { split_and_trim } from "./strings";
@@ -9,11 +10,22 @@ import { zod } from "./zod";
 type TransitiveRule =
 	BreadcrumbsSettings["implied_relations"]["transitive"][number];
 
+type ChainStep = TransitiveRule["chain"][number];
+
+/** A `~` before a field follows the edges of that field backwards */
+const stringify_chain_step = ({ reversed, ...attr }: ChainStep) =>
+	(reversed ? "~" : "") + url_search_params(attr, { trim_lone_param: true });
+
+const parse_chain_step = (field: string): ChainStep =>
+	field.startsWith("~")
+		? { field: field.slice(1), reversed: true }
+		: { field };
+
 export const stringify_transitive_relation = (
 	rule: Pick<TransitiveRule, "chain" | "close_field" | "close_reversed">,
 ) =>
 	`[${rule.chain
-		.map((attr) => url_search_params(attr, { trim_lone_param: true }))
+		.map(stringify_chain_step)
 		.join(", ")}] ${rule.close_reversed ? "<-" : "->"} ${rule.close_field}`;
 
 const regex = /\[(.+)\]\s*(<-|->)\s*(.+)/;
@@ -39,17 +51,24 @@ export const parse_transitive_relation = (
 		return succ({
 			close_field: match[3],
 			close_reversed: match[2] === "<-",
-			chain: split_and_trim(match[1]).map((field) => ({ field })),
+			chain: split_and_trim(match[1]).map(parse_chain_step),
 		});
 	}
 };
 
+/** Turns the chain of a rule from the settings into the steps of a wasm rule. */
+export const transitive_rule_steps = (chain: TransitiveRule["chain"]) =>
+	chain.map((attr) => new RuleStep(attr.field!, attr.reversed ?? false));
+
 export const input_transitive_rule_schema = (data: { fields: EdgeField[] }) => {
 	const field_labels = data.fields.map((f) => f.label);
 
 	return z.object({
 		chain: z.array(
-			z.object({ field: zod.schema.dynamic_enum(field_labels) }),
+			z.object({
+				field: zod.schema.dynamic_enum(field_labels),
+				reversed: z.boolean().optional(),
+			}),
 		),
 
 		close_field: zod.schema.dynamic_enum(field_labels, "close_field"),
@@ -687,4 +687,29 @@ describe("migration", () => {
 			},
 		} satisfies BreadcrumbsSettings);
 	});
+
+	test("reversed chain steps", () => {
+		const old: BreadcrumbsSettings = structuredClone(DEFAULT_SETTINGS);
+		old.implied_relations.transitive = [
+			{
+				name: "",
+				rounds: 1,
+				chain: [{ field: "up" }, { field: "~up" }],
+				close_field: "same",
+				close_reversed: false,
+			},
+		];
+
+		const migrated = migrate_old_settings(old);
+
+		expect(migrated.implied_relations.transitive).toStrictEqual([
+			{
+				name: "",
+				rounds: 1,
+				chain: [{ field: "up" }, { field: "up", reversed: true }],
+				close_field: "same",
+				close_reversed: false,
+			},
+		]);
+	});
 });
@@ -0,0 +1,84 @@
+use hashbrown::HashSet;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    builders::{basename, build_note_path, dirname, BuilderNote, BuilderResult},
+    data::construction::{GCEdgeData, GCNodeData},
+    utils::{NoteGraphError, Result},
+};
+
+/// The edge source of edges created by the Dendron builder.
+pub const DENDRON_EDGE_SOURCE: &str = "dendron_note";
+
+/// Builds the edges of a Dendron hierarchy, where the basename of a note is a
+/// `delimiter` separated list of its ancestors, e.g. `a.b.c` points to `a.b`,
+/// which points to `a`. Parents are looked up in the same folder as the child.
+///
+/// `notes` should contain all notes in the vault. Missing parents are added as
+/// unresolved nodes, which point to their own parents in turn. Like the edge
+/// from a note, the edges from its missing ancestors use the note's field, so
+/// an unresolved node gets an edge to its parent for each field of the notes
+/// below it.
+#[wasm_bindgen]
+pub fn build_dendron_edges(
+    notes: Vec<BuilderNote>,
+    delimiter: String,
+    default_field: String,
+) -> Result<BuilderResult> {
+    if delimiter.is_empty() {
+        return Err(NoteGraphError::new(
+            "The Dendron note delimiter must not be empty",
+        ));
+    }
+
+    let existing_paths: HashSet<&str> = notes.iter().map(|note| note.path.as_str()).collect();
+    let mut unresolved_paths: HashSet<String> = HashSet::new();
+    // the edges from unresolved nodes, so that we don't add them twice
+    let mut unresolved_edges: HashSet<(String, String)> = HashSet::new();
+    let mut result = BuilderResult::default();
+
+    for note in &notes {
+        let Some(field) = note.field_or(&default_field) else {
+            continue;
+        };
+
+        let mut source = note.path.clone();
+
+        // walk up the hierarchy until we reach a parent that exists
+        while let Some(target) = dendron_parent(&source, &delimiter) {
+            result.edges.push(GCEdgeData::new(
+                source,
+                target.clone(),
+                field.to_string(),
+                DENDRON_EDGE_SOURCE.to_string(),
+            ));
+
+            if existing_paths.contains(target.as_str()) {
+                break;
+            }
+
+            if unresolved_paths.insert(target.clone()) {
+                result
+                    .nodes
+                    .push(GCNodeData::new_unresolved(target.clone()));
+            }
+
+            // the rest of the hierarchy was already added with this field
+            if !unresolved_edges.insert((target.clone(), field.to_string())) {
+                break;
+            }
+
+            source = target;
+        }
+    }
+
+    Ok(result)
+}
+
+/// The path of the Dendron parent of a note, or None if the note is at the top
+/// of its hierarchy.
+fn dendron_parent(path: &str, delimiter: &str) -> Option<String> {
+    let (parent_basename, _) = basename(path).rsplit_once(delimiter)?;
+
+    Some(build_note_path(dirname(path), parent_basename))
+}
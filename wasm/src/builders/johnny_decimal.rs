@@ -0,0 +1,136 @@
+use hashbrown::HashMap;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    builders::{basename, BuilderNote, BuilderResult},
+    data::construction::GCEdgeData,
+    utils::{NoteGraphError, Result},
+};
+
+/// The edge source of edges created by the Johnny.Decimal builder.
+pub const JOHNNY_DECIMAL_EDGE_SOURCE: &str = "johnny_decimal_note";
+
+/// A Johnny.Decimal prefix of a note's basename, e.g. `10-19` for an area,
+/// `11` for a category and `11.04` for an ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JohnnyDecimalPrefix {
+    /// an area, identified by its tens digit, e.g. 1 for `10-19`
+    Area(u8),
+    /// a category, e.g. 11
+    Category(u8),
+    /// an ID within a category, e.g. (11, 4) for `11.04`
+    Id(u8, u16),
+}
+
+impl JohnnyDecimalPrefix {
+    /// Parses the prefix of a basename. The prefix has to be followed by
+    /// whitespace or be the whole basename. Returns None if the basename does
+    /// not start with a well formed prefix.
+    pub fn parse(basename: &str, delimiter: &str) -> Option<JohnnyDecimalPrefix> {
+        let prefix = basename.split_whitespace().next()?;
+
+        if let Some((start, end)) = prefix.split_once('-') {
+            let start = parse_two_digits(start)?;
+            let end = parse_two_digits(end)?;
+
+            return (start % 10 == 0 && end == start + 9)
+                .then_some(JohnnyDecimalPrefix::Area(start / 10));
+        }
+
+        match prefix.split_once(delimiter) {
+            Some((category, id)) => {
+                let category = parse_two_digits(category)?;
+                if id.len() < 2 || !id.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+
+                Some(JohnnyDecimalPrefix::Id(category, id.parse().ok()?))
+            }
+            None => Some(JohnnyDecimalPrefix::Category(parse_two_digits(prefix)?)),
+        }
+    }
+
+    /// The prefix one level up in the hierarchy.
+    pub fn parent(&self) -> Option<JohnnyDecimalPrefix> {
+        match self {
+            JohnnyDecimalPrefix::Area(_) => None,
+            JohnnyDecimalPrefix::Category(category) => {
+                Some(JohnnyDecimalPrefix::Area(category / 10))
+            }
+            JohnnyDecimalPrefix::Id(category, _) => Some(JohnnyDecimalPrefix::Category(*category)),
+        }
+    }
+}
+
+fn parse_two_digits(s: &str) -> Option<u8> {
+    if s.len() == 2 && s.bytes().all(|c| c.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Builds the edges of a Johnny.Decimal hierarchy. Notes with an ID prefix
+/// point to the note of their category via `id_field` and notes with a
+/// category prefix point to the note of their area via `category_field`. An
+/// edge type set on a note overrides these.
+///
+/// Notes without a well formed prefix are ignored. If multiple notes share the
+/// prefix of a parent, edges to all of them are created. Parents are not
+/// added as unresolved nodes, since their full name is unknown.
+#[wasm_bindgen]
+pub fn build_johnny_decimal_edges(
+    notes: Vec<BuilderNote>,
+    delimiter: String,
+    category_field: String,
+    id_field: String,
+) -> Result<BuilderResult> {
+    if delimiter.is_empty() {
+        return Err(NoteGraphError::new(
+            "The Johnny.Decimal note delimiter must not be empty",
+        ));
+    }
+
+    let prefixed_notes: Vec<(JohnnyDecimalPrefix, &BuilderNote)> = notes
+        .iter()
+        .filter_map(|note| {
+            JohnnyDecimalPrefix::parse(basename(&note.path), &delimiter)
+                .map(|prefix| (prefix, note))
+        })
+        .collect();
+
+    let mut notes_by_prefix: HashMap<JohnnyDecimalPrefix, Vec<&str>> = HashMap::new();
+    for (prefix, note) in &prefixed_notes {
+        notes_by_prefix.entry(*prefix).or_default().push(&note.path);
+    }
+
+    let mut result = BuilderResult::default();
+
+    for (prefix, note) in &prefixed_notes {
+        let Some(parents) = prefix
+            .parent()
+            .and_then(|parent| notes_by_prefix.get(&parent))
+        else {
+            continue;
+        };
+
+        let default_field = match prefix {
+            JohnnyDecimalPrefix::Id(..) => &id_field,
+            _ => &category_field,
+        };
+        let Some(field) = note.field_or(default_field) else {
+            continue;
+        };
+
+        for parent in parents {
+            result.edges.push(GCEdgeData::new(
+                note.path.clone(),
+                parent.to_string(),
+                field.to_string(),
+                JOHNNY_DECIMAL_EDGE_SOURCE.to_string(),
+            ));
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,126 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::construction::{GCEdgeData, GCNodeData},
+    update::{batch::BatchGraphUpdate, AddEdgeGraphUpdate, AddNoteGraphUpdate},
+};
+
+pub mod dendron;
+pub mod johnny_decimal;
+
+/// A note that is passed to an edge builder.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct BuilderNote {
+    #[wasm_bindgen(skip)]
+    pub path: String,
+    /// the edge type set in the note's metadata, if this is undefined, the
+    /// default edge type of the builder is used
+    #[wasm_bindgen(skip)]
+    pub field: Option<String>,
+}
+
+#[wasm_bindgen]
+impl BuilderNote {
+    #[wasm_bindgen(constructor)]
+    pub fn new(path: String, field: Option<String>) -> BuilderNote {
+        BuilderNote { path, field }
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+impl BuilderNote {
+    /// The edge type for edges from this note. Returns None if neither the
+    /// note nor the builder set an edge type.
+    pub fn field_or<'a>(&'a self, default_field: &'a str) -> Option<&'a str> {
+        let field = self.field.as_deref().unwrap_or(default_field);
+
+        if field.is_empty() {
+            None
+        } else {
+            Some(field)
+        }
+    }
+}
+
+/// The nodes and edges produced by an edge builder.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct BuilderResult {
+    #[wasm_bindgen(skip)]
+    pub nodes: Vec<GCNodeData>,
+    #[wasm_bindgen(skip)]
+    pub edges: Vec<GCEdgeData>,
+}
+
+#[wasm_bindgen]
+impl BuilderResult {
+    #[wasm_bindgen(js_name = nodes, getter)]
+    pub fn get_nodes(&self) -> Vec<GCNodeData> {
+        self.nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = edges, getter)]
+    pub fn get_edges(&self) -> Vec<GCEdgeData> {
+        self.edges.clone()
+    }
+
+    /// Creates a batch update that adds the nodes and then the edges to a
+    /// graph.
+    pub fn to_batch_update(&self) -> BatchGraphUpdate {
+        let mut batch = BatchGraphUpdate::new();
+
+        for node in &self.nodes {
+            batch.add_update(AddNoteGraphUpdate::new(node.clone()).into());
+        }
+
+        for edge in &self.edges {
+            batch.add_update(AddEdgeGraphUpdate::new(edge.clone()).into());
+        }
+
+        batch
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+/// The extension of markdown notes.
+pub const NOTE_EXTENSION: &str = ".md";
+
+/// The folder of a path, without a trailing slash.
+pub fn dirname(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+/// The file name of a path, without the folder and the markdown extension.
+/// Other extensions are kept, as dots are common in note names.
+pub fn basename(path: &str) -> &str {
+    let file_name = path
+        .rsplit_once('/')
+        .map_or(path, |(_, file_name)| file_name);
+
+    file_name.strip_suffix(NOTE_EXTENSION).unwrap_or(file_name)
+}
+
+/// Builds a markdown note path from a folder and a basename.
+pub fn build_note_path(folder: &str, basename: &str) -> String {
+    let path = [folder, basename]
+        .iter()
+        .flat_map(|part| part.split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if path.ends_with(NOTE_EXTENSION) {
+        path
+    } else {
+        format!("{path}{NOTE_EXTENSION}")
+    }
+}
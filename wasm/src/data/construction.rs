@@ -1,6 +1,15 @@
+use std::rc::Rc;
+
 use wasm_bindgen::prelude::*;
 
-use crate::data::edge::EdgeData;
+use crate::{
+    data::{
+        edge::{EdgeAttributes, EdgeData, EdgeSourceData},
+        node::NodeData,
+        property::{NodeProperties, PropertyValue},
+    },
+    utils::Result,
+};
 
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
@@ -15,6 +24,10 @@ pub struct GCNodeData {
     pub ignore_in_edges: bool,
     #[wasm_bindgen(skip)]
     pub ignore_out_edges: bool,
+    #[wasm_bindgen(skip)]
+    pub tags: Vec<String>,
+    #[wasm_bindgen(skip)]
+    pub properties: NodeProperties,
 }
 
 #[wasm_bindgen]
@@ -33,7 +46,44 @@ impl GCNodeData {
             resolved,
             ignore_in_edges,
             ignore_out_edges,
+            tags: Vec::new(),
+            properties: NodeProperties::new(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = tags, getter)]
+    pub fn get_tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    #[wasm_bindgen(js_name = tags, setter)]
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = tags;
+    }
+
+    /// Sets a property of the note. The value may be a string, number,
+    /// boolean, `Date` or an array of those. Setting a property to `null` or
+    /// `undefined` removes it.
+    ///
+    /// Will return an error if the value has an unsupported type.
+    pub fn set_property(&mut self, key: String, value: JsValue) -> Result<()> {
+        match PropertyValue::from_js(&value)? {
+            Some(value) => {
+                self.properties.insert(key, value);
+            }
+            None => {
+                self.properties.remove(&key);
+            }
         }
+
+        Ok(())
+    }
+
+    /// Returns the value of a property, or `undefined` if it is not set.
+    pub fn get_property(&self, key: &str) -> JsValue {
+        self.properties
+            .get(key)
+            .map_or(JsValue::UNDEFINED, |value| value.to_js())
     }
 
     #[wasm_bindgen(js_name = toString)]
@@ -50,6 +100,22 @@ impl GCNodeData {
             resolved: false,
             ignore_in_edges: false,
             ignore_out_edges: false,
+            tags: Vec::new(),
+            properties: NodeProperties::new(),
+        }
+    }
+}
+
+impl From<NodeData> for GCNodeData {
+    fn from(data: NodeData) -> GCNodeData {
+        GCNodeData {
+            path: data.path,
+            aliases: data.aliases,
+            resolved: data.resolved,
+            ignore_in_edges: data.ignore_in_edges,
+            ignore_out_edges: data.ignore_out_edges,
+            tags: data.tags,
+            properties: data.properties,
         }
     }
 }
@@ -65,6 +131,12 @@ pub struct GCEdgeData {
     pub edge_type: String,
     #[wasm_bindgen(skip)]
     pub edge_source: String,
+    #[wasm_bindgen(skip)]
+    pub order: Option<u32>,
+    #[wasm_bindgen(skip)]
+    pub label: Option<String>,
+    #[wasm_bindgen(skip)]
+    pub attributes: EdgeAttributes,
 }
 
 #[wasm_bindgen]
@@ -81,9 +153,72 @@ impl GCEdgeData {
             target,
             edge_type,
             edge_source,
+            order: None,
+            label: None,
+            attributes: EdgeAttributes::new(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = source, getter)]
+    pub fn get_source(&self) -> String {
+        self.source.clone()
+    }
+
+    #[wasm_bindgen(js_name = target, getter)]
+    pub fn get_target(&self) -> String {
+        self.target.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_type, getter)]
+    pub fn get_edge_type(&self) -> String {
+        self.edge_type.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_source, getter)]
+    pub fn get_edge_source(&self) -> String {
+        self.edge_source.clone()
+    }
+
+    #[wasm_bindgen(js_name = order, getter)]
+    pub fn get_order(&self) -> Option<u32> {
+        self.order
+    }
+
+    /// Sets the position of the edge among its siblings, e.g. the position of
+    /// the list item in a list note.
+    #[wasm_bindgen(js_name = order, setter)]
+    pub fn set_order(&mut self, order: Option<u32>) {
+        self.order = order;
+    }
+
+    #[wasm_bindgen(js_name = label, getter)]
+    pub fn get_label(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    /// Sets a label to display instead of the edge type.
+    #[wasm_bindgen(js_name = label, setter)]
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    /// Sets a free-form attribute of the edge. Setting an attribute to
+    /// `undefined` removes it.
+    pub fn set_attribute(&mut self, key: String, value: Option<String>) {
+        match value {
+            Some(value) => {
+                self.attributes.insert(key, value);
+            }
+            None => {
+                self.attributes.remove(&key);
+            }
         }
     }
 
+    pub fn get_attribute(&self, key: &str) -> Option<String> {
+        self.attributes.get(key).cloned()
+    }
+
     #[wasm_bindgen(js_name = toString)]
     pub fn to_fancy_string(&self) -> String {
         format!("{self:#?}")
@@ -91,7 +226,47 @@ impl GCEdgeData {
 }
 
 impl GCEdgeData {
+    /// Creates the construction data of an explicit edge of the graph for
+    /// one of its edge sources.
+    pub fn from_edge_source_data(
+        source: String,
+        target: String,
+        edge_type: &str,
+        data: &EdgeSourceData,
+    ) -> GCEdgeData {
+        GCEdgeData {
+            source,
+            target,
+            edge_type: edge_type.to_string(),
+            edge_source: data.edge_source.to_string(),
+            order: data.order,
+            label: data.label.clone(),
+            attributes: data.attributes.clone(),
+        }
+    }
+
     pub fn to_edge_data(self) -> EdgeData {
-        EdgeData::new(self.edge_type.into(), self.edge_source.into(), true, 0)
+        let edge_type = Rc::from(self.edge_type.as_str());
+        EdgeData::from_edge_source(edge_type, self.to_edge_source_data())
+    }
+
+    /// The data the edge source of this edge gives the edge.
+    pub fn to_edge_source_data(self) -> EdgeSourceData {
+        EdgeSourceData {
+            edge_source: Rc::from(self.edge_source),
+            count: 1,
+            order: self.order,
+            label: self.label,
+            attributes: self.attributes,
+        }
+    }
+
+    /// Whether two edges have the same edge source and attributes, ignoring
+    /// the nodes and edge type.
+    pub fn has_same_data(&self, other: &GCEdgeData) -> bool {
+        self.edge_source == other.edge_source
+            && self.order == other.order
+            && self.label == other.label
+            && self.attributes == other.attributes
     }
 }
@@ -1,9 +1,12 @@
-use std::rc::Rc;
+use std::{collections::BTreeMap, rc::Rc};
 
 use itertools::Itertools;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::graph::{edge_matches_edge_filter, edge_matches_edge_filter_string};
+use crate::{
+    data::NGNodeIndex,
+    graph::{edge_matches_edge_filter, edge_matches_edge_filter_string},
+};
 
 #[wasm_bindgen]
 #[derive(Clone, Debug, PartialEq)]
@@ -13,9 +16,59 @@ pub struct EdgeData {
     #[wasm_bindgen(skip)]
     pub edge_source: Rc<str>,
     pub explicit: bool,
-    pub round: u8,
+    pub round: u16,
+    /// the position of the edge among its siblings, e.g. the position of the
+    /// list item in a list note
+    pub order: Option<u32>,
+    /// a label to display instead of the edge type
+    #[wasm_bindgen(getter_with_clone)]
+    pub label: Option<String>,
+    /// free-form metadata of the edge
+    #[wasm_bindgen(skip)]
+    pub attributes: EdgeAttributes,
+    /// the edge sources asserting an explicit edge, in the order they were
+    /// added. `edge_source`, `order`, `label` and `attributes` are those of
+    /// the first of them.
+    #[wasm_bindgen(skip)]
+    pub sources: Vec<EdgeSourceData>,
+    /// the edges an implied edge was derived from, in the order of the path
+    /// of the rule that created it. Empty for explicit edges.
+    #[wasm_bindgen(skip)]
+    pub derived_from: Vec<EdgeStep>,
+}
+
+/// Free-form metadata of an edge, by key.
+pub type EdgeAttributes = BTreeMap<String, String>;
+
+/// An edge source asserting an explicit edge, with the data it gave the edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeSourceData {
+    pub edge_source: Rc<str>,
+    /// the number of times the edge source asserts the edge, e.g. for two
+    /// links in the same note. The data is that of the first of them.
+    pub count: u32,
+    pub order: Option<u32>,
+    pub label: Option<String>,
+    pub attributes: EdgeAttributes,
+}
+
+impl EdgeSourceData {
+    /// Creates the data of an edge source that asserts an edge once, without
+    /// order, label or attributes.
+    pub fn new(edge_source: Rc<str>) -> EdgeSourceData {
+        EdgeSourceData {
+            edge_source,
+            count: 1,
+            order: None,
+            label: None,
+            attributes: EdgeAttributes::new(),
+        }
+    }
 }
 
+/// An edge identified by its source node, target node and edge type.
+pub type EdgeStep = (NGNodeIndex, NGNodeIndex, Rc<str>);
+
 #[wasm_bindgen]
 impl EdgeData {
     #[wasm_bindgen(js_name = toString)]
@@ -32,18 +85,115 @@ impl EdgeData {
     pub fn get_edge_source(&self) -> String {
         self.edge_source.to_string()
     }
+
+    /// All edge sources asserting the edge. For implied edges this is the
+    /// name of the rule that created the edge.
+    #[wasm_bindgen(js_name = edge_sources, getter)]
+    pub fn get_edge_sources(&self) -> Vec<String> {
+        if self.sources.is_empty() {
+            vec![self.edge_source.to_string()]
+        } else {
+            self.sources
+                .iter()
+                .map(|source| source.edge_source.to_string())
+                .collect()
+        }
+    }
+
+    pub fn get_attribute(&self, key: &str) -> Option<String> {
+        self.attributes.get(key).cloned()
+    }
+
+    #[wasm_bindgen(js_name = attribute_keys, getter)]
+    pub fn get_attribute_keys(&self) -> Vec<String> {
+        self.attributes.keys().cloned().collect()
+    }
 }
 
 impl EdgeData {
-    pub fn new(edge_type: Rc<str>, edge_source: Rc<str>, explicit: bool, round: u8) -> EdgeData {
+    pub fn new(edge_type: Rc<str>, edge_source: Rc<str>, explicit: bool, round: u16) -> EdgeData {
         EdgeData {
             edge_type,
-            edge_source,
+            edge_source: Rc::clone(&edge_source),
             explicit,
             round,
+            order: None,
+            label: None,
+            attributes: EdgeAttributes::new(),
+            sources: if explicit {
+                vec![EdgeSourceData::new(edge_source)]
+            } else {
+                Vec::new()
+            },
+            derived_from: Vec::new(),
+        }
+    }
+
+    /// Creates an explicit edge asserted by a single edge source.
+    pub fn from_edge_source(edge_type: Rc<str>, source_data: EdgeSourceData) -> EdgeData {
+        let mut edge_data = EdgeData::new(edge_type, Rc::clone(&source_data.edge_source), true, 0);
+        edge_data.sources = vec![source_data];
+        edge_data.sync_with_first_source();
+
+        edge_data
+    }
+
+    pub fn has_edge_source(&self, edge_source: &str) -> bool {
+        self.sources
+            .iter()
+            .any(|source| source.edge_source.as_ref() == edge_source)
+    }
+
+    /// Records that another edge source asserts the edge, or that an edge
+    /// source asserts it once more. In the latter case, the data of the edge
+    /// source is kept.
+    pub fn add_edge_source(&mut self, source_data: EdgeSourceData) {
+        match self
+            .sources
+            .iter_mut()
+            .find(|source| source.edge_source == source_data.edge_source)
+        {
+            Some(source) => source.count += 1,
+            None => self.sources.push(source_data),
         }
     }
 
+    /// Records that an edge source asserts the edge once less.
+    /// Returns false if no edge source asserts the edge anymore, in which case
+    /// the edge should be removed.
+    pub fn remove_edge_source(&mut self, edge_source: &str) -> bool {
+        if let Some(position) = self
+            .sources
+            .iter()
+            .position(|source| source.edge_source.as_ref() == edge_source)
+        {
+            self.sources[position].count -= 1;
+            if self.sources[position].count == 0 {
+                self.sources.remove(position);
+            }
+        }
+
+        if self.sources.is_empty() {
+            return false;
+        }
+
+        self.sync_with_first_source();
+        true
+    }
+
+    /// Takes the edge source, order, label and attributes of the edge from
+    /// its first edge source.
+    fn sync_with_first_source(&mut self) {
+        let Some(first) = self.sources.first() else {
+            return;
+        };
+
+        self.edge_source = Rc::clone(&first.edge_source);
+        self.order = first.order;
+        self.label.clone_from(&first.label);
+        self.attributes.clone_from(&first.attributes);
+    }
+
     pub fn matches_edge_filter_string(&self, edge_types: Option<&Vec<String>>) -> bool {
         edge_matches_edge_filter_string(self, edge_types)
     }
@@ -52,11 +202,15 @@ impl EdgeData {
         edge_matches_edge_filter(self, edge_types)
     }
 
+    /// Renders the given attributes of the edge. Besides the built-in
+    /// attributes, the name of any custom attribute can be given.
+    /// Attributes the edge doesn't have are skipped.
     pub fn attribute_label(&self, attributes: &Vec<String>) -> String {
         let mut result = vec![];
 
         // the mapping that exist on the JS side are as follows
-        // "field" | "explicit" | "source" | "implied_kind" | "round"
+        // "field" | "explicit" | "source" | "implied_kind" | "round" | "label" |
+        // "order"
 
         // TODO(JS): maybe change the attribute options so that the JS side better
         // matches the data
@@ -79,7 +233,12 @@ impl EdgeData {
                     }
                 }
                 "round" => Some(("round", self.round.to_string())),
-                _ => None,
+                "label" => self.label.clone().map(|label| ("label", label)),
+                "order" => self.order.map(|order| ("order", order.to_string())),
+                custom => self
+                    .attributes
+                    .get(custom)
+                    .map(|value| (custom, value.clone())),
             };
 
             if let Some(data) = data {
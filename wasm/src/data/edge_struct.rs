@@ -81,14 +81,27 @@ impl EdgeStruct {
         Ok(self.edge_data_ref(graph)?.get_edge_source())
     }
 
+    /// All edge sources asserting the edge.
+    pub fn edge_sources(&self, graph: &NoteGraph) -> utils::Result<Vec<String>> {
+        Ok(self.edge_data_ref(graph)?.get_edge_sources())
+    }
+
     pub fn explicit(&self, graph: &NoteGraph) -> utils::Result<bool> {
         Ok(self.edge_data_ref(graph)?.explicit)
     }
 
-    pub fn round(&self, graph: &NoteGraph) -> utils::Result<u8> {
+    pub fn round(&self, graph: &NoteGraph) -> utils::Result<u16> {
         Ok(self.edge_data_ref(graph)?.round)
     }
 
+    pub fn order(&self, graph: &NoteGraph) -> utils::Result<Option<u32>> {
+        Ok(self.edge_data_ref(graph)?.order)
+    }
+
+    pub fn label(&self, graph: &NoteGraph) -> utils::Result<Option<String>> {
+        Ok(self.edge_data_ref(graph)?.label.clone())
+    }
+
     pub fn get_attribute_label(
         &self,
         graph: &NoteGraph,
@@ -13,6 +13,8 @@ pub mod edge;
 pub mod edge_list;
 pub mod edge_struct;
 pub mod node;
+pub mod property;
+pub mod rule_syntax;
 pub mod rules;
 pub mod traversal;
 
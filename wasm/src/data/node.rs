@@ -1,6 +1,6 @@
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::prelude::*;
 
-use crate::data::construction::GCNodeData;
+use crate::data::{construction::GCNodeData, property::NodeProperties};
 
 #[wasm_bindgen]
 #[derive(Clone, Debug, PartialEq)]
@@ -10,8 +10,18 @@ pub struct NodeData {
     #[wasm_bindgen(getter_with_clone)]
     pub aliases: Vec<String>,
     pub resolved: bool,
+    /// if true, edges pointing to this node are not traversed and no implied
+    /// edges are built through or onto this node
     pub ignore_in_edges: bool,
+    /// if true, edges from this node are not traversed and no implied edges
+    /// are built through or from this node
     pub ignore_out_edges: bool,
+    /// the tags of the note, with or without the leading `#`
+    #[wasm_bindgen(getter_with_clone)]
+    pub tags: Vec<String>,
+    /// typed metadata properties of the note, e.g. from the frontmatter
+    #[wasm_bindgen(skip)]
+    pub properties: NodeProperties,
 }
 
 #[wasm_bindgen]
@@ -30,9 +40,24 @@ impl NodeData {
             resolved,
             ignore_in_edges,
             ignore_out_edges,
+            tags: Vec::new(),
+            properties: NodeProperties::new(),
         }
     }
 
+    /// Returns the value of a property, or `undefined` if the note does not
+    /// have the property.
+    pub fn get_property(&self, key: &str) -> JsValue {
+        self.properties
+            .get(key)
+            .map_or(JsValue::UNDEFINED, |value| value.to_js())
+    }
+
+    #[wasm_bindgen(js_name = property_keys, getter)]
+    pub fn get_property_keys(&self) -> Vec<String> {
+        self.properties.keys().cloned().collect()
+    }
+
     #[wasm_bindgen(js_name = toString)]
     pub fn to_fancy_string(&self) -> String {
         format!("{self:#?}")
@@ -47,6 +72,8 @@ impl NodeData {
             resolved: false,
             ignore_in_edges: false,
             ignore_out_edges: false,
+            tags: Vec::new(),
+            properties: NodeProperties::new(),
         }
     }
 
@@ -59,6 +86,35 @@ impl NodeData {
         self.resolved = data.resolved;
         self.ignore_in_edges = data.ignore_in_edges;
         self.ignore_out_edges = data.ignore_out_edges;
+        self.tags = data.tags;
+        self.properties = data.properties;
+    }
+
+    /// Turns the node into an unresolved node, removing all data that comes
+    /// from the note itself, so that only the path is left.
+    pub fn clear_note_data(&mut self) {
+        self.aliases.clear();
+        self.resolved = false;
+        self.ignore_in_edges = false;
+        self.ignore_out_edges = false;
+        self.tags.clear();
+        self.properties.clear();
+    }
+
+    /// Whether the note has a tag. If `exact` is false, nested tags also
+    /// count, so `#foo/bar` counts as `#foo`.
+    pub fn has_tag(&self, tag: &str, exact: bool) -> bool {
+        let tag = tag.trim_start_matches('#');
+
+        self.tags.iter().any(|node_tag| {
+            let node_tag = node_tag.trim_start_matches('#');
+
+            node_tag == tag
+                || (!exact
+                    && node_tag
+                        .strip_prefix(tag)
+                        .is_some_and(|rest| rest.starts_with('/')))
+        })
     }
 }
 
@@ -70,6 +126,8 @@ impl From<GCNodeData> for NodeData {
             resolved: data.resolved,
             ignore_in_edges: data.ignore_in_edges,
             ignore_out_edges: data.ignore_out_edges,
+            tags: data.tags,
+            properties: data.properties,
         }
     }
 }
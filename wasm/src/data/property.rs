@@ -0,0 +1,114 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use js_sys::{Array, Date};
+use wasm_bindgen::prelude::*;
+
+use crate::utils::{NoteGraphError, Result};
+
+/// The metadata properties of a note, e.g. from the frontmatter, by key.
+pub type NodeProperties = BTreeMap<String, PropertyValue>;
+
+/// A typed metadata property value of a note.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    String(String),
+    Number(f64),
+    /// milliseconds since the unix epoch, like JS dates
+    Date(f64),
+    Bool(bool),
+    List(Vec<PropertyValue>),
+}
+
+impl PropertyValue {
+    /// Converts a JS value into a property value. Strings, numbers, booleans,
+    /// `Date` objects and arrays of those are supported. `null` and
+    /// `undefined` are converted to `None`.
+    ///
+    /// Will return an error if the value has an unsupported type.
+    pub fn from_js(value: &JsValue) -> Result<Option<PropertyValue>> {
+        if value.is_null() || value.is_undefined() {
+            return Ok(None);
+        }
+
+        if let Some(string) = value.as_string() {
+            Ok(Some(PropertyValue::String(string)))
+        } else if let Some(number) = value.as_f64() {
+            Ok(Some(PropertyValue::Number(number)))
+        } else if let Some(boolean) = value.as_bool() {
+            Ok(Some(PropertyValue::Bool(boolean)))
+        } else if let Some(date) = value.dyn_ref::<Date>() {
+            Ok(Some(PropertyValue::Date(date.get_time())))
+        } else if let Some(array) = value.dyn_ref::<Array>() {
+            let mut list = Vec::new();
+            for item in array.iter() {
+                match PropertyValue::from_js(&item)? {
+                    Some(item) => list.push(item),
+                    None => {
+                        return Err(NoteGraphError::new(
+                            "invalid property value, lists may not contain null or undefined",
+                        ))
+                    }
+                }
+            }
+            Ok(Some(PropertyValue::List(list)))
+        } else {
+            Err(NoteGraphError::new(&format!(
+                "invalid property value, expected a string, number, boolean, date or list, found {value:?}"
+            )))
+        }
+    }
+
+    pub fn to_js(&self) -> JsValue {
+        match self {
+            PropertyValue::String(string) => JsValue::from_str(string),
+            PropertyValue::Number(number) => JsValue::from_f64(*number),
+            PropertyValue::Date(time) => Date::new(&JsValue::from_f64(*time)).into(),
+            PropertyValue::Bool(boolean) => JsValue::from_bool(*boolean),
+            PropertyValue::List(list) => list
+                .iter()
+                .map(PropertyValue::to_js)
+                .collect::<Array>()
+                .into(),
+        }
+    }
+
+    /// A total order over property values. Values of the same type are
+    /// compared by value, lists element-wise. Values of different types are
+    /// ordered bool < number < date < string < list.
+    pub fn compare(&self, other: &PropertyValue) -> Ordering {
+        match (self, other) {
+            (PropertyValue::String(a), PropertyValue::String(b)) => a.cmp(b),
+            (PropertyValue::Number(a), PropertyValue::Number(b)) => a.total_cmp(b),
+            (PropertyValue::Date(a), PropertyValue::Date(b)) => a.total_cmp(b),
+            (PropertyValue::Bool(a), PropertyValue::Bool(b)) => a.cmp(b),
+            (PropertyValue::List(a), PropertyValue::List(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.compare(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+
+    /// Whether the property matches a value, either because it is equal to
+    /// the value, or because it is a list containing the value.
+    pub fn contains(&self, value: &PropertyValue) -> bool {
+        match self {
+            PropertyValue::List(list) if !matches!(value, PropertyValue::List(_)) => {
+                list.iter().any(|item| item.contains(value))
+            }
+            _ => self == value,
+        }
+    }
+
+    fn type_rank(&self) -> u8 {
+        match self {
+            PropertyValue::Bool(_) => 0,
+            PropertyValue::Number(_) => 1,
+            PropertyValue::Date(_) => 2,
+            PropertyValue::String(_) => 3,
+            PropertyValue::List(_) => 4,
+        }
+    }
+}
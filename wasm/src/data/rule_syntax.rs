@@ -0,0 +1,357 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::rules::{RuleStep, TransitiveGraphRule},
+    utils::{NoteGraphError, Result},
+};
+
+/// The number of rounds of a rule if the `rounds` option is not given.
+pub const DEFAULT_RULE_ROUNDS: u8 = 1;
+
+/// A textual syntax for transitive rules, e.g.
+/// `[up, same] -> up (rounds=5, loop)` or `[down] <- up`.
+///
+/// The syntax is
+/// - `[a, b]`: the path of the rule by edge type
+/// - `[a, ~b]`: `~` follows `b` edges backwards, from their target to their
+///   source
+/// - `[a]+`: a closure rule, the path may be repeated one or more times
+/// - `-> c`: add a `c` edge from the start to the end of the path
+/// - `<- c`: add a `c` edge from the end to the start of the path
+/// - `(...)`: optional, comma separated options
+///   - `rounds=5`: the number of rounds, 1 if not given, not allowed for
+///     closure rules
+///   - `loop`: the rule may add edges from a node to itself
+///   - `name="..."`: the name of the rule, if not given the rule is named after
+///     its path and edge type
+///
+/// Edge types and names can be quoted to allow any characters, with `\"`
+/// and `\\` as escapes.
+#[wasm_bindgen]
+impl TransitiveGraphRule {
+    /// Parses a rule from the textual syntax.
+    ///
+    /// Will return an error with the column of the problem if the rule is
+    /// malformed.
+    pub fn parse(text: String) -> Result<TransitiveGraphRule> {
+        RuleParser::new(&text, None).parse_rule()
+    }
+
+    /// Prints the rule in the textual syntax. Options with default values are
+    /// left out, so that parsing the text results in the same rule.
+    pub fn stringify(&self) -> String {
+        let mut text = format!(
+            "[{}]{} {} {}",
+            self.iter_steps()
+                .map(|step| format!(
+                    "{}{}",
+                    if step.reversed { "~" } else { "" },
+                    quote_if_needed(&step.edge_type)
+                ))
+                .collect::<Vec<_>>()
+                .join(", "),
+            if self.closure() { "+" } else { "" },
+            if self.close_reversed() { "<-" } else { "->" },
+            quote_if_needed(self.edge_type_ref())
+        );
+
+        let mut options = Vec::new();
+        if self.rounds() != DEFAULT_RULE_ROUNDS && !self.closure() {
+            options.push(format!("rounds={}", self.rounds()));
+        }
+        if self.can_loop() {
+            options.push("loop".to_string());
+        }
+        if self.name_ref() != self.default_name() {
+            options.push(format!("name={}", quote(self.name_ref())));
+        }
+
+        if !options.is_empty() {
+            text.push_str(&format!(" ({})", options.join(", ")));
+        }
+
+        text
+    }
+}
+
+/// Parses a set of rules, one rule per line. Empty lines and lines starting
+/// with `#` are skipped.
+///
+/// Will return an error with the line and column of the problem if a rule is
+/// malformed.
+#[wasm_bindgen]
+pub fn parse_transitive_rules(text: String) -> Result<Vec<TransitiveGraphRule>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(index, line)| RuleParser::new(line, Some(index + 1)).parse_rule())
+        .collect()
+}
+
+/// Prints a set of rules, one rule per line.
+#[wasm_bindgen]
+pub fn stringify_transitive_rules(rules: Vec<TransitiveGraphRule>) -> String {
+    rules
+        .iter()
+        .map(TransitiveGraphRule::stringify)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_bare_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '[' | ']' | '(' | ')' | ',' | '=' | '"' | '\\')
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quotes text that can't be written bare. Text starting with `~` is quoted
+/// as well, so that an edge type isn't read back as a reversed step.
+fn quote_if_needed(text: &str) -> String {
+    if text.is_empty() || text.starts_with('~') || !text.chars().all(is_bare_char) {
+        quote(text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// A recursive descent parser for a single rule.
+struct RuleParser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    /// the line of the rule in a set of rules, used for error locations
+    line: Option<usize>,
+}
+
+impl<'a> RuleParser<'a> {
+    fn new(text: &'a str, line: Option<usize>) -> RuleParser<'a> {
+        RuleParser {
+            text,
+            chars: text.char_indices().peekable(),
+            line,
+        }
+    }
+
+    fn parse_rule(&mut self) -> Result<TransitiveGraphRule> {
+        self.expect('[')?;
+        let mut path = Vec::new();
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            loop {
+                self.skip_whitespace();
+                let reversed = self.chars.next_if(|(_, c)| *c == '~').is_some();
+                let edge_type = self.parse_string("an edge type")?;
+                path.push(RuleStep::new(edge_type, reversed));
+                self.skip_whitespace();
+                if self.chars.next_if(|(_, c)| *c == ',').is_none() {
+                    break;
+                }
+            }
+        }
+        self.expect(']')?;
+        let closure = self.chars.next_if(|(_, c)| *c == '+').is_some();
+
+        self.skip_whitespace();
+        let close_reversed = match self.chars.next_if(|(_, c)| matches!(c, '-' | '<')) {
+            Some((_, '-')) => {
+                self.expect_directly('>', "\"->\" or \"<-\"")?;
+                false
+            }
+            Some(_) => {
+                self.expect_directly('-', "\"->\" or \"<-\"")?;
+                true
+            }
+            None => return Err(self.error("\"->\" or \"<-\"")),
+        };
+
+        let edge_type = self.parse_string("an edge type")?;
+
+        let mut rounds = None;
+        let mut can_loop = false;
+        let mut name = None;
+
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == '(').is_some() {
+            loop {
+                self.skip_whitespace();
+                let position = self.position();
+                let option = self.parse_bare("an option")?;
+
+                match option.as_str() {
+                    "rounds" if closure => {
+                        return Err(self.error_at(
+                            position,
+                            "closure rules don't have rounds, they find paths of any length",
+                        ));
+                    }
+                    "rounds" if rounds.is_none() => {
+                        self.expect('=')?;
+                        rounds = Some(self.parse_rounds()?);
+                    }
+                    "loop" if !can_loop => {
+                        can_loop = true;
+                    }
+                    "name" if name.is_none() => {
+                        self.expect('=')?;
+                        name = Some(self.parse_string("a name")?);
+                    }
+                    "rounds" | "loop" | "name" => {
+                        return Err(
+                            self.error_at(position, &format!("duplicate option \"{option}\""))
+                        );
+                    }
+                    _ => {
+                        return Err(self.error_at(
+                            position,
+                            &format!(
+                                "unknown option \"{option}\", expected \"rounds\", \"loop\" or \"name\""
+                            ),
+                        ));
+                    }
+                }
+
+                self.skip_whitespace();
+                if self.chars.next_if(|(_, c)| *c == ',').is_none() {
+                    break;
+                }
+            }
+            self.expect(')')?;
+        }
+
+        self.skip_whitespace();
+        if self.chars.peek().is_some() {
+            return Err(self.error("the end of the rule"));
+        }
+
+        let name = name.unwrap_or_default();
+        if closure {
+            Ok(TransitiveGraphRule::new_closure(
+                name,
+                path,
+                edge_type,
+                can_loop,
+                close_reversed,
+            ))
+        } else {
+            Ok(TransitiveGraphRule::from_steps(
+                name,
+                path,
+                edge_type,
+                rounds.unwrap_or(DEFAULT_RULE_ROUNDS),
+                can_loop,
+                close_reversed,
+            ))
+        }
+    }
+
+    fn parse_rounds(&mut self) -> Result<u8> {
+        self.skip_whitespace();
+        let position = self.position();
+        let mut digits = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+            digits.push(c);
+        }
+
+        if digits.is_empty() {
+            return Err(self.error("a number of rounds"));
+        }
+
+        digits.parse().map_err(|_| {
+            self.error_at(
+                position,
+                &format!("invalid number of rounds \"{digits}\", expected at most 255"),
+            )
+        })
+    }
+
+    /// Parses a bare or quoted string.
+    fn parse_string(&mut self, expected: &str) -> Result<String> {
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == '"').is_none() {
+            return self.parse_bare(expected);
+        }
+
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(string),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c @ ('"' | '\\'))) => string.push(c),
+                    Some((position, _)) => {
+                        return Err(self.error_at(position - 1, "invalid escape sequence"))
+                    }
+                    None => return Err(self.error("a closing quote")),
+                },
+                Some((_, c)) => string.push(c),
+                None => return Err(self.error("a closing quote")),
+            }
+        }
+    }
+
+    fn parse_bare(&mut self, expected: &str) -> Result<String> {
+        let mut string = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| is_bare_char(*c)) {
+            string.push(c);
+        }
+
+        if string.is_empty() {
+            Err(self.error(expected))
+        } else {
+            Ok(string)
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        self.expect_directly(expected, &format!("\"{expected}\""))
+    }
+
+    fn expect_directly(&mut self, expected: char, description: &str) -> Result<()> {
+        match self.chars.next_if(|(_, c)| *c == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error(description)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.text.len(), |(position, _)| *position)
+    }
+
+    /// An error for an unexpected character or the unexpected end of the
+    /// rule at the current position.
+    fn error(&mut self, expected: &str) -> NoteGraphError {
+        let found = match self.peek() {
+            Some(c) => format!("found \"{c}\""),
+            None => "found the end of the rule".to_string(),
+        };
+        let position = self.position();
+
+        self.error_at(position, &format!("expected {expected}, {found}"))
+    }
+
+    fn error_at(&self, position: usize, message: &str) -> NoteGraphError {
+        let column = self.text[..position].chars().count() + 1;
+        let location = match self.line {
+            Some(line) => format!("line {line}, column {column}"),
+            None => format!("column {column}"),
+        };
+
+        NoteGraphError::new(&format!("failed to parse rule at {location}, {message}"))
+    }
+}
@@ -1,28 +1,93 @@
-use std::rc::Rc;
+use std::{fmt::Display, rc::Rc};
 
+use itertools::Itertools;
 use wasm_bindgen::prelude::*;
 
 use crate::{
     data::construction::{GCEdgeData, GCNodeData},
     graph::NoteGraph,
-    utils::Result,
+    traversal::{edge_pattern::EdgeTypePattern, options::TraversalDirection},
+    utils::{NoteGraphError, Result},
 };
 
+/// A step of the path of a transitive rule. A reversed step, written as
+/// `~up` in the rule syntax, follows an `up` edge backwards, from its target
+/// to its source.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleStep {
+    #[wasm_bindgen(skip)]
+    pub edge_type: Rc<str>,
+    #[wasm_bindgen(skip)]
+    pub reversed: bool,
+}
+
+#[wasm_bindgen]
+impl RuleStep {
+    #[wasm_bindgen(constructor)]
+    pub fn new(edge_type: String, reversed: bool) -> RuleStep {
+        RuleStep {
+            edge_type: Rc::from(edge_type),
+            reversed,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn edge_type(&self) -> String {
+        self.edge_type.to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reversed(&self) -> bool {
+        self.reversed
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+impl RuleStep {
+    /// The direction in which the step follows edges.
+    pub fn direction(&self) -> TraversalDirection {
+        if self.reversed {
+            TraversalDirection::Incoming
+        } else {
+            TraversalDirection::Outgoing
+        }
+    }
+}
+
+impl Display for RuleStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.reversed {
+            write!(f, "~")?;
+        }
+        write!(f, "{}", self.edge_type)
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
 pub struct TransitiveGraphRule {
     name: Rc<str>,
-    // the path by edge type
-    path: Vec<Rc<str>>,
+    // the path by edge type and direction
+    path: Vec<RuleStep>,
     // the edge type to add
     edge_type: Rc<str>,
     rounds: u8,
     can_loop: bool,
     close_reversed: bool,
+    // if true, the path may be repeated one or more times, independent of the
+    // rounds
+    closure: bool,
 }
 
 #[wasm_bindgen]
 impl TransitiveGraphRule {
+    /// Creates a rule whose path only follows edges forwards. The path is a
+    /// list of edge types, which are taken as they are.
     #[wasm_bindgen(constructor)]
     pub fn new(
         name: String,
@@ -32,24 +97,51 @@ impl TransitiveGraphRule {
         can_loop: bool,
         close_reversed: bool,
     ) -> TransitiveGraphRule {
-        let mut name = name.trim().to_string();
-        if name.is_empty() {
-            name = format!(
-                "[{}] {} {}",
-                path.join(", "),
-                if close_reversed { "<-" } else { "->" },
-                edge_type
-            );
-        }
+        TransitiveGraphRule::from_steps(
+            name,
+            path.into_iter()
+                .map(|edge_type| RuleStep::new(edge_type, false))
+                .collect(),
+            edge_type,
+            rounds,
+            can_loop,
+            close_reversed,
+        )
+    }
 
-        TransitiveGraphRule {
-            name: Rc::from(name),
-            path: path.into_iter().map(Rc::from).collect(),
-            edge_type: Rc::from(edge_type),
+    /// Creates a rule from a path of steps, which may follow edges backwards,
+    /// e.g. `[up, ~up] -> same`.
+    pub fn from_steps(
+        name: String,
+        path: Vec<RuleStep>,
+        edge_type: String,
+        rounds: u8,
+        can_loop: bool,
+        close_reversed: bool,
+    ) -> TransitiveGraphRule {
+        TransitiveGraphRule::int_new(
+            name,
+            path,
+            edge_type,
             rounds,
             can_loop,
             close_reversed,
-        }
+            false,
+        )
+    }
+
+    /// Creates a closure rule, which adds an edge for every path that repeats
+    /// the rule path one or more times, e.g. `[up]+ -> ancestor`. Unlike
+    /// normal rules, closure rules find paths of any length, independent of
+    /// the number of rounds, so their number of rounds is 0.
+    pub fn new_closure(
+        name: String,
+        path: Vec<RuleStep>,
+        edge_type: String,
+        can_loop: bool,
+        close_reversed: bool,
+    ) -> TransitiveGraphRule {
+        TransitiveGraphRule::int_new(name, path, edge_type, 0, can_loop, close_reversed, true)
     }
 
     pub fn create_example_graph(&self) -> Result<NoteGraph> {
@@ -58,8 +150,12 @@ impl TransitiveGraphRule {
         let mut node_data = vec![];
         let mut edge_data = vec![];
 
+        // closure rules are shown with the path repeated twice, so that the edge
+        // spanning both repetitions shows up as well
+        let repetitions = if self.closure { 2 } else { 1 };
+
         let mut counter = 1;
-        for element in self.path.iter() {
+        for step in self.path.iter().cycle().take(self.path.len() * repetitions) {
             node_data.push(GCNodeData::new(
                 counter.to_string(),
                 vec![],
@@ -67,10 +163,17 @@ impl TransitiveGraphRule {
                 false,
                 false,
             ));
+
+            // reversed steps follow an edge from the next node back to this one
+            let (source, target) = if step.reversed {
+                (counter + 1, counter)
+            } else {
+                (counter, counter + 1)
+            };
             edge_data.push(GCEdgeData::new(
-                counter.to_string(),
-                (counter + 1).to_string(),
-                element.to_string(),
+                source.to_string(),
+                target.to_string(),
+                step.edge_type.to_string(),
                 "explicit".to_string(),
             ));
 
@@ -97,6 +200,44 @@ impl TransitiveGraphRule {
 }
 
 impl TransitiveGraphRule {
+    fn int_new(
+        name: String,
+        path: Vec<RuleStep>,
+        edge_type: String,
+        rounds: u8,
+        can_loop: bool,
+        close_reversed: bool,
+        closure: bool,
+    ) -> TransitiveGraphRule {
+        let mut rule = TransitiveGraphRule {
+            name: Rc::from(name.trim()),
+            path,
+            edge_type: Rc::from(edge_type),
+            rounds,
+            can_loop,
+            close_reversed,
+            closure,
+        };
+
+        if rule.name.is_empty() {
+            rule.name = Rc::from(rule.default_name());
+        }
+
+        rule
+    }
+
+    /// The name of a rule that wasn't given a name, e.g. `[up, same] -> up`
+    /// or `[up]+ -> ancestor`.
+    pub fn default_name(&self) -> String {
+        format!(
+            "[{}]{} {} {}",
+            self.path.iter().map(|step| step.to_string()).join(", "),
+            if self.closure { "+" } else { "" },
+            if self.close_reversed { "<-" } else { "->" },
+            self.edge_type
+        )
+    }
+
     pub fn name(&self) -> Rc<str> {
         Rc::clone(&self.name)
     }
@@ -113,10 +254,30 @@ impl TransitiveGraphRule {
         &self.edge_type
     }
 
+    /// The edge types of the path, regardless of the direction of the steps.
     pub fn iter_path(&self) -> impl Iterator<Item = &Rc<str>> {
+        self.path.iter().map(|step| &step.edge_type)
+    }
+
+    pub fn iter_steps(&self) -> impl Iterator<Item = &RuleStep> {
         self.path.iter()
     }
 
+    /// A pattern that matches exactly the path of this rule. It can be used
+    /// to run a traversal along the edges the rule would follow.
+    ///
+    /// Will return an error if the path has reversed steps, since edge type
+    /// patterns only follow edges forwards.
+    pub fn path_pattern(&self) -> Result<EdgeTypePattern> {
+        if self.path.iter().any(|step| step.reversed) {
+            return Err(NoteGraphError::new(
+                "failed to create path pattern, the rule path has reversed steps",
+            ));
+        }
+
+        Ok(EdgeTypePattern::int_from_sequence(self.iter_path()))
+    }
+
     pub fn rounds(&self) -> u8 {
         self.rounds
     }
@@ -128,4 +289,8 @@ impl TransitiveGraphRule {
     pub fn close_reversed(&self) -> bool {
         self.close_reversed
     }
+
+    pub fn closure(&self) -> bool {
+        self.closure
+    }
 }
@@ -26,6 +26,9 @@ pub struct TraversalData {
     /// whether the node has a cut of children due to being at the depth limit
     /// of a traversal, or similar
     pub has_cut_of_children: bool,
+    /// whether the node already appears on the path from the entry node to
+    /// this node, so the traversal did not descend into it again
+    pub is_cycle_back_reference: bool,
 }
 
 #[wasm_bindgen]
@@ -44,6 +47,7 @@ impl TraversalData {
             number_of_children,
             children,
             has_cut_of_children,
+            is_cycle_back_reference: false,
         }
     }
 
@@ -62,6 +66,19 @@ impl TraversalData {
 }
 
 impl TraversalData {
+    /// Creates the traversal data for a node that already appears on the path
+    /// from the entry node to this node.
+    pub fn new_cycle_back_reference(edge: EdgeStruct, depth: u32) -> TraversalData {
+        TraversalData {
+            edge,
+            depth,
+            number_of_children: 0,
+            children: Vec::new(),
+            has_cut_of_children: false,
+            is_cycle_back_reference: true,
+        }
+    }
+
     fn to_paths(&self) -> Vec<Path> {
         let mut paths = Vec::new();
 
@@ -91,6 +108,9 @@ pub struct TraversalResult {
     pub node_count: u32,
     pub max_depth: u32,
     pub hit_depth_limit: bool,
+    /// whether the traversal was stopped early because it exceeded the max
+    /// traversal count
+    pub truncated: bool,
     pub traversal_time: u64,
 }
 
@@ -107,6 +127,7 @@ impl TraversalResult {
             node_count,
             max_depth,
             hit_depth_limit,
+            truncated: false,
             traversal_time,
         }
     }
@@ -218,6 +239,7 @@ pub struct FlatTraversalData {
     #[wasm_bindgen(getter_with_clone)]
     pub children: Vec<usize>,
     pub has_cut_of_children: bool,
+    pub is_cycle_back_reference: bool,
 }
 
 impl FlatTraversalData {
@@ -227,6 +249,7 @@ impl FlatTraversalData {
         number_of_children: u32,
         children: Vec<usize>,
         has_cut_of_children: bool,
+        is_cycle_back_reference: bool,
     ) -> FlatTraversalData {
         FlatTraversalData {
             edge,
@@ -234,6 +257,7 @@ impl FlatTraversalData {
             number_of_children,
             children,
             has_cut_of_children,
+            is_cycle_back_reference,
         }
     }
 }
@@ -293,6 +317,11 @@ impl FlatTraversalData {
             &JsValue::from_str("has_cut_of_children"),
             &JsValue::from_bool(self.has_cut_of_children),
         );
+        let _ = js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("is_cycle_back_reference"),
+            &JsValue::from_bool(self.is_cycle_back_reference),
+        );
 
         Ok(obj.into())
     }
@@ -306,6 +335,7 @@ pub struct FlatTraversalResult {
     pub node_count: u32,
     pub max_depth: u32,
     pub hit_depth_limit: bool,
+    pub truncated: bool,
     pub traversal_time: u64,
     #[wasm_bindgen(getter_with_clone)]
     pub entry_nodes: Vec<usize>,
@@ -317,6 +347,7 @@ impl FlatTraversalResult {
         node_count: u32,
         max_depth: u32,
         hit_depth_limit: bool,
+        truncated: bool,
         traversal_time: u64,
         entry_nodes: Vec<usize>,
     ) -> FlatTraversalResult {
@@ -325,6 +356,7 @@ impl FlatTraversalResult {
             node_count,
             max_depth,
             hit_depth_limit,
+            truncated,
             traversal_time,
             entry_nodes,
         }
@@ -343,6 +375,7 @@ impl FlatTraversalResult {
             result.node_count,
             result.max_depth,
             result.hit_depth_limit,
+            result.truncated,
             result.traversal_time,
             entry_nodes,
         )
@@ -416,6 +449,7 @@ fn rec_flatten_traversal_data(
         data.number_of_children,
         children,
         data.has_cut_of_children,
+        data.is_cycle_back_reference,
     ));
     result.len() - 1
 }
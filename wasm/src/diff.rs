@@ -0,0 +1,372 @@
+use hashbrown::HashSet;
+use indexmap::IndexMap;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        node::NodeData,
+        property::PropertyValue,
+    },
+    graph::NoteGraph,
+    update::{
+        batch::BatchGraphUpdate, AddEdgeGraphUpdate, AddNoteGraphUpdate, RemoveEdgeGraphUpdate,
+        RemoveNoteGraphUpdate, SetNoteDataGraphUpdate, SetNotePropertyGraphUpdate,
+    },
+};
+
+/// The edge sources of explicit edges, keyed by source path, target path and
+/// edge type. The edge sources of each edge are in the order they assert it,
+/// together with the number of times they assert it.
+type ExplicitEdgeMap<'a> = IndexMap<(&'a str, &'a str, &'a str), Vec<(GCEdgeData, u32)>>;
+
+/// The differences between two graphs, created with [NoteGraph::diff].
+/// Only nodes and explicit edges are compared, implied edges follow from
+/// them.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct GraphDiff {
+    /// nodes that only exist in the new graph
+    #[wasm_bindgen(skip)]
+    pub added_nodes: Vec<String>,
+    /// resolved nodes that only exist in the old graph
+    #[wasm_bindgen(skip)]
+    pub removed_nodes: Vec<String>,
+    /// nodes that exist in both graphs, but with different data
+    #[wasm_bindgen(skip)]
+    pub changed_nodes: Vec<String>,
+    /// explicit edges that only exist in the new graph
+    #[wasm_bindgen(skip)]
+    pub added_edges: Vec<GCEdgeData>,
+    /// explicit edges that only exist in the old graph
+    #[wasm_bindgen(skip)]
+    pub removed_edges: Vec<GCEdgeData>,
+    /// edges to remove via [RemoveEdgeGraphUpdate]
+    edges_to_remove: Vec<GCEdgeData>,
+    /// nodes to remove via [RemoveNoteGraphUpdate]
+    nodes_to_remove: Vec<String>,
+    /// node data to replace via [SetNoteDataGraphUpdate]
+    node_data_to_set: Vec<GCNodeData>,
+    /// properties to set or remove via [SetNotePropertyGraphUpdate], as node,
+    /// key and value
+    properties_to_set: Vec<(String, String, Option<PropertyValue>)>,
+    /// nodes to add via [AddNoteGraphUpdate]
+    nodes_to_add: Vec<GCNodeData>,
+    /// edges to add via [AddEdgeGraphUpdate]
+    edges_to_add: Vec<GCEdgeData>,
+}
+
+#[wasm_bindgen]
+impl GraphDiff {
+    #[wasm_bindgen(js_name = added_nodes, getter)]
+    pub fn get_added_nodes(&self) -> Vec<String> {
+        self.added_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = removed_nodes, getter)]
+    pub fn get_removed_nodes(&self) -> Vec<String> {
+        self.removed_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = changed_nodes, getter)]
+    pub fn get_changed_nodes(&self) -> Vec<String> {
+        self.changed_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = added_edges, getter)]
+    pub fn get_added_edges(&self) -> Vec<GCEdgeData> {
+        self.added_edges.clone()
+    }
+
+    #[wasm_bindgen(js_name = removed_edges, getter)]
+    pub fn get_removed_edges(&self) -> Vec<GCEdgeData> {
+        self.removed_edges.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+
+    /// Creates a batch update that turns the old graph into the new graph.
+    pub fn to_batch_update(&self) -> BatchGraphUpdate {
+        let mut batch = BatchGraphUpdate::new();
+
+        for edge in &self.edges_to_remove {
+            RemoveEdgeGraphUpdate::with_source(
+                edge.source.clone(),
+                edge.target.clone(),
+                edge.edge_type.clone(),
+                edge.edge_source.clone(),
+            )
+            .add_to_batch(&mut batch);
+        }
+        for node in &self.nodes_to_remove {
+            RemoveNoteGraphUpdate::new(node.clone()).add_to_batch(&mut batch);
+        }
+        for node in &self.node_data_to_set {
+            SetNoteDataGraphUpdate::new(node.clone()).add_to_batch(&mut batch);
+        }
+        for (node, key, value) in &self.properties_to_set {
+            SetNotePropertyGraphUpdate::with_value(node.clone(), key.clone(), value.clone())
+                .add_to_batch(&mut batch);
+        }
+        for node in &self.nodes_to_add {
+            AddNoteGraphUpdate::new(node.clone()).add_to_batch(&mut batch);
+        }
+        for edge in &self.edges_to_add {
+            AddEdgeGraphUpdate::new(edge.clone()).add_to_batch(&mut batch);
+        }
+
+        batch
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+#[wasm_bindgen]
+impl NoteGraph {
+    /// Compares this graph with another graph and returns the differences.
+    /// [GraphDiff::to_batch_update] creates an update that turns this graph
+    /// into the other graph. Transitive rules are not compared.
+    /// Explicit edges are compared per edge source, including the order of the
+    /// edge sources of an edge. Edge sources with different attributes or a
+    /// different position count as removed and added.
+    pub fn diff(&self, other: &NoteGraph) -> GraphDiff {
+        let mut diff = GraphDiff::default();
+
+        // Removing a resolved node also removes all its outgoing edges. So nodes
+        // that become unresolved are removed and added again, together with all
+        // their outgoing edges. Nodes that stay resolved only get their changed
+        // data or properties set, which keeps their edges.
+        let mut removed_nodes: HashSet<&str> = HashSet::new();
+        let mut readded_nodes: HashSet<&str> = HashSet::new();
+
+        for (_, node) in self.graph.node_references() {
+            let other_node = other
+                .int_get_node_index(&node.path)
+                .and_then(|index| other.graph.node_weight(index));
+
+            match other_node {
+                Some(other_node) if other_node == node => {}
+                Some(other_node) => {
+                    diff.changed_nodes.push(node.path.clone());
+
+                    if node.resolved && other_node.resolved {
+                        diff.int_set_node_changes(node, other_node);
+                    } else if node.resolved {
+                        diff.nodes_to_remove.push(node.path.clone());
+                        removed_nodes.insert(&node.path);
+                        readded_nodes.insert(&node.path);
+                    }
+                }
+                None => {
+                    // unresolved nodes are removed automatically once they have no edges
+                    if node.resolved {
+                        diff.removed_nodes.push(node.path.clone());
+                        diff.nodes_to_remove.push(node.path.clone());
+                        removed_nodes.insert(&node.path);
+                    }
+                }
+            }
+        }
+
+        for (_, other_node) in other.graph.node_references() {
+            let node = self
+                .int_get_node_index(&other_node.path)
+                .and_then(|index| self.graph.node_weight(index));
+
+            match node {
+                Some(node) if node == other_node => {}
+                // the changes of nodes that stay resolved were already set above
+                Some(node) if node.resolved && other_node.resolved => {}
+                Some(_) => {
+                    diff.nodes_to_add.push(other_node.clone().into());
+                }
+                None => {
+                    diff.added_nodes.push(other_node.path.clone());
+                    diff.nodes_to_add.push(other_node.clone().into());
+                }
+            }
+        }
+
+        let edges = self.int_explicit_edge_map();
+        let other_edges = other.int_explicit_edge_map();
+
+        for (key, sources) in &edges {
+            let other_sources = other_edges.get(key).map_or(&[][..], Vec::as_slice);
+            diff.int_diff_edge_sources(sources, other_sources, &removed_nodes, &readded_nodes);
+        }
+        for (key, other_sources) in &other_edges {
+            if !edges.contains_key(key) {
+                diff.int_diff_edge_sources(&[], other_sources, &removed_nodes, &readded_nodes);
+            }
+        }
+
+        diff
+    }
+}
+
+impl GraphDiff {
+    /// Records the changes between the edge sources of an explicit edge in the
+    /// old and the new graph.
+    ///
+    /// An edge takes its label and attributes from its first edge source, and
+    /// edge sources that assert an edge again are added at the end. So the
+    /// edge sources at the front of the new edge that are unchanged and in
+    /// the same order as in the old edge are kept, only changing how often
+    /// they assert the edge. All other old edge sources are removed and the
+    /// remaining new edge sources are added in order.
+    fn int_diff_edge_sources(
+        &mut self,
+        sources: &[(GCEdgeData, u32)],
+        other_sources: &[(GCEdgeData, u32)],
+        removed_nodes: &HashSet<&str>,
+        readded_nodes: &HashSet<&str>,
+    ) {
+        let mut kept_positions = Vec::new();
+        for (other_source, _) in other_sources {
+            let position = sources
+                .iter()
+                .position(|(source, _)| source.has_same_data(other_source));
+            match position {
+                Some(position)
+                    if kept_positions
+                        .last()
+                        .is_none_or(|&previous| previous < position) =>
+                {
+                    kept_positions.push(position)
+                }
+                _ => break,
+            }
+        }
+
+        let Some((first, _)) = sources.first().or(other_sources.first()) else {
+            return;
+        };
+        // all outgoing edges of removed nodes are removed with the node, and they
+        // are all added again if the node is added again
+        let source_removed = removed_nodes.contains(first.source.as_str());
+        let source_readded = readded_nodes.contains(first.source.as_str());
+
+        for (position, (source, count)) in sources.iter().enumerate() {
+            let kept_count = kept_positions
+                .iter()
+                .position(|&kept| kept == position)
+                .map(|other_position| other_sources[other_position].1);
+
+            match kept_count {
+                Some(kept_count) if kept_count == *count => {}
+                Some(kept_count) => {
+                    self.removed_edges.push(source.clone());
+                    self.added_edges.push(source.clone());
+                    if !source_removed && kept_count < *count {
+                        for _ in kept_count..*count {
+                            self.edges_to_remove.push(source.clone());
+                        }
+                    }
+                }
+                None => {
+                    self.removed_edges.push(source.clone());
+                    if !source_removed {
+                        for _ in 0..*count {
+                            self.edges_to_remove.push(source.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        for (position, (other_source, other_count)) in other_sources.iter().enumerate() {
+            let kept_count = kept_positions.get(position).map(|&kept| sources[kept].1);
+            if kept_count.is_none() {
+                self.added_edges.push(other_source.clone());
+            }
+
+            let added_count = match kept_count {
+                Some(kept_count) if !source_readded => other_count.saturating_sub(kept_count),
+                _ => *other_count,
+            };
+            for _ in 0..added_count {
+                self.edges_to_add.push(other_source.clone());
+            }
+        }
+    }
+
+    /// Records the changes between the data of a node that is resolved in both
+    /// graphs. If only properties changed, only the changed properties are set,
+    /// otherwise the whole node data is replaced.
+    fn int_set_node_changes(&mut self, node: &NodeData, other_node: &NodeData) {
+        let same_data = node.aliases == other_node.aliases
+            && node.ignore_in_edges == other_node.ignore_in_edges
+            && node.ignore_out_edges == other_node.ignore_out_edges
+            && node.tags == other_node.tags;
+
+        if !same_data {
+            self.node_data_to_set.push(other_node.clone().into());
+            return;
+        }
+
+        for (key, value) in &other_node.properties {
+            if node.properties.get(key) != Some(value) {
+                self.properties_to_set
+                    .push((node.path.clone(), key.clone(), Some(value.clone())));
+            }
+        }
+        for key in node.properties.keys() {
+            if !other_node.properties.contains_key(key) {
+                self.properties_to_set
+                    .push((node.path.clone(), key.clone(), None));
+            }
+        }
+    }
+}
+
+impl NoteGraph {
+    /// Collects the edge sources of all explicit edges, keyed by source path,
+    /// target path and edge type. The edges are kept in the order of the
+    /// graph.
+    fn int_explicit_edge_map(&self) -> ExplicitEdgeMap<'_> {
+        let mut map = ExplicitEdgeMap::new();
+
+        for edge in self.graph.edge_references() {
+            let weight = edge.weight();
+            if !weight.explicit {
+                continue;
+            }
+
+            let source = &self.graph[edge.source()].path;
+            let target = &self.graph[edge.target()].path;
+
+            let sources = weight
+                .sources
+                .iter()
+                .map(|source_data| {
+                    (
+                        GCEdgeData::from_edge_source_data(
+                            source.clone(),
+                            target.clone(),
+                            &weight.edge_type,
+                            source_data,
+                        ),
+                        source_data.count,
+                    )
+                })
+                .collect();
+
+            map.insert(
+                (source.as_str(), target.as_str(), weight.edge_type.as_ref()),
+                sources,
+            );
+        }
+
+        map
+    }
+}
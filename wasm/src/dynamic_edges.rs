@@ -0,0 +1,439 @@
+use std::rc::Rc;
+
+use hashbrown::{HashMap, HashSet};
+use js_sys::{Array, Reflect, RegExp};
+use petgraph::{visit::EdgeRef, Direction};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{
+        edge::{EdgeData, EdgeSourceData},
+        node::NodeData,
+        NGEdgeIndex, NGNodeIndex,
+    },
+    graph::NoteGraph,
+    utils::{NoteGraphError, Result},
+};
+
+/// The edge source of edges created by regex edge sources.
+pub const REGEX_EDGE_SOURCE: &str = "regex_note";
+/// The edge source of edges created by folder edge sources.
+pub const FOLDER_EDGE_SOURCE: &str = "folder_note";
+/// The edge source of edges created by tag edge sources.
+pub const TAG_EDGE_SOURCE: &str = "tag_note";
+
+/// A source of edges from one note to all nodes matching a condition, e.g. a
+/// regex over the node paths, a folder or a tag. Unlike explicit edges, the
+/// edges of a dynamic edge source are re-evaluated when nodes are added,
+/// removed or renamed, so they don't go stale.
+///
+/// Only resolved nodes are matched.
+///
+/// Each edge source keeps track of the nodes it asserts an edge to, so that
+/// edge sources with the same edge source name and edge type on the same note
+/// assert their edges independently of each other.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct DynamicEdgeSource {
+    #[wasm_bindgen(skip)]
+    pub source: String,
+    #[wasm_bindgen(skip)]
+    pub edge_type: String,
+    #[wasm_bindgen(skip)]
+    pub edge_source: String,
+    #[wasm_bindgen(skip)]
+    pub matcher: NodeMatcher,
+    /// the nodes the edge source currently asserts an edge to
+    #[wasm_bindgen(skip)]
+    pub targets: HashSet<NGNodeIndex>,
+}
+
+/// The condition a node has to fulfill to be the target of a dynamic edge.
+#[derive(Clone, Debug)]
+pub enum NodeMatcher {
+    /// the node path matches a JS regular expression
+    PathRegex {
+        pattern: String,
+        flags: String,
+        regex: RegExp,
+    },
+    /// the node is a markdown note in the folder of the source note, or in one
+    /// of its subfolders if `recurse` is set
+    Folder { recurse: bool },
+    /// the node has a tag, if `exact` is false nested tags count as well
+    Tag { tag: String, exact: bool },
+}
+
+#[wasm_bindgen]
+impl DynamicEdgeSource {
+    /// Creates an edge source that adds edges of type `edge_type` from the
+    /// `source` note to all notes whose path matches the JS regular expression
+    /// `pattern` with the given `flags`.
+    ///
+    /// Will return an error if the regular expression is invalid.
+    pub fn regex(
+        source: String,
+        pattern: String,
+        flags: String,
+        edge_type: String,
+    ) -> Result<DynamicEdgeSource> {
+        // constructing the regex via reflection lets us catch syntax errors
+        let regex = Reflect::construct(
+            &RegExp::new("", "").constructor(),
+            &Array::of2(&JsValue::from_str(&pattern), &JsValue::from_str(&flags)),
+        )
+        .map_err(|_| {
+            NoteGraphError::new(&format!(
+                "failed to create regex edge source, \"{pattern}\" with flags \"{flags}\" is not a valid regex"
+            ))
+        })?
+        .unchecked_into::<RegExp>();
+
+        Ok(DynamicEdgeSource {
+            source,
+            edge_type,
+            edge_source: REGEX_EDGE_SOURCE.to_string(),
+            matcher: NodeMatcher::PathRegex {
+                pattern,
+                flags,
+                regex,
+            },
+            targets: HashSet::new(),
+        })
+    }
+
+    /// Creates an edge source that adds edges of type `edge_type` from the
+    /// `source` note to all other markdown notes in its folder. If `recurse`
+    /// is set, notes in subfolders are included as well. Moving the source
+    /// note changes the folder.
+    pub fn folder(source: String, edge_type: String, recurse: bool) -> DynamicEdgeSource {
+        DynamicEdgeSource {
+            source,
+            edge_type,
+            edge_source: FOLDER_EDGE_SOURCE.to_string(),
+            matcher: NodeMatcher::Folder { recurse },
+            targets: HashSet::new(),
+        }
+    }
+
+    /// Creates an edge source that adds edges of type `edge_type` from the
+    /// `source` note to all notes that have the tag `tag`. If `exact` is
+    /// false, notes with nested tags like `#tag/nested` are included as well.
+    pub fn tag(source: String, tag: String, edge_type: String, exact: bool) -> DynamicEdgeSource {
+        DynamicEdgeSource {
+            source,
+            edge_type,
+            edge_source: TAG_EDGE_SOURCE.to_string(),
+            matcher: NodeMatcher::Tag { tag, exact },
+            targets: HashSet::new(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = source, getter)]
+    pub fn get_source(&self) -> String {
+        self.source.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_type, getter)]
+    pub fn get_edge_type(&self) -> String {
+        self.edge_type.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_source, getter)]
+    pub fn get_edge_source(&self) -> String {
+        self.edge_source.clone()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+impl DynamicEdgeSource {
+    /// Whether the edge source has an edge to a node.
+    pub fn matches(&self, node: &NodeData) -> bool {
+        if !node.resolved {
+            return false;
+        }
+
+        match &self.matcher {
+            NodeMatcher::PathRegex { regex, .. } => {
+                // global and sticky regexes remember the position of the last match
+                regex.set_last_index(0);
+                regex.test(&node.path)
+            }
+            NodeMatcher::Folder { recurse } => {
+                let folder = folder_of(&self.source);
+                let relative_path = if folder.is_empty() {
+                    Some(node.path.as_str())
+                } else {
+                    node.path
+                        .strip_prefix(folder)
+                        .and_then(|rest| rest.strip_prefix('/'))
+                };
+
+                node.path != self.source
+                    && node.path.ends_with(".md")
+                    && relative_path.is_some_and(|path| *recurse || !path.contains('/'))
+            }
+            NodeMatcher::Tag { tag, exact } => node.has_tag(tag, *exact),
+        }
+    }
+}
+
+/// A change to the edges of a dynamic edge source.
+enum DynamicEdgeChange {
+    /// add a new edge to the target
+    Add(NGNodeIndex),
+    /// add the edge source to the existing edge to the target, e.g. because
+    /// other edge sources assert it as well
+    AddSource(NGNodeIndex, NGEdgeIndex),
+    /// remove the edge source from the edge to the target, if it still exists
+    Remove(NGNodeIndex, Option<NGEdgeIndex>),
+}
+
+impl DynamicEdgeSource {
+    /// The change needed so that the edge source asserts an edge to a node if
+    /// and only if it matches the node, given the existing explicit edge of
+    /// the edge type to the node.
+    fn change_for(
+        &self,
+        target: NGNodeIndex,
+        target_data: &NodeData,
+        existing_edge: Option<NGEdgeIndex>,
+    ) -> Option<DynamicEdgeChange> {
+        match (self.targets.contains(&target), self.matches(target_data)) {
+            (true, false) => Some(DynamicEdgeChange::Remove(target, existing_edge)),
+            (false, true) => match existing_edge {
+                Some(edge) => Some(DynamicEdgeChange::AddSource(target, edge)),
+                None => Some(DynamicEdgeChange::Add(target)),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The dynamic edge sources by what they match on, so that the edge sources
+/// that may have an edge to a node can be found without testing every edge
+/// source against the node.
+#[derive(Clone, Debug, Default)]
+pub struct DynamicEdgeSourceIndex {
+    /// regex edge sources, they may match any node
+    regex: Vec<usize>,
+    /// folder edge sources by the folder of their source note
+    by_folder: HashMap<String, Vec<usize>>,
+    /// tag edge sources by their tag, without the leading `#`
+    by_tag: HashMap<String, Vec<usize>>,
+    /// all edge sources by their source note
+    by_source: HashMap<String, Vec<usize>>,
+}
+
+impl DynamicEdgeSourceIndex {
+    pub fn new(edge_sources: &[DynamicEdgeSource]) -> DynamicEdgeSourceIndex {
+        let mut index = DynamicEdgeSourceIndex::default();
+
+        for (i, edge_source) in edge_sources.iter().enumerate() {
+            match &edge_source.matcher {
+                NodeMatcher::PathRegex { .. } => index.regex.push(i),
+                NodeMatcher::Folder { .. } => index
+                    .by_folder
+                    .entry(folder_of(&edge_source.source).to_string())
+                    .or_default()
+                    .push(i),
+                NodeMatcher::Tag { tag, .. } => index
+                    .by_tag
+                    .entry(tag.trim_start_matches('#').to_string())
+                    .or_default()
+                    .push(i),
+            }
+            index
+                .by_source
+                .entry(edge_source.source.clone())
+                .or_default()
+                .push(i);
+        }
+
+        index
+    }
+
+    /// The indices of the edge sources that may match a node or currently
+    /// have an edge to it, in ascending order.
+    fn candidates(&self, graph: &NoteGraph, node: NGNodeIndex, node_data: &NodeData) -> Vec<usize> {
+        let mut candidates = self.regex.clone();
+
+        let mut folder = node_data.path.as_str();
+        loop {
+            folder = folder_of(folder);
+            candidates.extend(self.by_folder.get(folder).into_iter().flatten());
+            if folder.is_empty() {
+                break;
+            }
+        }
+
+        for tag in &node_data.tags {
+            let tag = tag.trim_start_matches('#');
+            // nested tags match the edge sources of their parent tags as well
+            let parent_tags = tag.match_indices('/').map(|(position, _)| &tag[..position]);
+            for tag in parent_tags.chain([tag]) {
+                candidates.extend(self.by_tag.get(tag).into_iter().flatten());
+            }
+        }
+
+        for edge in graph.graph.edges_directed(node, Direction::Incoming) {
+            let dynamic = edge.weight().explicit
+                && edge.weight().sources.iter().any(|source| {
+                    [REGEX_EDGE_SOURCE, FOLDER_EDGE_SOURCE, TAG_EDGE_SOURCE]
+                        .contains(&source.edge_source.as_ref())
+                });
+            if dynamic {
+                let source = &graph.graph[edge.source()].path;
+                candidates.extend(self.by_source.get(source).into_iter().flatten());
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// The folder of a path, or the empty string for paths in the root folder.
+pub fn folder_of(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+impl NoteGraph {
+    /// Adds or removes the edges of the dynamic edge source at `index`, so
+    /// that they match the current nodes of the graph.
+    ///
+    /// There is no index of the nodes by folder or tag, so every node is
+    /// matched against the edge source. This is only done when an edge source
+    /// is added or a folder edge source moves to another folder. The existing
+    /// edges are collected once from the outgoing edges of the source node
+    /// instead of being looked up for every node.
+    pub fn int_evaluate_dynamic_edge_source(&mut self, index: usize) {
+        let edge_source = &self.dynamic_edge_sources[index];
+        let Some(source) = self.int_get_node_index(&edge_source.source) else {
+            return;
+        };
+
+        let existing_edges: HashMap<NGNodeIndex, NGEdgeIndex> = self
+            .int_iter_outgoing_edges(source)
+            .filter(|edge| {
+                edge.weight().explicit && edge.weight().edge_type.as_ref() == edge_source.edge_type
+            })
+            .map(|edge| (edge.target(), edge.id()))
+            .collect();
+
+        let changes: Vec<DynamicEdgeChange> = self
+            .graph
+            .node_indices()
+            .filter_map(|target| {
+                edge_source.change_for(
+                    target,
+                    &self.graph[target],
+                    existing_edges.get(&target).copied(),
+                )
+            })
+            .collect();
+
+        self.int_apply_dynamic_edge_changes(index, source, changes);
+    }
+
+    /// Removes the edges of a dynamic edge source that is no longer in the
+    /// list of dynamic edge sources. Edges that other edge sources assert as
+    /// well are kept.
+    pub fn int_remove_dynamic_edges(&mut self, edge_source: &DynamicEdgeSource) {
+        let Some(source) = self.int_get_node_index(&edge_source.source) else {
+            return;
+        };
+
+        for &target in &edge_source.targets {
+            if let Some(edge) = self
+                .int_get_explicit_edge(source, target, &edge_source.edge_type)
+                .map(|edge| edge.id())
+            {
+                self.int_remove_edge_source(edge, &edge_source.edge_source);
+            }
+        }
+    }
+
+    /// Re-evaluates the dynamic edge sources for a node, e.g. because it was
+    /// added, removed or renamed. Only the edge sources that may match the
+    /// node or have an edge to it are evaluated.
+    pub fn int_sync_dynamic_edges_of_node(&mut self, target: NGNodeIndex) {
+        if !self.graph.contains_node(target) {
+            return;
+        }
+        let edge_source_index = self
+            .dynamic_edge_source_index
+            .take()
+            .unwrap_or_else(|| DynamicEdgeSourceIndex::new(&self.dynamic_edge_sources));
+        let candidates = edge_source_index.candidates(self, target, &self.graph[target]);
+        self.dynamic_edge_source_index = Some(edge_source_index);
+
+        for index in candidates {
+            let edge_source = &self.dynamic_edge_sources[index];
+            let Some(source) = self.int_get_node_index(&edge_source.source) else {
+                continue;
+            };
+
+            let existing_edge = self
+                .graph
+                .edges_connecting(source, target)
+                .find(|edge| {
+                    edge.weight().explicit
+                        && edge.weight().edge_type.as_ref() == edge_source.edge_type
+                })
+                .map(|edge| edge.id());
+
+            if let Some(change) = edge_source.change_for(target, &self.graph[target], existing_edge)
+            {
+                self.int_apply_dynamic_edge_changes(index, source, [change]);
+            }
+        }
+    }
+
+    /// Drops the index of the dynamic edge sources, it is rebuilt when it is
+    /// needed next. Has to be called whenever the list of dynamic edge sources
+    /// or the source note or matcher of one of them changes.
+    pub(crate) fn int_invalidate_dynamic_edge_source_index(&mut self) {
+        self.dynamic_edge_source_index = None;
+    }
+
+    fn int_apply_dynamic_edge_changes(
+        &mut self,
+        index: usize,
+        source: NGNodeIndex,
+        changes: impl IntoIterator<Item = DynamicEdgeChange>,
+    ) {
+        let edge_source = &self.dynamic_edge_sources[index];
+        let edge_type: Rc<str> = Rc::from(edge_source.edge_type.as_str());
+        let edge_source_name: Rc<str> = Rc::from(edge_source.edge_source.as_str());
+
+        for change in changes {
+            match change {
+                DynamicEdgeChange::Add(target) => {
+                    let edge_data =
+                        EdgeData::new(edge_type.clone(), edge_source_name.clone(), true, 0);
+                    self.change_tracker.touch_edge(source, target, &edge_type);
+                    self.int_add_edge(source, target, edge_data);
+                    self.int_set_dynamic_edge_target(index, target, true);
+                }
+                DynamicEdgeChange::AddSource(target, edge) => {
+                    if let Ok(edge_weight) = self.int_get_edge_weight_mut(edge) {
+                        edge_weight.add_edge_source(EdgeSourceData::new(edge_source_name.clone()));
+                    }
+                    self.int_set_dynamic_edge_target(index, target, true);
+                }
+                DynamicEdgeChange::Remove(target, edge) => {
+                    if let Some(edge) = edge {
+                        self.int_remove_edge_source(edge, &edge_source_name);
+                    }
+                    self.int_set_dynamic_edge_target(index, target, false);
+                }
+            }
+        }
+    }
+}
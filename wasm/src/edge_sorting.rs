@@ -16,7 +16,11 @@ pub enum SortField {
     Basename,
     EdgeType,
     Implied,
+    /// the order index of the edges, e.g. the position in a list note
+    Order,
     Neighbour(String),
+    /// a metadata property of the target node
+    Property(String),
 }
 
 impl FromStr for SortField {
@@ -28,9 +32,13 @@ impl FromStr for SortField {
             "basename" => Ok(SortField::Basename),
             "field" => Ok(SortField::EdgeType),
             "explicit" => Ok(SortField::Implied),
+            "order" => Ok(SortField::Order),
             s if s.starts_with("neighbour-field:") => Ok(SortField::Neighbour(
                 s["neighbour-field:".len()..].to_string(),
             )),
+            s if s.starts_with("property:") => {
+                Ok(SortField::Property(s["property:".len()..].to_string()))
+            }
             _ => Err(NoteGraphError::new("Invalid sort field")),
         }
     }
@@ -125,9 +133,11 @@ impl EdgeSorter {
             SortField::Basename => BasenameComparer.into(),
             SortField::EdgeType => EdgeTypeComparer.into(),
             SortField::Implied => ImpliedComparer.into(),
+            SortField::Order => OrderComparer.into(),
             SortField::Neighbour(neighbour_field) => {
                 NeighbourComparer::new(neighbour_field, graph).into()
             }
+            SortField::Property(key) => PropertyComparer::new(key).into(),
         }
     }
 
@@ -168,7 +178,9 @@ pub enum Comparer<'a> {
     BasenameComparer,
     EdgeTypeComparer,
     ImpliedComparer,
+    OrderComparer,
     NeighbourOrdering(NeighbourComparer<'a>),
+    PropertyComparer,
 }
 
 #[derive(Default)]
@@ -222,6 +234,31 @@ impl EdgeComparer for ImpliedComparer {
     }
 }
 
+/// Sorts by the order index of the edges. Edges without an order index come
+/// last, ties are broken by path.
+#[derive(Default)]
+pub struct OrderComparer;
+
+impl EdgeComparer for OrderComparer {
+    fn compare(&self, graph: &NoteGraph, a: &EdgeStruct, b: &EdgeStruct) -> std::cmp::Ordering {
+        let ordering = match (
+            a.edge_data_ref(graph).unwrap().order,
+            b.edge_data_ref(graph).unwrap().order,
+        ) {
+            (Some(a_order), Some(b_order)) => a_order.cmp(&b_order),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+
+        ordering.then_with(|| {
+            a.target_path_ref(graph)
+                .unwrap()
+                .cmp(b.target_path_ref(graph).unwrap())
+        })
+    }
+}
+
 pub struct NeighbourComparer<'a> {
     neighbour_field: String,
     graph: &'a NoteGraph,
@@ -269,3 +306,41 @@ impl EdgeComparer for NeighbourComparer<'_> {
         }
     }
 }
+
+/// Sorts by a property of the target nodes. Nodes without the property come
+/// last, ties are broken by path.
+pub struct PropertyComparer {
+    key: String,
+}
+
+impl PropertyComparer {
+    pub fn new(key: String) -> Self {
+        PropertyComparer { key }
+    }
+}
+
+impl EdgeComparer for PropertyComparer {
+    fn compare(&self, graph: &NoteGraph, a: &EdgeStruct, b: &EdgeStruct) -> std::cmp::Ordering {
+        let a_property = graph
+            .int_get_node_weight(a.target_index)
+            .ok()
+            .and_then(|node| node.properties.get(&self.key));
+        let b_property = graph
+            .int_get_node_weight(b.target_index)
+            .ok()
+            .and_then(|node| node.properties.get(&self.key));
+
+        let ordering = match (a_property, b_property) {
+            (Some(a_property), Some(b_property)) => a_property.compare(b_property),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+
+        ordering.then_with(|| {
+            a.target_path_ref(graph)
+                .unwrap()
+                .cmp(b.target_path_ref(graph).unwrap())
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use petgraph::visit::EdgeRef;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::NGNodeIndex,
+    graph::NoteGraph,
+    utils::{NoteGraphError, Result},
+};
+
+/// Explains why an edge exists, created with [NoteGraph::explain_edge].
+/// Explicit edges are asserted by their edge sources, implied edges are
+/// derived by a transitive rule from the edges in `derived_from`, which are
+/// explained in turn.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct EdgeExplanation {
+    #[wasm_bindgen(skip)]
+    pub source: String,
+    #[wasm_bindgen(skip)]
+    pub target: String,
+    #[wasm_bindgen(skip)]
+    pub edge_type: String,
+    pub explicit: bool,
+    /// the round of the transitive rules in which an implied edge was created
+    pub round: u16,
+    /// the edge sources asserting an explicit edge, or the name of the rule
+    /// that created an implied edge
+    #[wasm_bindgen(skip)]
+    pub edge_sources: Vec<String>,
+    /// the edges an implied edge was derived from, in the order of the rule
+    /// path
+    #[wasm_bindgen(skip)]
+    pub derived_from: Vec<EdgeExplanation>,
+}
+
+#[wasm_bindgen]
+impl EdgeExplanation {
+    #[wasm_bindgen(js_name = source, getter)]
+    pub fn get_source(&self) -> String {
+        self.source.clone()
+    }
+
+    #[wasm_bindgen(js_name = target, getter)]
+    pub fn get_target(&self) -> String {
+        self.target.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_type, getter)]
+    pub fn get_edge_type(&self) -> String {
+        self.edge_type.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_sources, getter)]
+    pub fn get_edge_sources(&self) -> Vec<String> {
+        self.edge_sources.clone()
+    }
+
+    #[wasm_bindgen(js_name = derived_from, getter)]
+    pub fn get_derived_from(&self) -> Vec<EdgeExplanation> {
+        self.derived_from.clone()
+    }
+
+    /// The explicit edges the edge ultimately rests on, in the order of the
+    /// derivation. For an explicit edge, this is the edge itself.
+    pub fn explicit_edges(&self) -> Vec<EdgeExplanation> {
+        let mut edges = Vec::new();
+        self.int_collect_explicit_edges(&mut edges);
+        edges
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+impl EdgeExplanation {
+    fn int_collect_explicit_edges(&self, edges: &mut Vec<EdgeExplanation>) {
+        if self.explicit {
+            edges.push(self.clone());
+        } else {
+            for edge in &self.derived_from {
+                edge.int_collect_explicit_edges(edges);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl NoteGraph {
+    /// Explains why an edge exists. For implied edges, the returned tree
+    /// contains the edges the edge was derived from, down to the explicit
+    /// edges.
+    ///
+    /// Will return an error if the edge does not exist.
+    pub fn explain_edge(
+        &self,
+        from: String,
+        to: String,
+        edge_type: String,
+    ) -> Result<EdgeExplanation> {
+        let from = self.int_get_node_index(&from).ok_or(NoteGraphError::new(
+            "failed to explain edge, source node not found",
+        ))?;
+        let to = self.int_get_node_index(&to).ok_or(NoteGraphError::new(
+            "failed to explain edge, target node not found",
+        ))?;
+
+        self.int_explain_edge(from, to, &edge_type)
+    }
+}
+
+impl NoteGraph {
+    fn int_explain_edge(
+        &self,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        edge_type: &str,
+    ) -> Result<EdgeExplanation> {
+        let edge = self
+            .int_get_edge(from, to, edge_type)
+            .ok_or(NoteGraphError::new(
+                "failed to explain edge, edge not found",
+            ))?;
+        let weight = edge.weight();
+
+        // the edges an implied edge is derived from were created in earlier rounds,
+        // so the recursion terminates at the explicit edges
+        let derived_from = weight
+            .derived_from
+            .iter()
+            .map(|(from, to, edge_type)| self.int_explain_edge(*from, *to, edge_type))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(EdgeExplanation {
+            source: self.int_get_node_weight(edge.source())?.path.clone(),
+            target: self.int_get_node_weight(edge.target())?.path.clone(),
+            edge_type: weight.edge_type.to_string(),
+            explicit: weight.explicit,
+            round: weight.round,
+            edge_sources: weight.get_edge_sources(),
+            derived_from,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
 use petgraph::{
     stable_graph::{Edges, StableGraph},
     visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences, NodeRef},
@@ -12,14 +13,22 @@ use wasm_bindgen::prelude::*;
 use crate::{
     data::{
         construction::{GCEdgeData, GCNodeData},
-        edge::EdgeData,
+        edge::{EdgeData, EdgeStep},
         edge_list::{EdgeList, GroupedEdgeList},
         edge_struct::EdgeStruct,
         node::NodeData,
-        rules::TransitiveGraphRule,
+        rules::{RuleStep, TransitiveGraphRule},
         NGEdgeIndex, NGEdgeRef, NGNodeIndex,
     },
-    update::{batch::BatchGraphUpdate, AddEdgeGraphUpdate, AddNoteGraphUpdate},
+    dynamic_edges::{DynamicEdgeSource, DynamicEdgeSourceIndex},
+    traversal::options::TraversalDirection,
+    update::{
+        batch::BatchGraphUpdate,
+        change_set::{GraphChangeState, ImpliedEdgeKeys},
+        tracker::ChangeTracker,
+        undo::{UndoEntry, UndoLog},
+        AddEdgeGraphUpdate, AddNoteGraphUpdate,
+    },
     utils::{NoteGraphError, PerfLogger, Result, LOGGER},
 };
 
@@ -56,12 +65,30 @@ pub struct NoteGraph {
     pub edge_types: VecSet<[Rc<str>; 16]>,
     #[wasm_bindgen(skip)]
     pub node_hash: HashMap<String, NGNodeIndex>,
+    /// Edge sources whose edges are re-evaluated when nodes change.
+    #[wasm_bindgen(skip)]
+    pub dynamic_edge_sources: Vec<DynamicEdgeSource>,
+    /// The dynamic edge sources by what they match on, built when needed.
+    pub(crate) dynamic_edge_source_index: Option<DynamicEdgeSourceIndex>,
     /// A JS function that is called after every update to the graph, notifying
     /// the JS side that there were changes in the graph, but not which changes.
     update_callback: Option<js_sys::Function>,
+    /// A JS function that is called after every update to the graph with a
+    /// [GraphChangeSet](crate::update::change_set::GraphChangeSet) describing
+    /// the changes.
+    change_callback: Option<js_sys::Function>,
     /// A revision number that is incremented after every update.
     /// This can be used to check if the graph has changed.
     revision: u32,
+    /// Tracks the changes made by the updates of the current batch.
+    #[wasm_bindgen(skip)]
+    pub change_tracker: ChangeTracker,
+    /// Records the changes made by the updates of the current batch, so that
+    /// they can be undone if the batch fails.
+    #[wasm_bindgen(skip)]
+    pub undo_log: UndoLog,
+    /// Whether updates only rebuild the implied edges affected by them.
+    incremental_updates: bool,
 }
 
 #[wasm_bindgen]
@@ -72,8 +99,14 @@ impl NoteGraph {
             transitive_rules: Vec::new(),
             edge_types: VecSet::empty(),
             node_hash: HashMap::new(),
+            dynamic_edge_sources: Vec::new(),
+            dynamic_edge_source_index: None,
             update_callback: None,
+            change_callback: None,
             revision: 0,
+            change_tracker: ChangeTracker::new(),
+            undo_log: UndoLog::new(),
+            incremental_updates: true,
         }
     }
 
@@ -83,6 +116,17 @@ impl NoteGraph {
         self.update_callback = Some(callback);
     }
 
+    /// Set the change callback.
+    /// This will be called after every update to the graph with a
+    /// [GraphChangeSet](crate::update::change_set::GraphChangeSet) describing
+    /// the changes made by the update.
+    ///
+    /// Computing the change set requires comparing the graph before and after
+    /// the update, so this should only be set if the changes are needed.
+    pub fn set_change_callback(&mut self, callback: js_sys::Function) {
+        self.change_callback = Some(callback);
+    }
+
     /// Notify the JS side that the graph has been updated.
     pub fn notify_update(&self) {
         if let Some(callback) = &self.update_callback {
@@ -98,7 +142,9 @@ impl NoteGraph {
     }
 
     /// Builds the graph from a list of nodes, edges, and transitive rules.
-    /// All existing data in the graph is removed.
+    /// All existing data in the graph is removed, including the dynamic edge
+    /// sources.
+    /// If building fails, the previous graph is kept.
     pub fn build_graph(
         &mut self,
         nodes: Vec<GCNodeData>,
@@ -107,10 +153,12 @@ impl NoteGraph {
     ) -> Result<()> {
         LOGGER.with(|l| l.info("Building Graph"));
 
-        self.graph = StableGraph::<NodeData, EdgeData, Directed, u32>::default();
-        self.edge_types = VecSet::empty();
-        self.node_hash = HashMap::new();
-        self.transitive_rules = transitive_rules;
+        let old_graph = std::mem::take(&mut self.graph);
+        let old_edge_types = std::mem::replace(&mut self.edge_types, VecSet::empty());
+        let old_node_hash = std::mem::take(&mut self.node_hash);
+        let old_dynamic_edge_sources = std::mem::take(&mut self.dynamic_edge_sources);
+        self.int_invalidate_dynamic_edge_source_index();
+        let old_transitive_rules = std::mem::replace(&mut self.transitive_rules, transitive_rules);
 
         let mut update = BatchGraphUpdate::new();
         for data in nodes {
@@ -120,41 +168,29 @@ impl NoteGraph {
             AddEdgeGraphUpdate::new(data).add_to_batch(&mut update);
         }
 
-        self.apply_update(update)
+        self.int_apply_update(update, true).inspect_err(|_| {
+            self.graph = old_graph;
+            self.edge_types = old_edge_types;
+            self.node_hash = old_node_hash;
+            self.dynamic_edge_sources = old_dynamic_edge_sources;
+            self.int_invalidate_dynamic_edge_source_index();
+            self.transitive_rules = old_transitive_rules;
+        })
     }
 
     /// Applies a batch update to the graph.
-    /// Throws an error if the update fails, and leave the graph in an
-    /// inconsistent state.
-    ///
-    /// TODO: some security against errors leaving the graph in an inconsistent
-    /// state. Maybe safely clear the entire graph.
+    /// The update is applied all-or-nothing. If any update in the batch fails,
+    /// the graph is restored to the state before the batch and the returned
+    /// error contains the index of the failing update.
     pub fn apply_update(&mut self, update: BatchGraphUpdate) -> Result<()> {
-        let mut perf_logger = PerfLogger::new("Applying Update".to_owned());
-        perf_logger.start_split("Removing implied edges".to_owned());
-
-        self.int_remove_implied_edges();
-
-        perf_logger.start_split("Applying updates".to_owned());
-
-        // self.log();
-        update.apply(self)?;
-        // self.log();
-
-        perf_logger.start_split("Rebuilding edge type tracker".to_owned());
-
-        self.int_remove_orphan_unresolved_nodes();
-        self.int_rebuild_edge_type_tracker();
-        self.int_build_implied_edges(&mut perf_logger);
-        self.revision += 1;
-
-        perf_logger.start_split("Update notification callback".to_owned());
-
-        self.notify_update();
-
-        perf_logger.log();
+        self.int_apply_update(update, !self.incremental_updates)
+    }
 
-        Ok(())
+    /// Sets whether updates should only rebuild the implied edges that can be
+    /// affected by the update, instead of rebuilding all implied edges.
+    /// This is enabled by default.
+    pub fn set_incremental_updates(&mut self, incremental: bool) {
+        self.incremental_updates = incremental;
     }
 
     /// Iterate all nodes in the graph and call the provided function with each
@@ -186,12 +222,14 @@ impl NoteGraph {
     }
 
     /// Get all outgoing edges from a node.
+    /// Edges that are ignored due to `ignore_out_edges` or `ignore_in_edges`
+    /// are skipped.
     pub fn get_outgoing_edges(&self, node: String) -> EdgeList {
         let node_index = self.int_get_node_index(&node);
 
         EdgeList::from_vec(match node_index {
             Some(node_index) => self
-                .int_iter_outgoing_edges(node_index)
+                .int_iter_traversable_outgoing_edges(node_index)
                 .map(|edge| EdgeStruct::from_edge_ref(edge, self))
                 .collect(),
             None => Vec::new(),
@@ -208,7 +246,7 @@ impl NoteGraph {
 
         EdgeList::from_vec(match node_index {
             Some(node_index) => self
-                .int_iter_outgoing_edges(node_index)
+                .int_iter_traversable_outgoing_edges(node_index)
                 .filter(|edge_ref| {
                     edge_matches_edge_filter_string(edge_ref.weight(), edge_types.as_ref())
                 })
@@ -228,12 +266,14 @@ impl NoteGraph {
     }
 
     /// Get all incoming edges to a node.
+    /// Edges that are ignored due to `ignore_out_edges` or `ignore_in_edges`
+    /// are skipped.
     pub fn get_incoming_edges(&self, node: String) -> EdgeList {
         let node_index = self.int_get_node_index(&node);
 
         EdgeList::from_vec(match node_index {
             Some(node_index) => self
-                .int_iter_incoming_edges(node_index)
+                .int_iter_traversable_incoming_edges(node_index)
                 .map(|edge| EdgeStruct::from_edge_ref(edge, self))
                 .collect(),
             None => Vec::new(),
@@ -275,6 +315,23 @@ impl Default for NoteGraph {
     }
 }
 
+/// Decides which edges the transitive rules can see while implied edges are
+/// built.
+#[derive(Clone, Copy)]
+struct RuleEdgeFilter<'a> {
+    /// Only edges created before this round are visible.
+    round: u16,
+    /// Implied edges that are being rebuilt and are not visible.
+    hidden_edges: &'a HashSet<NGEdgeIndex>,
+}
+
+impl RuleEdgeFilter<'_> {
+    fn is_visible(&self, edge: NGEdgeRef) -> bool {
+        let weight = edge.weight();
+        weight.round < self.round && (weight.explicit || !self.hidden_edges.contains(&edge.id()))
+    }
+}
+
 /// Internal methods, not exposed to the wasm interface.
 impl NoteGraph {
     /// Get the current revision number of the graph, useful to check if
@@ -283,16 +340,293 @@ impl NoteGraph {
         self.revision
     }
 
+    /// Overrides the revision number, e.g. when restoring a graph from a
+    /// snapshot.
+    pub(crate) fn set_revision(&mut self, revision: u32) {
+        self.revision = revision;
+    }
+
+    /// Applies a batch update to the graph.
+    /// If any update in the batch fails, the changes made by the batch are
+    /// undone with the undo log and the graph is left as before the batch.
+    ///
+    /// The updates only change nodes and explicit edges. The implied edges
+    /// stay untouched until all updates succeeded, so that they don't need to
+    /// be restored on failure.
+    ///
+    /// If `full_rebuild` is false, only the implied edges that can be affected
+    /// by the update are rebuilt, see [NoteGraph::int_rebuild_implied_edges].
+    fn int_apply_update(&mut self, update: BatchGraphUpdate, full_rebuild: bool) -> Result<()> {
+        let mut perf_logger = PerfLogger::new("Applying Update".to_owned());
+        perf_logger.start_split("Applying updates".to_owned());
+
+        self.change_tracker = ChangeTracker::new();
+
+        self.undo_log.start();
+        // self.log();
+        let result = update.apply(self);
+        // self.log();
+        let undo_entries = self.undo_log.stop();
+
+        if let Err(err) = result {
+            self.int_undo(undo_entries);
+            self.change_tracker = ChangeTracker::new();
+            return Err(err);
+        }
+
+        let mut change_state = self
+            .change_callback
+            .is_some()
+            .then(|| GraphChangeState::new(self, &undo_entries));
+
+        let full_rebuild = full_rebuild || self.change_tracker.rules_changed;
+        self.int_rebuild_implied_edges(
+            full_rebuild,
+            change_state
+                .as_mut()
+                .map(|change_state| &mut change_state.implied_edges),
+            &mut perf_logger,
+        );
+
+        self.revision += 1;
+
+        perf_logger.start_split("Update notification callback".to_owned());
+
+        self.notify_update();
+        if let Some(change_state) = change_state {
+            self.notify_change(change_state);
+        }
+
+        perf_logger.log();
+
+        Ok(())
+    }
+
+    /// Calls the change callback with the changes made since `change_state`
+    /// was created.
+    fn notify_change(&self, change_state: GraphChangeState) {
+        if let Some(callback) = &self.change_callback {
+            let change_set = change_state.into_change_set(self, &self.change_tracker.renamed_nodes);
+
+            match callback.call1(&JsValue::NULL, &JsValue::from(change_set)) {
+                Ok(_) => {}
+                Err(e) => LOGGER.with(|l| {
+                    l.warn(&format!(
+                        "Error calling change notification function: {e:?}"
+                    ))
+                }),
+            }
+        }
+    }
+
     /// Builds the implied edges based on the transitive rules.
     pub fn int_build_implied_edges(&mut self, perf_logger: &mut PerfLogger) {
-        let perf_split = perf_logger.start_split("Building Implied Edges".to_owned());
+        self.int_rebuild_implied_edges(true, None, perf_logger);
+    }
+
+    /// Rebuilds the implied edges after the explicit edges changed, and
+    /// removes unresolved nodes that were left without any edges.
+    ///
+    /// If `full_rebuild` is false, only the implied edges that can be affected
+    /// by the changes recorded in the change tracker are rebuilt. Implied edges
+    /// only depend on edges between nodes of the same weakly connected
+    /// component, so only the components of the touched nodes are affected.
+    /// Within them, only edge types that are touched or produced by a rule that
+    /// reads an affected edge type can change. All other implied edges are
+    /// kept, including the round in which they were created.
+    ///
+    /// The affected implied edges are not removed up front, but hidden from the
+    /// rules. Edges that the rules derive again are updated in place and only
+    /// the remaining hidden edges are removed, in the order of their indices.
+    /// This way, rebuilding only the affected edges results in exactly the same
+    /// graph as a full rebuild.
+    ///
+    /// If `implied_edge_keys` is given, the keys of the affected implied edges
+    /// before and after the rebuild are collected into it.
+    pub fn int_rebuild_implied_edges(
+        &mut self,
+        full_rebuild: bool,
+        mut implied_edge_keys: Option<&mut ImpliedEdgeKeys>,
+        perf_logger: &mut PerfLogger,
+    ) {
+        let perf_split = perf_logger.start_split(if full_rebuild {
+            "Building Implied Edges".to_owned()
+        } else {
+            "Rebuilding Affected Implied Edges".to_owned()
+        });
 
+        let (start_nodes, edge_types, orphan_candidates) = if full_rebuild {
+            let nodes: Vec<NGNodeIndex> = self.graph.node_indices().collect();
+            (nodes.clone(), None, nodes)
+        } else {
+            let tracker = &self.change_tracker;
+            // sort the nodes so that the edges are derived in the same order as in a
+            // full rebuild
+            let start_nodes: Vec<NGNodeIndex> = self
+                .int_weakly_connected_nodes(&tracker.touched_nodes)
+                .into_iter()
+                .sorted()
+                .collect();
+            let orphan_candidates: Vec<NGNodeIndex> = tracker
+                .touched_nodes
+                .union(&tracker.unresolved_nodes)
+                .copied()
+                .sorted()
+                .collect();
+
+            (
+                start_nodes,
+                Some(self.int_affected_edge_types()),
+                orphan_candidates,
+            )
+        };
+
+        let mut hidden_edges: HashSet<NGEdgeIndex> = start_nodes
+            .iter()
+            .flat_map(|&node| self.int_iter_outgoing_edges(node))
+            .filter(|edge| {
+                let weight = edge.weight();
+                !weight.explicit
+                    && edge_types
+                        .as_ref()
+                        .is_none_or(|types| types.contains(&weight.edge_type))
+            })
+            .map(|edge| edge.id())
+            .collect();
+
+        if let Some(keys) = implied_edge_keys.as_mut() {
+            keys.before = hidden_edges
+                .iter()
+                .filter_map(|&edge| ImpliedEdgeKeys::key(self, edge))
+                .collect();
+        }
+
+        LOGGER.with(|l| {
+            l.debug(&format!(
+                "Rebuilding {} implied edges for {} nodes",
+                hidden_edges.len(),
+                start_nodes.len()
+            ))
+        });
+
+        for edge_type in &self.change_tracker.touched_edge_types {
+            self.edge_types.insert(Rc::clone(edge_type));
+        }
+
+        if !start_nodes.is_empty() {
+            self.int_apply_transitive_rules(
+                &start_nodes,
+                edge_types.as_ref(),
+                &mut hidden_edges,
+                perf_split,
+            );
+        }
+
+        perf_split.start_split("Removing stale implied edges".to_owned());
+
+        let mut removed_edge_types: HashSet<Rc<str>> = HashSet::new();
+        for edge in hidden_edges.into_iter().sorted() {
+            if let Some(edge_data) = self.graph.remove_edge(edge) {
+                removed_edge_types.insert(edge_data.edge_type);
+            }
+        }
+
+        if let Some(keys) = implied_edge_keys {
+            keys.after = start_nodes
+                .iter()
+                .flat_map(|&node| self.int_iter_outgoing_edges(node))
+                .filter(|edge| {
+                    !edge.weight().explicit
+                        && edge_types
+                            .as_ref()
+                            .is_none_or(|types| types.contains(&edge.weight().edge_type))
+                })
+                .filter_map(|edge| ImpliedEdgeKeys::key(self, edge.id()))
+                .collect();
+        }
+
+        self.int_remove_orphan_unresolved_nodes(&orphan_candidates);
+
+        if full_rebuild {
+            self.int_rebuild_edge_type_tracker();
+        } else {
+            let unused_edge_types: Vec<Rc<str>> = self
+                .change_tracker
+                .touched_edge_types
+                .iter()
+                .chain(removed_edge_types.iter())
+                .filter(|edge_type| {
+                    !self
+                        .graph
+                        .edge_weights()
+                        .any(|weight| weight.edge_type == **edge_type)
+                })
+                .cloned()
+                .collect();
+            self.edge_types
+                .retain(|edge_type| !unused_edge_types.contains(edge_type));
+        }
+
+        perf_split.stop();
+    }
+
+    /// Applies the transitive rules for paths starting at the given nodes.
+    /// If `edge_types` is set, only rules that produce one of these edge types
+    /// are applied.
+    ///
+    /// `hidden_edges` are implied edges that are being rebuilt. They are
+    /// invisible to the rules, unless they are derived again, in which case
+    /// they are updated in place and removed from the set.
+    ///
+    /// An edge created in round `i` is only visible to the rules from round
+    /// `i + 1` onwards. This way implied edges that are already in the graph
+    /// keep their effect on later rounds, as if they had just been built.
+    ///
+    /// Closure rules are applied in every round and in one more round after
+    /// the last round of the other rules, so that they see all edges the
+    /// other rules add.
+    fn int_apply_transitive_rules(
+        &mut self,
+        start_nodes: &[NGNodeIndex],
+        edge_types: Option<&HashSet<Rc<str>>>,
+        hidden_edges: &mut HashSet<NGEdgeIndex>,
+        perf_split: &mut PerfLogger,
+    ) {
         let max_rounds = self
             .transitive_rules
             .iter()
-            .map(|rule| rule.rounds())
+            .filter(|rule| !rule.closure())
+            .map(|rule| u16::from(rule.rounds()))
             .max()
             .unwrap_or(0);
+        // rounds are counted as u16, so that there is room for the extra round even
+        // if a rule has the maximum number of rounds
+        let last_round = if self.transitive_rules.iter().any(|rule| rule.closure()) {
+            max_rounds + 1
+        } else {
+            max_rounds
+        };
+
+        // Implied edges that are already in the graph become visible in the round
+        // after the round they were created in. We need to keep going until all of
+        // them are visible, even if no new edges are added.
+        let mut existing_types_by_round: Vec<VecSet<[Rc<str>; 16]>> = Vec::new();
+        for edge in start_nodes
+            .iter()
+            .flat_map(|&node| self.int_iter_outgoing_edges(node))
+        {
+            let weight = edge.weight();
+            if weight.explicit || hidden_edges.contains(&edge.id()) {
+                continue;
+            }
+
+            let round = weight.round as usize;
+            if existing_types_by_round.len() <= round {
+                existing_types_by_round.resize(round + 1, VecSet::empty());
+            }
+            existing_types_by_round[round].insert(Rc::clone(&weight.edge_type));
+        }
+        let last_existing_round = existing_types_by_round.len().saturating_sub(1);
 
         // rules look like
         // [A, B, C] -> D
@@ -303,23 +637,49 @@ impl NoteGraph {
         // multiple times, since the edges on the left side were not modified.
 
         let mut edge_type_tracker = self.edge_types.clone();
-        let mut edges_to_add: Vec<(NGNodeIndex, NGNodeIndex, &TransitiveGraphRule)> = Vec::new();
-        // we reuse these two vectors to avoid allocations
+        // the edges to add, together with the node before the last repetition of the
+        // path for closure rules
+        let mut edges_to_add: Vec<(
+            NGNodeIndex,
+            NGNodeIndex,
+            &TransitiveGraphRule,
+            Option<NGNodeIndex>,
+        )> = Vec::new();
+        // we reuse these vectors to avoid allocations
         let mut node_vec_1: Vec<NGNodeIndex> = Vec::new();
         let mut node_vec_2: Vec<NGNodeIndex> = Vec::new();
+        let mut end_nodes: Vec<(NGNodeIndex, Option<NGNodeIndex>)> = Vec::new();
 
-        for i in 1..=max_rounds {
+        for i in 1..=last_round {
             let round_perf_split = perf_split.start_split(format!("Round {i}",));
+            let existing_edges_pending = (i as usize) <= last_existing_round;
+
+            // existing implied edges from the last round become visible in this round
+            if let Some(types) = existing_types_by_round.get(i as usize - 1) {
+                for edge_type in types.iter() {
+                    edge_type_tracker.insert(Rc::clone(edge_type));
+                }
+            }
 
             // if the edge type tracker is empty, we didn't add any edges last round, so we
             // can stop
-            if edge_type_tracker.is_empty() {
+            if edge_type_tracker.is_empty() && !existing_edges_pending {
                 break;
             }
 
             round_perf_split.start_split("Applying Rules".to_string());
 
             for rule in self.transitive_rules.iter() {
+                // after the last round of the other rules, only closure rules are applied
+                if i > max_rounds && !rule.closure() {
+                    continue;
+                }
+
+                // if the rule does not produce one of the requested edge types, we can skip it
+                if edge_types.is_some_and(|types| !types.contains(rule.edge_type_ref())) {
+                    continue;
+                }
+
                 // if there is any edge type that the graph doesn't have, we can skip the rule
                 if rule
                     .iter_path()
@@ -337,52 +697,56 @@ impl NoteGraph {
                     continue;
                 }
 
-                // For every rule (outer loop) we iterate over all nodes in the graph (this
+                // For every rule (outer loop) we iterate over all start nodes (this
                 // loop) and check for all possible applications of that rule
                 // for that node.
-                for start_node in self.graph.node_indices() {
-                    node_vec_1.clear();
-                    node_vec_2.clear();
-
-                    // We start with the start node.
-                    node_vec_1.push(start_node);
-
-                    // Now we iterate the path of the rule and each step, for all current nodes,
-                    // we check for outgoing edges that match the edge type of the current element
-                    // of the rule path.
-                    for edge_type in rule.iter_path() {
-                        for current_node in &node_vec_1 {
-                            for edge in self.graph.edges(*current_node) {
-                                if edge.weight().edge_type == *edge_type {
-                                    node_vec_2.push(edge.target());
-                                }
-                            }
-                        }
-
-                        std::mem::swap(&mut node_vec_1, &mut node_vec_2);
-                        node_vec_2.clear();
+                let edge_filter = RuleEdgeFilter {
+                    round: i,
+                    hidden_edges,
+                };
+
+                for &start_node in start_nodes {
+                    if rule.closure() {
+                        self.int_rule_closure_ends(
+                            start_node,
+                            rule,
+                            edge_filter,
+                            &mut end_nodes,
+                            &mut node_vec_1,
+                            &mut node_vec_2,
+                        );
+                    } else {
+                        self.int_rule_path_ends(
+                            start_node,
+                            rule,
+                            edge_filter,
+                            &mut node_vec_1,
+                            &mut node_vec_2,
+                        );
+                        end_nodes.clear();
+                        end_nodes.extend(node_vec_1.iter().map(|end_node| (*end_node, None)));
                     }
 
-                    // Now we are left with end nodes. For each end node, there exists a path from
-                    // the start node to the end node that matches the rule.
-                    for end_node in &node_vec_1 {
+                    // For each end node, there exists a path from the start node to the end node
+                    // that matches the rule.
+                    for &(end_node, via) in &end_nodes {
                         // If the rule can't loop, that means the start and end nodes can't be the
                         // same.
-                        if !rule.can_loop() && start_node == *end_node {
+                        if !rule.can_loop() && start_node == end_node {
                             continue;
                         }
 
                         if rule.close_reversed() {
-                            edges_to_add.push((*end_node, start_node, rule));
+                            edges_to_add.push((end_node, start_node, rule, via));
                         } else {
-                            edges_to_add.push((start_node, *end_node, rule));
+                            edges_to_add.push((start_node, end_node, rule, via));
                         }
                     }
                 }
             }
 
             // if there are no edges to add, we can stop
-            if edges_to_add.is_empty() {
+            if edges_to_add.is_empty() && !existing_edges_pending {
                 break;
             }
 
@@ -390,25 +754,285 @@ impl NoteGraph {
 
             round_perf_split.start_split(format!("Adding {} Edges", edges_to_add.len()));
 
-            for (from, to, rule) in edges_to_add.drain(..) {
-                if self.int_has_edge(from, to, rule.edge_type_ref()) {
+            for (from, to, rule, via) in edges_to_add.drain(..) {
+                // we don't add edges that would be ignored anyway
+                if !self.int_is_edge_traversable(from, to) {
+                    continue;
+                }
+
+                // a hidden edge that is derived again is updated in place
+                let mut hidden_edge = None;
+                let mut edge_exists = false;
+                for edge in self.graph.edges_connecting(from, to) {
+                    if *edge.weight().edge_type != *rule.edge_type_ref() {
+                        continue;
+                    }
+
+                    if hidden_edges.contains(&edge.id()) {
+                        hidden_edge = Some(edge.id());
+                    } else {
+                        edge_exists = true;
+                    }
+                }
+                if edge_exists {
                     continue;
                 }
 
                 self.edge_types.insert(rule.edge_type());
                 edge_type_tracker.insert(rule.edge_type());
 
-                self.graph.add_edge(
-                    from,
-                    to,
-                    EdgeData::new(rule.edge_type(), rule.name(), false, i),
-                );
+                let (start_node, end_node) = if rule.close_reversed() {
+                    (to, from)
+                } else {
+                    (from, to)
+                };
+
+                let mut edge_data = EdgeData::new(rule.edge_type(), rule.name(), false, i);
+                // for closure rules, the edge is derived from the edge for the shorter path
+                // to the node before the last repetition, which is added in this round as well
+                let repetition_start = match via {
+                    Some(via) => {
+                        edge_data.derived_from.push(if rule.close_reversed() {
+                            (via, start_node, rule.edge_type())
+                        } else {
+                            (start_node, via, rule.edge_type())
+                        });
+                        via
+                    }
+                    None => start_node,
+                };
+                edge_data.derived_from.extend(self.int_find_rule_path(
+                    repetition_start,
+                    end_node,
+                    rule,
+                    RuleEdgeFilter {
+                        round: i,
+                        hidden_edges,
+                    },
+                ));
+
+                match hidden_edge {
+                    Some(edge) => {
+                        hidden_edges.remove(&edge);
+                        self.graph[edge] = edge_data;
+                    }
+                    None => {
+                        self.graph.add_edge(from, to, edge_data);
+                    }
+                }
             }
 
             round_perf_split.stop();
         }
+    }
 
-        perf_split.stop();
+    /// Collects the nodes at the end of the paths from the start node that
+    /// match the path of a rule, into `end_nodes`. For each step, for all
+    /// current nodes, we check for outgoing edges, or incoming edges for
+    /// reversed steps, that match the edge type of the step and pass the edge
+    /// filter. Ignored edges are not followed.
+    fn int_rule_path_ends(
+        &self,
+        start_node: NGNodeIndex,
+        rule: &TransitiveGraphRule,
+        edge_filter: RuleEdgeFilter,
+        end_nodes: &mut Vec<NGNodeIndex>,
+        buffer: &mut Vec<NGNodeIndex>,
+    ) {
+        end_nodes.clear();
+        buffer.clear();
+        end_nodes.push(start_node);
+
+        for step in rule.iter_steps() {
+            for current_node in end_nodes.iter() {
+                for (edge, next_node) in
+                    self.int_iter_traversable_edges_directed(*current_node, step.direction())
+                {
+                    if edge.weight().edge_type == step.edge_type && edge_filter.is_visible(edge) {
+                        buffer.push(next_node);
+                    }
+                }
+            }
+
+            std::mem::swap(end_nodes, buffer);
+            buffer.clear();
+        }
+    }
+
+    /// Collects the nodes that can be reached from the start node by repeating
+    /// the path of a closure rule one or more times, into `end_nodes`. Each
+    /// node is found once, by a breadth first search over the repetitions,
+    /// together with the node before the last repetition of the shortest
+    /// path, if the path is repeated more than once.
+    fn int_rule_closure_ends(
+        &self,
+        start_node: NGNodeIndex,
+        rule: &TransitiveGraphRule,
+        edge_filter: RuleEdgeFilter,
+        end_nodes: &mut Vec<(NGNodeIndex, Option<NGNodeIndex>)>,
+        node_vec_1: &mut Vec<NGNodeIndex>,
+        node_vec_2: &mut Vec<NGNodeIndex>,
+    ) {
+        end_nodes.clear();
+
+        let mut visited: HashSet<NGNodeIndex> = HashSet::new();
+        visited.insert(start_node);
+        let mut start_reached = false;
+
+        let mut frontier = vec![start_node];
+        while !frontier.is_empty() {
+            let mut next_frontier = Vec::new();
+
+            for &node in &frontier {
+                self.int_rule_path_ends(node, rule, edge_filter, node_vec_1, node_vec_2);
+                let via = (node != start_node).then_some(node);
+
+                for &end_node in node_vec_1.iter() {
+                    // the start node is only an end node if the path leads back to it
+                    if end_node == start_node {
+                        if !start_reached {
+                            start_reached = true;
+                            end_nodes.push((end_node, via));
+                        }
+                    } else if visited.insert(end_node) {
+                        end_nodes.push((end_node, via));
+                        next_frontier.push(end_node);
+                    }
+                }
+            }
+
+            frontier = next_frontier;
+        }
+    }
+
+    /// Finds a path from the start node to the end node that matches the path
+    /// of a rule, using only edges that pass the edge filter.
+    /// The path is returned as the edges it consists of, which are the edges
+    /// an edge created by the rule is derived from.
+    fn int_find_rule_path(
+        &self,
+        start_node: NGNodeIndex,
+        end_node: NGNodeIndex,
+        rule: &TransitiveGraphRule,
+        edge_filter: RuleEdgeFilter,
+    ) -> Vec<EdgeStep> {
+        let rule_path: Vec<&RuleStep> = rule.iter_steps().collect();
+        let mut path: Vec<EdgeStep> = Vec::with_capacity(rule_path.len());
+        // positions in the rule path from which a node is known to not reach the end
+        // node, so that we don't search them again
+        let mut dead_ends: HashSet<(NGNodeIndex, usize)> = HashSet::new();
+
+        if self.int_find_rule_path_from(
+            start_node,
+            end_node,
+            &rule_path,
+            edge_filter,
+            &mut path,
+            &mut dead_ends,
+        ) {
+            path
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn int_find_rule_path_from(
+        &self,
+        node: NGNodeIndex,
+        end_node: NGNodeIndex,
+        rule_path: &[&RuleStep],
+        edge_filter: RuleEdgeFilter,
+        path: &mut Vec<EdgeStep>,
+        dead_ends: &mut HashSet<(NGNodeIndex, usize)>,
+    ) -> bool {
+        let position = path.len();
+        let Some(&step) = rule_path.get(position) else {
+            return node == end_node;
+        };
+
+        if dead_ends.contains(&(node, position)) {
+            return false;
+        }
+
+        for (edge, next_node) in self.int_iter_traversable_edges_directed(node, step.direction()) {
+            if edge.weight().edge_type != step.edge_type || !edge_filter.is_visible(edge) {
+                continue;
+            }
+
+            path.push((edge.source(), edge.target(), Rc::clone(&step.edge_type)));
+            if self.int_find_rule_path_from(
+                next_node,
+                end_node,
+                rule_path,
+                edge_filter,
+                path,
+                dead_ends,
+            ) {
+                return true;
+            }
+            path.pop();
+        }
+
+        dead_ends.insert((node, position));
+        false
+    }
+
+    /// Collects all nodes that are weakly connected to any of the given nodes
+    /// by explicit edges.
+    fn int_weakly_connected_nodes(&self, nodes: &HashSet<NGNodeIndex>) -> HashSet<NGNodeIndex> {
+        let mut visited: HashSet<NGNodeIndex> = HashSet::new();
+        let mut stack: Vec<NGNodeIndex> = Vec::new();
+
+        for &node in nodes {
+            if self.graph.contains_node(node) && visited.insert(node) {
+                stack.push(node);
+            }
+        }
+
+        while let Some(node) = stack.pop() {
+            let neighbours = self
+                .int_iter_outgoing_edges(node)
+                .filter(|edge| edge.weight().explicit)
+                .map(|edge| edge.target())
+                .chain(
+                    self.int_iter_incoming_edges(node)
+                        .filter(|edge| edge.weight().explicit)
+                        .map(|edge| edge.source()),
+                );
+
+            for neighbour in neighbours.collect_vec() {
+                if visited.insert(neighbour) {
+                    stack.push(neighbour);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Collects all edge types that can be affected by the touched edge types
+    /// of the change tracker. These are the touched edge types and the edge
+    /// types of all rules that have an affected edge type in their path.
+    fn int_affected_edge_types(&self) -> HashSet<Rc<str>> {
+        let mut edge_types = self.change_tracker.touched_edge_types.clone();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for rule in self.transitive_rules.iter() {
+                if !edge_types.contains(rule.edge_type_ref())
+                    && rule
+                        .iter_path()
+                        .any(|edge_type| edge_types.contains(edge_type))
+                {
+                    edge_types.insert(rule.edge_type());
+                    changed = true;
+                }
+            }
+        }
+
+        edge_types
     }
 
     pub fn int_rebuild_edge_type_tracker(&mut self) {
@@ -440,29 +1064,27 @@ impl NoteGraph {
         });
     }
 
-    /// Removes all unresolved notes with no incoming or outgoing edges.
+    /// Removes the given nodes if they are unresolved notes with no incoming or
+    /// outgoing edges. The nodes are removed in the given order.
     ///
     /// INVARIANT: This updates the node hash.
     /// INVARIANT: This keeps the edge type tracker up to date, as only nodes
     /// with no connecting edges are removed.
-    pub fn int_remove_orphan_unresolved_nodes(&mut self) {
-        let mut nodes_to_remove: Vec<(NGNodeIndex, String)> = Vec::new();
-
-        for node in self.graph.node_indices() {
-            let node_weight = self.graph.node_weight(node).unwrap();
+    pub fn int_remove_orphan_unresolved_nodes(&mut self, nodes: &[NGNodeIndex]) {
+        for &node in nodes {
+            let Some(node_weight) = self.graph.node_weight(node) else {
+                continue;
+            };
 
             if !node_weight.resolved
                 && !self.int_has_incoming_edges(node)
                 && !self.int_has_outgoing_edges(node)
             {
-                nodes_to_remove.push((node, node_weight.path.clone()));
+                let name = node_weight.path.clone();
+                self.node_hash.remove(&name);
+                self.graph.remove_node(node);
             }
         }
-
-        for (node_index, name) in nodes_to_remove {
-            self.node_hash.remove(&name);
-            self.graph.remove_node(node_index);
-        }
     }
 
     // ---------------------
@@ -488,10 +1110,18 @@ impl NoteGraph {
         ))
     }
 
+    /// Returns a mutable reference to the [NodeData] for a specific node index.
+    /// The current data is recorded in the undo log.
+    ///
+    /// Will return an error if the node is not found.
     pub fn int_get_node_weight_mut(&mut self, node: NGNodeIndex) -> Result<&mut NodeData> {
-        self.graph.node_weight_mut(node).ok_or(NoteGraphError::new(
+        let node_weight = self.graph.node_weight_mut(node).ok_or(NoteGraphError::new(
             "failed to get node weight, node not found",
-        ))
+        ))?;
+        self.undo_log
+            .record(|| UndoEntry::NodeWeight(node, node_weight.clone()));
+
+        Ok(node_weight)
     }
 
     pub fn int_has_incoming_edges(&self, node: NGNodeIndex) -> bool {
@@ -518,6 +1148,52 @@ impl NoteGraph {
             .edges_directed(node, petgraph::Direction::Outgoing)
     }
 
+    /// Iterates the outgoing edges of a node, skipping ignored edges.
+    /// See [NoteGraph::int_is_edge_traversable].
+    pub fn int_iter_traversable_outgoing_edges(
+        &self,
+        node: NGNodeIndex,
+    ) -> impl Iterator<Item = NGEdgeRef<'_>> {
+        self.int_iter_outgoing_edges(node)
+            .filter(|edge| self.int_is_edge_traversable(edge.source(), edge.target()))
+    }
+
+    /// Iterates the incoming edges of a node, skipping ignored edges.
+    /// See [NoteGraph::int_is_edge_traversable].
+    pub fn int_iter_traversable_incoming_edges(
+        &self,
+        node: NGNodeIndex,
+    ) -> impl Iterator<Item = NGEdgeRef<'_>> {
+        self.int_iter_incoming_edges(node)
+            .filter(|edge| self.int_is_edge_traversable(edge.source(), edge.target()))
+    }
+
+    /// Iterates the traversable edges of a node in the given direction,
+    /// together with the node on the other end of each edge. Self loops are
+    /// only returned once.
+    pub fn int_iter_traversable_edges_directed(
+        &self,
+        node: NGNodeIndex,
+        direction: TraversalDirection,
+    ) -> impl Iterator<Item = (NGEdgeRef<'_>, NGNodeIndex)> {
+        let outgoing = direction
+            .includes_outgoing()
+            .then(|| self.int_iter_traversable_outgoing_edges(node))
+            .into_iter()
+            .flatten()
+            .map(|edge| (edge, edge.target()));
+
+        let incoming = direction
+            .includes_incoming()
+            .then(|| self.int_iter_traversable_incoming_edges(node))
+            .into_iter()
+            .flatten()
+            .filter(move |edge| !direction.includes_outgoing() || edge.source() != edge.target())
+            .map(|edge| (edge, edge.source()));
+
+        outgoing.chain(incoming)
+    }
+
     // ---------------------
     // Edge Methods
     // ---------------------
@@ -539,6 +1215,20 @@ impl NoteGraph {
             .find(|e| e.target() == to && *e.weight().edge_type == *edge_type)
     }
 
+    /// Gets an explicit edge between two nodes based on a specific edge type,
+    /// ignoring implied edges.
+    /// Returns None if the edge does not exist.
+    pub fn int_get_explicit_edge(
+        &self,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        edge_type: &str,
+    ) -> Option<NGEdgeRef<'_>> {
+        self.graph.edges(from).find(|e| {
+            e.target() == to && e.weight().explicit && *e.weight().edge_type == *edge_type
+        })
+    }
+
     /// Gets an edge between two nodes based on a specific edge type.
     /// Returns None if the edge does not exist.
     pub fn int_get_edge_by_name(
@@ -553,6 +1243,22 @@ impl NoteGraph {
         self.int_get_edge(from_index, to_index, edge_type)
     }
 
+    /// Checks if an edge between two nodes should be followed by traversals and
+    /// transitive rules. An edge is ignored if its source node has
+    /// `ignore_out_edges` set or its target node has `ignore_in_edges` set.
+    pub fn int_is_edge_traversable(&self, from: NGNodeIndex, to: NGNodeIndex) -> bool {
+        let from_ignored = self
+            .graph
+            .node_weight(from)
+            .is_none_or(|node| node.ignore_out_edges);
+        let to_ignored = self
+            .graph
+            .node_weight(to)
+            .is_none_or(|node| node.ignore_in_edges);
+
+        !from_ignored && !to_ignored
+    }
+
     /// Checks if an edge exists between two nodes with a specific edge type.
     pub fn int_has_edge(&self, from: NGNodeIndex, to: NGNodeIndex, edge_type: &str) -> bool {
         self.graph
@@ -583,12 +1289,17 @@ impl NoteGraph {
     }
 
     /// Get a mutable reference to the edge weight for a specific edge index.
+    /// The current data is recorded in the undo log.
     ///
     /// Will return an error if the edge is not found.
     pub fn int_get_edge_weight_mut(&mut self, edge: NGEdgeIndex) -> Result<&mut EdgeData> {
-        self.graph.edge_weight_mut(edge).ok_or(NoteGraphError::new(
+        let edge_weight = self.graph.edge_weight_mut(edge).ok_or(NoteGraphError::new(
             "failed to get edge weight, edge not found",
-        ))
+        ))?;
+        self.undo_log
+            .record(|| UndoEntry::EdgeWeight(edge, edge_weight.clone()));
+
+        Ok(edge_weight)
     }
 
     pub fn int_edge_types(&self) -> Vec<Rc<str>> {
@@ -1,7 +1,13 @@
+pub mod builders;
 pub mod data;
+pub mod diff;
+pub mod dynamic_edges;
 pub mod edge_sorting;
+pub mod explanation;
 pub mod graph;
 pub mod mermaid;
+pub mod rule_validation;
+pub mod snapshot;
 pub mod traversal;
 pub mod update;
 pub mod utils;
@@ -0,0 +1,279 @@
+use std::rc::Rc;
+
+use hashbrown::HashSet;
+use itertools::Itertools;
+use petgraph::visit::IntoEdgeReferences;
+use wasm_bindgen::prelude::*;
+
+use crate::{data::rules::TransitiveGraphRule, graph::NoteGraph};
+
+/// The kind of problem a [RuleDiagnostic] reports.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleDiagnosticKind {
+    /// the rule has no path
+    EmptyPath,
+    /// the rule has `rounds` set to 0
+    ZeroRounds,
+    /// an edge type in the path of the rule neither exists in the graph nor
+    /// can be created by any rule, so the rule never applies
+    UnreachableEdgeType,
+    /// the rule produces an edge type it reads, directly or through other
+    /// rules, so it keeps adding edges until the rounds cap is reached. Not
+    /// reported for closure rules, which stop once they find no new paths.
+    SelfFeeding,
+    /// another rule already adds every edge this rule adds
+    Shadowed,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleDiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+/// A problem with a transitive rule, created by [validate_rules].
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleDiagnostic {
+    pub kind: RuleDiagnosticKind,
+    pub severity: RuleDiagnosticSeverity,
+    /// the position of the rule in the validated rules
+    pub rule_index: u32,
+    #[wasm_bindgen(skip)]
+    pub rule_name: String,
+    #[wasm_bindgen(skip)]
+    pub message: String,
+    /// the edge type the diagnostic is about, if any
+    #[wasm_bindgen(skip)]
+    pub edge_type: Option<String>,
+    /// the positions of other rules involved, e.g. the rule that shadows this
+    /// rule or the other rules of a self-feeding cycle
+    #[wasm_bindgen(skip)]
+    pub related_rules: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl RuleDiagnostic {
+    #[wasm_bindgen(js_name = rule_name, getter)]
+    pub fn get_rule_name(&self) -> String {
+        self.rule_name.clone()
+    }
+
+    #[wasm_bindgen(js_name = message, getter)]
+    pub fn get_message(&self) -> String {
+        self.message.clone()
+    }
+
+    #[wasm_bindgen(js_name = edge_type, getter)]
+    pub fn get_edge_type(&self) -> Option<String> {
+        self.edge_type.clone()
+    }
+
+    #[wasm_bindgen(js_name = related_rules, getter)]
+    pub fn get_related_rules(&self) -> Vec<u32> {
+        self.related_rules.clone()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+impl RuleDiagnostic {
+    fn new(
+        kind: RuleDiagnosticKind,
+        rule_index: usize,
+        rule: &TransitiveGraphRule,
+        message: String,
+    ) -> RuleDiagnostic {
+        let severity = match kind {
+            RuleDiagnosticKind::EmptyPath => RuleDiagnosticSeverity::Error,
+            _ => RuleDiagnosticSeverity::Warning,
+        };
+
+        RuleDiagnostic {
+            kind,
+            severity,
+            rule_index: rule_index as u32,
+            rule_name: rule.name().to_string(),
+            message,
+            edge_type: None,
+            related_rules: Vec::new(),
+        }
+    }
+}
+
+/// Checks a set of transitive rules for problems, before they are applied
+/// to the graph with a `TransitiveRulesGraphUpdate`. The edge types of the
+/// explicit edges of the graph are used to find rules that can never apply.
+/// The diagnostics are ordered by rule.
+#[wasm_bindgen]
+pub fn validate_rules(rules: Vec<TransitiveGraphRule>, graph: &NoteGraph) -> Vec<RuleDiagnostic> {
+    let reachable_edge_types = reachable_edge_types(&rules, graph);
+    let mut diagnostics = Vec::new();
+
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.iter_path().next().is_none() {
+            diagnostics.push(RuleDiagnostic::new(
+                RuleDiagnosticKind::EmptyPath,
+                index,
+                rule,
+                "the rule has an empty path".to_string(),
+            ));
+        }
+
+        // closure rules don't depend on the rounds
+        if rule.rounds() == 0 && !rule.closure() {
+            diagnostics.push(RuleDiagnostic::new(
+                RuleDiagnosticKind::ZeroRounds,
+                index,
+                rule,
+                "the rule has 0 rounds, but it is still applied while other rules have rounds left"
+                    .to_string(),
+            ));
+        }
+
+        for edge_type in rule.iter_path().unique() {
+            if !reachable_edge_types.contains(edge_type) {
+                let mut diagnostic = RuleDiagnostic::new(
+                    RuleDiagnosticKind::UnreachableEdgeType,
+                    index,
+                    rule,
+                    format!(
+                        "the edge type \"{edge_type}\" does not exist in the graph and is not created by any rule, so the rule never applies"
+                    ),
+                );
+                diagnostic.edge_type = Some(edge_type.to_string());
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        // closure rules stop once they find no new paths, so only the other rules run
+        // until the rounds cap
+        let cycle = (!rule.closure())
+            .then(|| self_feeding_cycle(&rules, index))
+            .flatten();
+        if let Some(cycle) = cycle {
+            let mut diagnostic = RuleDiagnostic::new(
+                RuleDiagnosticKind::SelfFeeding,
+                index,
+                rule,
+                format!(
+                    "the rule creates \"{}\" edges that it reads itself, so it keeps adding edges until the rounds cap is reached",
+                    rule.edge_type_ref()
+                ),
+            );
+            diagnostic.edge_type = Some(rule.edge_type().to_string());
+            diagnostic.related_rules = cycle;
+            diagnostics.push(diagnostic);
+        }
+
+        if let Some(shadowing_index) = shadowing_rule(&rules, index) {
+            let mut diagnostic = RuleDiagnostic::new(
+                RuleDiagnosticKind::Shadowed,
+                index,
+                rule,
+                format!(
+                    "the rule only adds edges that the rule \"{}\" adds as well",
+                    rules[shadowing_index].name_ref()
+                ),
+            );
+            diagnostic.related_rules = vec![shadowing_index as u32];
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+/// Collects the edge types that can exist in the graph, which are the edge
+/// types of explicit edges and the edge types rules create from them.
+fn reachable_edge_types(rules: &[TransitiveGraphRule], graph: &NoteGraph) -> HashSet<Rc<str>> {
+    let mut edge_types: HashSet<Rc<str>> = graph
+        .graph
+        .edge_references()
+        .filter(|edge| edge.weight().explicit)
+        .map(|edge| Rc::clone(&edge.weight().edge_type))
+        .collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for rule in rules {
+            // rules with an empty path only create edges from a node to itself
+            let can_apply = if rule.iter_path().next().is_none() {
+                rule.can_loop()
+            } else {
+                rule.iter_path()
+                    .all(|edge_type| edge_types.contains(edge_type))
+            };
+
+            if can_apply && !edge_types.contains(rule.edge_type_ref()) {
+                edge_types.insert(rule.edge_type());
+                changed = true;
+            }
+        }
+    }
+
+    edge_types
+}
+
+/// Whether one rule reads the edge type another rule creates.
+fn feeds(from: &TransitiveGraphRule, to: &TransitiveGraphRule) -> bool {
+    to.iter_path()
+        .any(|edge_type| edge_type.as_ref() == from.edge_type_ref())
+}
+
+/// If the rule reads its own edges, directly or through other rules, returns
+/// the other rules involved in that cycle.
+fn self_feeding_cycle(rules: &[TransitiveGraphRule], index: usize) -> Option<Vec<u32>> {
+    let reaches = |start: usize| -> HashSet<usize> {
+        let mut visited: HashSet<usize> = HashSet::new();
+        let mut stack = vec![start];
+
+        while let Some(current) = stack.pop() {
+            for (next, rule) in rules.iter().enumerate() {
+                if feeds(&rules[current], rule) && visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        visited
+    };
+
+    let reachable = reaches(index);
+    if !reachable.contains(&index) {
+        return None;
+    }
+
+    Some(
+        reachable
+            .into_iter()
+            .filter(|&other| other != index && reaches(other).contains(&index))
+            .sorted()
+            .map(|other| other as u32)
+            .collect(),
+    )
+}
+
+/// Finds another rule that adds every edge the rule adds. Of two identical
+/// rules, the later one is shadowed by the earlier one.
+fn shadowing_rule(rules: &[TransitiveGraphRule], index: usize) -> Option<usize> {
+    let rule = &rules[index];
+    let covers = |a: &TransitiveGraphRule, b: &TransitiveGraphRule| {
+        a.edge_type_ref() == b.edge_type_ref()
+            && a.close_reversed() == b.close_reversed()
+            && a.iter_steps().eq(b.iter_steps())
+            && (a.can_loop() || !b.can_loop())
+            && (a.closure() || !b.closure())
+    };
+
+    rules.iter().enumerate().position(|(other_index, other)| {
+        other_index != index && covers(other, rule) && (other_index < index || !covers(rule, other))
+    })
+}
@@ -0,0 +1,675 @@
+use std::{collections::BTreeMap, rc::Rc};
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        edge::EdgeData,
+        property::PropertyValue,
+        rules::{RuleStep, TransitiveGraphRule},
+    },
+    dynamic_edges::{DynamicEdgeSource, NodeMatcher},
+    graph::NoteGraph,
+    utils::{NoteGraphError, Result},
+};
+
+/// Magic bytes at the start of every snapshot.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"BCGS";
+/// The version of the snapshot format. Snapshots with a different version can
+/// not be read and the graph needs to be rebuilt.
+pub const SNAPSHOT_VERSION: u16 = 2;
+
+const NODE_FLAG_RESOLVED: u8 = 1;
+const NODE_FLAG_IGNORE_IN_EDGES: u8 = 1 << 1;
+const NODE_FLAG_IGNORE_OUT_EDGES: u8 = 1 << 2;
+
+const EDGE_FLAG_ORDER: u8 = 1;
+const EDGE_FLAG_LABEL: u8 = 1 << 1;
+
+const RULE_FLAG_CAN_LOOP: u8 = 1;
+const RULE_FLAG_CLOSE_REVERSED: u8 = 1 << 1;
+const RULE_FLAG_CLOSURE: u8 = 1 << 2;
+
+const MATCHER_KIND_PATH_REGEX: u8 = 0;
+const MATCHER_KIND_FOLDER: u8 = 1;
+const MATCHER_KIND_TAG: u8 = 2;
+
+const PROPERTY_KIND_STRING: u8 = 0;
+const PROPERTY_KIND_NUMBER: u8 = 1;
+const PROPERTY_KIND_DATE: u8 = 2;
+const PROPERTY_KIND_BOOL: u8 = 3;
+const PROPERTY_KIND_LIST: u8 = 4;
+/// The maximum number of lists a property value can be nested in, so that
+/// corrupted snapshots can't overflow the stack.
+const MAX_PROPERTY_DEPTH: usize = 32;
+
+#[wasm_bindgen]
+impl NoteGraph {
+    /// Serializes the graph into a compact binary snapshot.
+    /// The snapshot contains the nodes, the explicit edges, the transitive
+    /// rules and the revision. Implied edges are rebuilt when loading the
+    /// snapshot.
+    ///
+    /// The format is
+    /// - header: magic bytes `BCGS`, version (u16)
+    /// - revision (u32)
+    /// - string table of edge types and edge sources
+    /// - nodes: path, aliases, flags, tags, properties
+    /// - explicit edges: source and target as node positions, edge type as
+    ///   string table index, edge sources as string table indices with their
+    ///   counts and the order, label and attributes each of them gave the edge
+    /// - transitive rules, with the edge type of each step of the path and
+    ///   whether the step is reversed
+    /// - dynamic edge sources: source as node position, edge type, edge source,
+    ///   the matcher and the nodes it asserts edges to as node positions
+    ///
+    /// All integers are little endian, strings and lists are prefixed with
+    /// their length as u32.
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut writer = SnapshotWriter::new();
+
+        writer.write_bytes(SNAPSHOT_MAGIC);
+        writer.write_u16(SNAPSHOT_VERSION);
+        writer.write_u32(self.get_revision());
+
+        // nodes are referenced by their position in the snapshot, since node indices
+        // can have gaps
+        let mut node_positions = HashMap::new();
+        for (position, node_ref) in self.graph.node_references().enumerate() {
+            node_positions.insert(node_ref.0, position as u32);
+        }
+
+        let mut strings = StringTable::default();
+        let edges: Vec<(u32, u32, u32, &EdgeData)> = self
+            .graph
+            .edge_references()
+            .filter(|edge| edge.weight().explicit)
+            .map(|edge| {
+                let weight = edge.weight();
+                for source in &weight.sources {
+                    strings.intern(&source.edge_source);
+                }
+                (
+                    node_positions[&edge.source()],
+                    node_positions[&edge.target()],
+                    strings.intern(&weight.edge_type),
+                    weight,
+                )
+            })
+            .collect();
+
+        writer.write_u32(strings.strings.len() as u32);
+        for string in &strings.strings {
+            writer.write_str(string);
+        }
+
+        writer.write_u32(self.graph.node_count() as u32);
+        for (_, node) in self.graph.node_references() {
+            writer.write_str(&node.path);
+            writer.write_u32(node.aliases.len() as u32);
+            for alias in &node.aliases {
+                writer.write_str(alias);
+            }
+
+            let mut flags = 0;
+            if node.resolved {
+                flags |= NODE_FLAG_RESOLVED;
+            }
+            if node.ignore_in_edges {
+                flags |= NODE_FLAG_IGNORE_IN_EDGES;
+            }
+            if node.ignore_out_edges {
+                flags |= NODE_FLAG_IGNORE_OUT_EDGES;
+            }
+            writer.write_u8(flags);
+
+            writer.write_u32(node.tags.len() as u32);
+            for tag in &node.tags {
+                writer.write_str(tag);
+            }
+
+            writer.write_u32(node.properties.len() as u32);
+            for (key, value) in &node.properties {
+                writer.write_str(key);
+                writer.write_property(value);
+            }
+        }
+
+        writer.write_u32(edges.len() as u32);
+        for (source, target, edge_type, weight) in edges {
+            writer.write_u32(source);
+            writer.write_u32(target);
+            writer.write_u32(edge_type);
+            writer.write_u32(weight.sources.len() as u32);
+            for source in &weight.sources {
+                // already interned above
+                writer.write_u32(strings.intern(&source.edge_source));
+                writer.write_u32(source.count);
+
+                let mut flags = 0;
+                if source.order.is_some() {
+                    flags |= EDGE_FLAG_ORDER;
+                }
+                if source.label.is_some() {
+                    flags |= EDGE_FLAG_LABEL;
+                }
+                writer.write_u8(flags);
+                if let Some(order) = source.order {
+                    writer.write_u32(order);
+                }
+                if let Some(label) = &source.label {
+                    writer.write_str(label);
+                }
+
+                writer.write_u32(source.attributes.len() as u32);
+                for (key, value) in &source.attributes {
+                    writer.write_str(key);
+                    writer.write_str(value);
+                }
+            }
+        }
+
+        writer.write_u32(self.transitive_rules.len() as u32);
+        for rule in &self.transitive_rules {
+            writer.write_str(rule.name_ref());
+            writer.write_u32(rule.iter_steps().count() as u32);
+            for step in rule.iter_steps() {
+                writer.write_str(&step.edge_type);
+                writer.write_u8(step.reversed as u8);
+            }
+            writer.write_str(rule.edge_type_ref());
+            writer.write_u8(rule.rounds());
+
+            let mut flags = 0;
+            if rule.can_loop() {
+                flags |= RULE_FLAG_CAN_LOOP;
+            }
+            if rule.close_reversed() {
+                flags |= RULE_FLAG_CLOSE_REVERSED;
+            }
+            if rule.closure() {
+                flags |= RULE_FLAG_CLOSURE;
+            }
+            writer.write_u8(flags);
+        }
+
+        let dynamic_edge_sources: Vec<(u32, &DynamicEdgeSource)> = self
+            .dynamic_edge_sources
+            .iter()
+            .filter_map(|edge_source| {
+                self.int_get_node_index(&edge_source.source)
+                    .map(|source| (node_positions[&source], edge_source))
+            })
+            .collect();
+
+        writer.write_u32(dynamic_edge_sources.len() as u32);
+        for (source, edge_source) in dynamic_edge_sources {
+            writer.write_u32(source);
+            writer.write_str(&edge_source.edge_type);
+            writer.write_str(&edge_source.edge_source);
+
+            match &edge_source.matcher {
+                NodeMatcher::PathRegex { pattern, flags, .. } => {
+                    writer.write_u8(MATCHER_KIND_PATH_REGEX);
+                    writer.write_str(pattern);
+                    writer.write_str(flags);
+                }
+                NodeMatcher::Folder { recurse } => {
+                    writer.write_u8(MATCHER_KIND_FOLDER);
+                    writer.write_u8(*recurse as u8);
+                }
+                NodeMatcher::Tag { tag, exact } => {
+                    writer.write_u8(MATCHER_KIND_TAG);
+                    writer.write_str(tag);
+                    writer.write_u8(*exact as u8);
+                }
+            }
+
+            let targets: Vec<u32> = edge_source
+                .targets
+                .iter()
+                .map(|target| node_positions[target])
+                .sorted()
+                .collect();
+            writer.write_u32(targets.len() as u32);
+            for target in targets {
+                writer.write_u32(target);
+            }
+        }
+
+        writer.data
+    }
+
+    /// Creates a graph from a snapshot created with [NoteGraph::to_snapshot].
+    /// The implied edges are rebuilt from the stored transitive rules.
+    ///
+    /// Will return an error if the snapshot is malformed or was created with a
+    /// different snapshot version.
+    pub fn from_snapshot(data: &[u8]) -> Result<NoteGraph> {
+        let mut reader = SnapshotReader::new(data);
+
+        if reader.read_bytes(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err(NoteGraphError::new(
+                "failed to read snapshot, data is not a graph snapshot",
+            ));
+        }
+
+        let version = reader.read_u16()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(NoteGraphError::new(&format!(
+                "failed to read snapshot, unsupported snapshot version {version}, expected version {SNAPSHOT_VERSION}"
+            )));
+        }
+
+        let revision = reader.read_u32()?;
+
+        let string_count = reader.read_u32()?;
+        let mut strings = Vec::new();
+        for _ in 0..string_count {
+            strings.push(reader.read_string()?);
+        }
+
+        let node_count = reader.read_u32()?;
+        let mut nodes = Vec::new();
+        for _ in 0..node_count {
+            let path = reader.read_string()?;
+            let alias_count = reader.read_u32()?;
+            let mut aliases = Vec::new();
+            for _ in 0..alias_count {
+                aliases.push(reader.read_string()?);
+            }
+            let flags = reader.read_u8()?;
+            let tag_count = reader.read_u32()?;
+            let mut tags = Vec::new();
+            for _ in 0..tag_count {
+                tags.push(reader.read_string()?);
+            }
+            let property_count = reader.read_u32()?;
+            let mut properties = BTreeMap::new();
+            for _ in 0..property_count {
+                let key = reader.read_string()?;
+                properties.insert(key, reader.read_property(0)?);
+            }
+
+            let mut node = GCNodeData::new(
+                path,
+                aliases,
+                flags & NODE_FLAG_RESOLVED != 0,
+                flags & NODE_FLAG_IGNORE_IN_EDGES != 0,
+                flags & NODE_FLAG_IGNORE_OUT_EDGES != 0,
+            );
+            node.tags = tags;
+            node.properties = properties;
+            nodes.push(node);
+        }
+
+        let edge_count = reader.read_u32()?;
+        let mut edges = Vec::new();
+        // edge sources that assert an edge more than once, their edges are only added
+        // once and the count is set after building the graph
+        let mut edge_source_counts = Vec::new();
+        for _ in 0..edge_count {
+            let source = reader.read_index(&nodes)?.path.clone();
+            let target = reader.read_index(&nodes)?.path.clone();
+            let edge_type = reader.read_index(&strings)?.clone();
+            let edge_source_count = reader.read_u32()?;
+            for _ in 0..edge_source_count {
+                let edge_source = reader.read_index(&strings)?.clone();
+                let count = reader.read_u32()?;
+                if count == 0 {
+                    return Err(NoteGraphError::new(
+                        "failed to read snapshot, edge source count is 0",
+                    ));
+                }
+                let mut edge = GCEdgeData::new(
+                    source.clone(),
+                    target.clone(),
+                    edge_type.clone(),
+                    edge_source,
+                );
+
+                let flags = reader.read_u8()?;
+                if flags & EDGE_FLAG_ORDER != 0 {
+                    edge.order = Some(reader.read_u32()?);
+                }
+                if flags & EDGE_FLAG_LABEL != 0 {
+                    edge.label = Some(reader.read_string()?);
+                }
+
+                let attribute_count = reader.read_u32()?;
+                for _ in 0..attribute_count {
+                    let key = reader.read_string()?;
+                    edge.attributes.insert(key, reader.read_string()?);
+                }
+
+                if count > 1 {
+                    edge_source_counts.push((
+                        edge.source.clone(),
+                        edge.target.clone(),
+                        edge.edge_type.clone(),
+                        edge.edge_source.clone(),
+                        count,
+                    ));
+                }
+                edges.push(edge);
+            }
+        }
+
+        let rule_count = reader.read_u32()?;
+        let mut rules = Vec::new();
+        for _ in 0..rule_count {
+            let name = reader.read_string()?;
+            let path_length = reader.read_u32()?;
+            let mut path = Vec::new();
+            for _ in 0..path_length {
+                let edge_type = reader.read_string()?;
+                let reversed = reader.read_u8()? != 0;
+                path.push(RuleStep::new(edge_type, reversed));
+            }
+            let edge_type = reader.read_string()?;
+            let rounds = reader.read_u8()?;
+            let flags = reader.read_u8()?;
+
+            let can_loop = flags & RULE_FLAG_CAN_LOOP != 0;
+            let close_reversed = flags & RULE_FLAG_CLOSE_REVERSED != 0;
+
+            rules.push(if flags & RULE_FLAG_CLOSURE != 0 {
+                TransitiveGraphRule::new_closure(name, path, edge_type, can_loop, close_reversed)
+            } else {
+                TransitiveGraphRule::from_steps(
+                    name,
+                    path,
+                    edge_type,
+                    rounds,
+                    can_loop,
+                    close_reversed,
+                )
+            });
+        }
+
+        let dynamic_edge_source_count = reader.read_u32()?;
+        let mut dynamic_edge_sources = Vec::new();
+        for _ in 0..dynamic_edge_source_count {
+            let source = reader.read_index(&nodes)?.path.clone();
+            let edge_type = reader.read_string()?;
+            let edge_source = reader.read_string()?;
+
+            let mut dynamic_edge_source = match reader.read_u8()? {
+                MATCHER_KIND_PATH_REGEX => {
+                    let pattern = reader.read_string()?;
+                    let flags = reader.read_string()?;
+                    DynamicEdgeSource::regex(source, pattern, flags, edge_type)?
+                }
+                MATCHER_KIND_FOLDER => {
+                    let recurse = reader.read_u8()? != 0;
+                    DynamicEdgeSource::folder(source, edge_type, recurse)
+                }
+                MATCHER_KIND_TAG => {
+                    let tag = reader.read_string()?;
+                    let exact = reader.read_u8()? != 0;
+                    DynamicEdgeSource::tag(source, tag, edge_type, exact)
+                }
+                kind => {
+                    return Err(NoteGraphError::new(&format!(
+                        "failed to read snapshot, unknown dynamic edge source kind {kind}"
+                    )))
+                }
+            };
+            dynamic_edge_source.edge_source = edge_source;
+
+            let target_count = reader.read_u32()?;
+            let mut targets = Vec::new();
+            for _ in 0..target_count {
+                targets.push(reader.read_index(&nodes)?.path.clone());
+            }
+
+            dynamic_edge_sources.push((dynamic_edge_source, targets));
+        }
+
+        if !reader.is_at_end() {
+            return Err(NoteGraphError::new(
+                "failed to read snapshot, unexpected data after the end of the snapshot",
+            ));
+        }
+
+        let mut graph = NoteGraph::new();
+        graph.build_graph(nodes, edges, rules)?;
+
+        for (source, target, edge_type, edge_source, count) in edge_source_counts {
+            graph.int_set_edge_source_count(&source, &target, &edge_type, &edge_source, count)?;
+        }
+
+        // the edges of the dynamic edge sources are part of the explicit edges, so
+        // the edge sources are restored as they were instead of being evaluated again
+        for (mut dynamic_edge_source, targets) in dynamic_edge_sources {
+            for target in targets {
+                if let Some(target) = graph.int_get_node_index(&target) {
+                    dynamic_edge_source.targets.insert(target);
+                }
+            }
+            graph.dynamic_edge_sources.push(dynamic_edge_source);
+        }
+        graph.int_invalidate_dynamic_edge_source_index();
+
+        graph.set_revision(revision);
+
+        Ok(graph)
+    }
+}
+
+impl NoteGraph {
+    /// Sets the number of times an edge source asserts an explicit edge.
+    fn int_set_edge_source_count(
+        &mut self,
+        source: &str,
+        target: &str,
+        edge_type: &str,
+        edge_source: &str,
+        count: u32,
+    ) -> Result<()> {
+        let source_data = self
+            .int_get_node_index(source)
+            .zip(self.int_get_node_index(target))
+            .and_then(|(source, target)| self.int_get_explicit_edge(source, target, edge_type))
+            .map(|edge| edge.id())
+            .and_then(|edge| self.graph.edge_weight_mut(edge))
+            .and_then(|edge_data| {
+                edge_data
+                    .sources
+                    .iter_mut()
+                    .find(|source_data| source_data.edge_source.as_ref() == edge_source)
+            })
+            .ok_or(NoteGraphError::new(
+                "failed to read snapshot, edge source of edge not found",
+            ))?;
+        source_data.count = count;
+
+        Ok(())
+    }
+}
+
+/// Deduplicates strings, so that they are only stored once in the snapshot.
+#[derive(Default)]
+struct StringTable {
+    strings: Vec<Rc<str>>,
+    indices: HashMap<Rc<str>, u32>,
+}
+
+impl StringTable {
+    fn intern(&mut self, string: &Rc<str>) -> u32 {
+        match self.indices.get(string) {
+            Some(index) => *index,
+            None => {
+                let index = self.strings.len() as u32;
+                self.strings.push(Rc::clone(string));
+                self.indices.insert(Rc::clone(string), index);
+                index
+            }
+        }
+    }
+}
+
+struct SnapshotWriter {
+    data: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    fn new() -> SnapshotWriter {
+        SnapshotWriter { data: Vec::new() }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_f64(&mut self, value: f64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_u32(value.len() as u32);
+        self.write_bytes(value.as_bytes());
+    }
+
+    /// Writes a property value as its kind (u8), followed by the value.
+    fn write_property(&mut self, value: &PropertyValue) {
+        match value {
+            PropertyValue::String(string) => {
+                self.write_u8(PROPERTY_KIND_STRING);
+                self.write_str(string);
+            }
+            PropertyValue::Number(number) => {
+                self.write_u8(PROPERTY_KIND_NUMBER);
+                self.write_f64(*number);
+            }
+            PropertyValue::Date(time) => {
+                self.write_u8(PROPERTY_KIND_DATE);
+                self.write_f64(*time);
+            }
+            PropertyValue::Bool(boolean) => {
+                self.write_u8(PROPERTY_KIND_BOOL);
+                self.write_u8(*boolean as u8);
+            }
+            PropertyValue::List(list) => {
+                self.write_u8(PROPERTY_KIND_LIST);
+                self.write_u32(list.len() as u32);
+                for item in list {
+                    self.write_property(item);
+                }
+            }
+        }
+    }
+}
+
+struct SnapshotReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn new(data: &'a [u8]) -> SnapshotReader<'a> {
+        SnapshotReader { data, position: 0 }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(length)
+            .ok_or(NoteGraphError::new(
+                "failed to read snapshot, length out of range",
+            ))?;
+        let bytes = self
+            .data
+            .get(self.position..end)
+            .ok_or(NoteGraphError::new(
+                "failed to read snapshot, unexpected end of data",
+            ))?;
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        let bytes = self.read_bytes(8)?;
+        Ok(f64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Reads a property value. `depth` is the number of lists the value is
+    /// nested in.
+    fn read_property(&mut self, depth: usize) -> Result<PropertyValue> {
+        match self.read_u8()? {
+            PROPERTY_KIND_STRING => Ok(PropertyValue::String(self.read_string()?)),
+            PROPERTY_KIND_NUMBER => Ok(PropertyValue::Number(self.read_f64()?)),
+            PROPERTY_KIND_DATE => Ok(PropertyValue::Date(self.read_f64()?)),
+            PROPERTY_KIND_BOOL => Ok(PropertyValue::Bool(self.read_u8()? != 0)),
+            PROPERTY_KIND_LIST => {
+                if depth >= MAX_PROPERTY_DEPTH {
+                    return Err(NoteGraphError::new(
+                        "failed to read snapshot, property lists are nested too deeply",
+                    ));
+                }
+
+                let length = self.read_u32()?;
+                let mut list = Vec::new();
+                for _ in 0..length {
+                    list.push(self.read_property(depth + 1)?);
+                }
+                Ok(PropertyValue::List(list))
+            }
+            kind => Err(NoteGraphError::new(&format!(
+                "failed to read snapshot, unknown property kind {kind}"
+            ))),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let length = self.read_u32()? as usize;
+        let bytes = self.read_bytes(length)?;
+
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| NoteGraphError::new("failed to read snapshot, string is not valid UTF-8"))
+    }
+
+    /// Reads an index and returns the element at that index of `list`.
+    fn read_index<'b, T>(&mut self, list: &'b [T]) -> Result<&'b T> {
+        let index = self.read_u32()? as usize;
+
+        list.get(index).ok_or(NoteGraphError::new(
+            "failed to read snapshot, index out of bounds",
+        ))
+    }
+}
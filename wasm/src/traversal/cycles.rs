@@ -0,0 +1,314 @@
+use std::rc::Rc;
+
+use hashbrown::{HashMap, HashSet};
+use petgraph::{
+    algo::tarjan_scc,
+    stable_graph::{NodeIndex, StableGraph},
+    visit::{EdgeFiltered, EdgeRef, NodeFiltered},
+    Directed,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{edge_struct::EdgeStruct, NGEdgeRef, NGNodeIndex},
+    graph::{edge_matches_edge_filter, NoteGraph},
+    traversal::path::{Path, PathList},
+};
+
+/// A strongly connected component as its own graph, so that nodes can be
+/// removed from it while enumerating its cycles. The edges are the edges of
+/// the note graph that connect nodes of the component.
+type ComponentGraph = StableGraph<NGNodeIndex, EdgeStruct, Directed, u32>;
+/// The outgoing edges of a node in a component graph, with their targets.
+type ComponentEdges<'a> = Vec<(NodeIndex, &'a EdgeStruct)>;
+
+#[wasm_bindgen]
+impl NoteGraph {
+    /// Finds all elementary cycles that only consist of edges of the given
+    /// edge types. If `edge_types` is undefined, all edge types are
+    /// considered. Implied edges are only considered if
+    /// `include_implied_edges` is true.
+    ///
+    /// The cycles are sorted by length. If more than `max_traversal_count`
+    /// edges are visited, the search stops and the path list is marked as
+    /// truncated.
+    pub fn find_cycles(
+        &self,
+        edge_types: Option<Vec<String>>,
+        include_implied_edges: bool,
+        max_traversal_count: u32,
+    ) -> PathList {
+        let components =
+            self.find_cycle_components(edge_types, include_implied_edges, max_traversal_count);
+        let truncated = components.iter().any(|component| component.truncated());
+
+        let mut cycles: Vec<Path> = components
+            .into_iter()
+            .flat_map(|component| component.to_paths())
+            .collect();
+
+        cycles.sort_by_key(|cycle| cycle.length());
+
+        PathList::new_truncated(cycles, truncated)
+    }
+
+    /// Finds all strongly connected components that contain a cycle, only
+    /// following edges of the given edge types. If `edge_types` is undefined,
+    /// all edge types are considered. Implied edges are only considered if
+    /// `include_implied_edges` is true.
+    ///
+    /// Each component is returned as a list of its elementary cycles, sorted by
+    /// length. If more than `max_traversal_count` edges are visited, the search
+    /// stops and the components whose cycles were not all found are marked as
+    /// truncated.
+    pub fn find_cycle_components(
+        &self,
+        edge_types: Option<Vec<String>>,
+        include_implied_edges: bool,
+        max_traversal_count: u32,
+    ) -> Vec<PathList> {
+        let edge_types: Option<Vec<Rc<str>>> =
+            edge_types.map(|edge_types| edge_types.into_iter().map(Rc::from).collect());
+
+        self.int_find_cycle_components(
+            edge_types.as_ref(),
+            include_implied_edges,
+            max_traversal_count,
+        )
+    }
+}
+
+impl NoteGraph {
+    /// Finds the strongly connected components with cycles and enumerates their
+    /// elementary cycles.
+    pub fn int_find_cycle_components(
+        &self,
+        edge_types: Option<&Vec<Rc<str>>>,
+        include_implied_edges: bool,
+        max_traversal_count: u32,
+    ) -> Vec<PathList> {
+        let edge_filter =
+            |edge: NGEdgeRef| self.int_is_cycle_edge(edge, edge_types, include_implied_edges);
+
+        let mut components: Vec<Vec<NGNodeIndex>> =
+            tarjan_scc(&EdgeFiltered::from_fn(&self.graph, edge_filter))
+                .into_iter()
+                .filter(|component| {
+                    component.len() > 1
+                        || self
+                            .int_iter_traversable_outgoing_edges(component[0])
+                            .any(|edge| edge.target() == component[0] && edge_filter(edge))
+                })
+                .collect();
+
+        // sort the components so that the result is deterministic
+        for component in &mut components {
+            component.sort();
+        }
+        components.sort();
+
+        let mut traversal_count = 0;
+
+        components
+            .into_iter()
+            .map(|component| {
+                let component_graph = self.int_component_graph(&component, edge_filter);
+
+                let mut cycles = Vec::new();
+                let complete = traversal_count < max_traversal_count
+                    && find_cycles_in_component(
+                        component_graph,
+                        &mut cycles,
+                        &mut traversal_count,
+                        max_traversal_count,
+                    );
+                cycles.sort_by_key(|cycle| cycle.length());
+
+                PathList::new_truncated(cycles, !complete)
+            })
+            .collect()
+    }
+
+    /// Copies a strongly connected component and the edges between its nodes
+    /// into its own graph. The nodes are added in the order of the component.
+    fn int_component_graph(
+        &self,
+        component: &[NGNodeIndex],
+        edge_filter: impl Fn(NGEdgeRef) -> bool,
+    ) -> ComponentGraph {
+        let mut component_graph = ComponentGraph::default();
+        let component_nodes: HashMap<NGNodeIndex, NodeIndex> = component
+            .iter()
+            .map(|&node| (node, component_graph.add_node(node)))
+            .collect();
+
+        for &node in component {
+            for edge in self.int_iter_traversable_outgoing_edges(node) {
+                let Some(&target) = component_nodes.get(&edge.target()) else {
+                    continue;
+                };
+                if !edge_filter(edge) {
+                    continue;
+                }
+
+                component_graph.add_edge(
+                    component_nodes[&node],
+                    target,
+                    EdgeStruct::new(
+                        node,
+                        edge.target(),
+                        edge.id(),
+                        edge.weight().edge_type.clone(),
+                        self.get_revision(),
+                    ),
+                );
+            }
+        }
+
+        component_graph
+    }
+
+    fn int_is_cycle_edge(
+        &self,
+        edge: NGEdgeRef,
+        edge_types: Option<&Vec<Rc<str>>>,
+        include_implied_edges: bool,
+    ) -> bool {
+        let edge_data = edge.weight();
+
+        (include_implied_edges || edge_data.explicit)
+            && edge_matches_edge_filter(edge_data, edge_types)
+            && self.int_is_edge_traversable(edge.source(), edge.target())
+    }
+}
+
+/// Enumerates the elementary cycles of a strongly connected component using
+/// Johnson's algorithm.
+///
+/// The cycles through the smallest node of a component are found with a
+/// depth first search from that node. Nodes from which the search did not get
+/// back to the start node are blocked until one of their successors is
+/// unblocked, so that dead ends are not searched again. Then the start node is
+/// removed and the same is done for the strongly connected components of the
+/// rest of the component.
+///
+/// Returns false if the search was stopped because more than
+/// `max_traversal_count` edges were visited.
+fn find_cycles_in_component(
+    mut graph: ComponentGraph,
+    cycles: &mut Vec<Path>,
+    traversal_count: &mut u32,
+    max_traversal_count: u32,
+) -> bool {
+    let mut components: Vec<Vec<NodeIndex>> = vec![graph.node_indices().collect()];
+
+    while let Some(component) = components.pop() {
+        let component_nodes: HashSet<NodeIndex> = component.iter().copied().collect();
+        let Some(&start_node) = component.iter().min() else {
+            continue;
+        };
+
+        if !find_cycles_through_node(
+            &graph,
+            &component_nodes,
+            start_node,
+            cycles,
+            traversal_count,
+            max_traversal_count,
+        ) {
+            return false;
+        }
+
+        graph.remove_node(start_node);
+
+        let remaining_graph = NodeFiltered::from_fn(&graph, |node| component_nodes.contains(&node));
+        // push the components in reverse, so that they are searched in order
+        components.extend(
+            tarjan_scc(&remaining_graph)
+                .into_iter()
+                .filter(|component| {
+                    component.len() > 1 || graph.contains_edge(component[0], component[0])
+                })
+                .rev(),
+        );
+    }
+
+    true
+}
+
+/// The depth first search of Johnson's algorithm, which finds the cycles
+/// through the start node that only visit nodes of the component. We use a
+/// manual stack instead of recursion, so that long cycles don't overflow the
+/// call stack.
+fn find_cycles_through_node(
+    graph: &ComponentGraph,
+    component_nodes: &HashSet<NodeIndex>,
+    start_node: NodeIndex,
+    cycles: &mut Vec<Path>,
+    traversal_count: &mut u32,
+    max_traversal_count: u32,
+) -> bool {
+    let next_edges = |node: NodeIndex| -> ComponentEdges {
+        graph
+            .edges(node)
+            .filter(|edge| component_nodes.contains(&edge.target()))
+            .map(|edge| (edge.target(), edge.weight()))
+            .collect()
+    };
+
+    let mut path: Vec<EdgeStruct> = Vec::new();
+    let mut blocked: HashSet<NodeIndex> = HashSet::new();
+    // the nodes to unblock when the key node is unblocked
+    let mut blocked_by: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
+    // the node, its remaining edges and whether a cycle was found from it
+    let mut stack: Vec<(NodeIndex, ComponentEdges, bool)> = Vec::new();
+
+    blocked.insert(start_node);
+    stack.push((start_node, next_edges(start_node), false));
+
+    while let Some((_, edges, found_cycle)) = stack.last_mut() {
+        if let Some((target, edge)) = edges.pop() {
+            if *traversal_count >= max_traversal_count {
+                return false;
+            }
+            *traversal_count += 1;
+
+            if target == start_node {
+                let mut cycle = path.clone();
+                cycle.push(edge.clone());
+                cycles.push(Path::new(cycle));
+                *found_cycle = true;
+            } else if !blocked.contains(&target) {
+                path.push(edge.clone());
+                blocked.insert(target);
+                stack.push((target, next_edges(target), false));
+            }
+            continue;
+        }
+
+        let (node, _, found_cycle) = stack.pop().unwrap();
+        path.pop();
+
+        if found_cycle {
+            // the node may lead to more cycles on a different path, so we unblock it
+            // and all nodes that were blocked because of it
+            let mut to_unblock = vec![node];
+            while let Some(node) = to_unblock.pop() {
+                if blocked.remove(&node) {
+                    to_unblock.extend(blocked_by.remove(&node).into_iter().flatten());
+                }
+            }
+
+            if let Some((_, _, parent_found_cycle)) = stack.last_mut() {
+                *parent_found_cycle = true;
+            }
+        } else {
+            // the node stays blocked until one of its successors is unblocked
+            for (target, _) in next_edges(node) {
+                blocked_by.entry(target).or_default().insert(node);
+            }
+        }
+    }
+
+    true
+}
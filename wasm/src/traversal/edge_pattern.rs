@@ -0,0 +1,400 @@
+use std::{iter::Peekable, rc::Rc, str::CharIndices};
+
+use wasm_bindgen::prelude::*;
+
+use crate::utils::{NoteGraphError, Result};
+
+/// A set of automaton states, sorted and without duplicates.
+pub type EdgePatternStates = Vec<usize>;
+
+/// A regex-like pattern over sequences of edge types, e.g. `up+ same` for
+/// "follow `up` one or more times, then exactly one `same`".
+///
+/// The syntax is
+/// - `up`, `"edge type"`: an edge of this type, quotes allow any characters,
+///   inside quotes `\"` and `\\` stand for `"` and `\`
+/// - `.`: an edge of any type
+/// - `a b` or `a, b`: `a` followed by `b`
+/// - `a | b`: `a` or `b`
+/// - `a*`, `a+`, `a?`: zero or more, one or more, zero or one times `a`
+/// - `(a b)`: grouping
+///
+/// The pattern is compiled into a nondeterministic automaton, which is
+/// simulated during a traversal.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct EdgeTypePattern {
+    pattern: String,
+    states: Vec<PatternState>,
+    start: usize,
+    accept: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+struct PatternState {
+    /// transitions that consume an edge
+    transitions: Vec<(EdgeMatcher, usize)>,
+    /// transitions that don't consume an edge
+    epsilon: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum EdgeMatcher {
+    Any,
+    EdgeType(Rc<str>),
+}
+
+impl EdgeMatcher {
+    fn matches(&self, edge_type: &str) -> bool {
+        match self {
+            EdgeMatcher::Any => true,
+            EdgeMatcher::EdgeType(t) => t.as_ref() == edge_type,
+        }
+    }
+}
+
+/// A part of the automaton with a single start and end state.
+#[derive(Clone, Copy)]
+struct Fragment {
+    start: usize,
+    end: usize,
+}
+
+#[wasm_bindgen]
+impl EdgeTypePattern {
+    /// Parses an edge type pattern.
+    ///
+    /// Will return an error if the pattern is malformed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(pattern: String) -> Result<EdgeTypePattern> {
+        let mut builder = PatternBuilder {
+            states: Vec::new(),
+            chars: pattern.char_indices().peekable(),
+        };
+
+        let fragment = builder.parse_alternation()?;
+        builder.skip_separators();
+        if let Some((position, c)) = builder.chars.next() {
+            return Err(NoteGraphError::new(&format!(
+                "failed to parse edge type pattern, unexpected \"{c}\" at position {position}"
+            )));
+        }
+
+        let states = builder.states;
+
+        Ok(EdgeTypePattern {
+            pattern,
+            states,
+            start: fragment.start,
+            accept: fragment.end,
+        })
+    }
+
+    /// Creates a pattern that matches exactly the given sequence of edge
+    /// types, like the path of a transitive rule.
+    pub fn from_sequence(edge_types: Vec<String>) -> EdgeTypePattern {
+        let edge_types: Vec<Rc<str>> = edge_types.into_iter().map(Rc::from).collect();
+
+        EdgeTypePattern::int_from_sequence(edge_types.iter())
+    }
+
+    /// Checks if the pattern matches a whole sequence of edge types.
+    pub fn matches(&self, edge_types: Vec<String>) -> bool {
+        let mut states = self.start_states();
+
+        for edge_type in edge_types {
+            states = self.step(&states, &edge_type);
+            if states.is_empty() {
+                return false;
+            }
+        }
+
+        self.is_accepting(&states)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        self.pattern.clone()
+    }
+}
+
+impl EdgeTypePattern {
+    /// Creates a pattern that matches exactly the given sequence of edge
+    /// types.
+    pub fn int_from_sequence<'a>(edge_types: impl Iterator<Item = &'a Rc<str>>) -> EdgeTypePattern {
+        let mut pattern = Vec::new();
+        let mut states = vec![PatternState::default()];
+
+        for edge_type in edge_types {
+            pattern.push(quote_edge_type(edge_type));
+
+            let next = states.len();
+            states
+                .last_mut()
+                .unwrap()
+                .transitions
+                .push((EdgeMatcher::EdgeType(edge_type.clone()), next));
+            states.push(PatternState::default());
+        }
+
+        EdgeTypePattern {
+            pattern: pattern.join(" "),
+            start: 0,
+            accept: states.len() - 1,
+            states,
+        }
+    }
+
+    /// The states before any edge was followed.
+    pub fn start_states(&self) -> EdgePatternStates {
+        self.closure(vec![self.start])
+    }
+
+    /// The states after following an edge of the given type. If the result is
+    /// empty, the pattern does not allow following the edge.
+    pub fn step(&self, states: &[usize], edge_type: &str) -> EdgePatternStates {
+        let mut next = Vec::new();
+
+        for &state in states {
+            for (matcher, target) in &self.states[state].transitions {
+                if matcher.matches(edge_type) {
+                    next.push(*target);
+                }
+            }
+        }
+
+        self.closure(next)
+    }
+
+    /// Whether a sequence of edges that lead to these states matches the
+    /// pattern.
+    pub fn is_accepting(&self, states: &[usize]) -> bool {
+        states.binary_search(&self.accept).is_ok()
+    }
+
+    /// Adds all states reachable via epsilon transitions.
+    fn closure(&self, mut states: Vec<usize>) -> EdgePatternStates {
+        let mut stack = states.clone();
+
+        while let Some(state) = stack.pop() {
+            for &next in &self.states[state].epsilon {
+                if !states.contains(&next) {
+                    states.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+}
+
+/// A recursive descent parser, that builds the automaton using Thompson's
+/// construction.
+struct PatternBuilder<'a> {
+    states: Vec<PatternState>,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl PatternBuilder<'_> {
+    fn add_state(&mut self) -> usize {
+        self.states.push(PatternState::default());
+        self.states.len() - 1
+    }
+
+    fn add_epsilon(&mut self, from: usize, to: usize) {
+        self.states[from].epsilon.push(to);
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .chars
+            .next_if(|(_, c)| c.is_whitespace() || *c == ',')
+            .is_some()
+        {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn parse_alternation(&mut self) -> Result<Fragment> {
+        let mut fragment = self.parse_sequence()?;
+
+        self.skip_whitespace();
+        while self.chars.next_if(|(_, c)| *c == '|').is_some() {
+            let other = self.parse_sequence()?;
+
+            let start = self.add_state();
+            let end = self.add_state();
+            self.add_epsilon(start, fragment.start);
+            self.add_epsilon(start, other.start);
+            self.add_epsilon(fragment.end, end);
+            self.add_epsilon(other.end, end);
+
+            fragment = Fragment { start, end };
+            self.skip_whitespace();
+        }
+
+        Ok(fragment)
+    }
+
+    fn parse_sequence(&mut self) -> Result<Fragment> {
+        let mut fragment: Option<Fragment> = None;
+
+        loop {
+            self.skip_separators();
+            match self.peek() {
+                None | Some('|') | Some(')') => break,
+                _ => {}
+            }
+
+            let next = self.parse_repetition()?;
+            fragment = Some(match fragment {
+                Some(fragment) => {
+                    self.add_epsilon(fragment.end, next.start);
+                    Fragment {
+                        start: fragment.start,
+                        end: next.end,
+                    }
+                }
+                None => next,
+            });
+        }
+
+        match fragment {
+            Some(fragment) => Ok(fragment),
+            None => Err(NoteGraphError::new(&format!(
+                "failed to parse edge type pattern, expected an edge type {}",
+                self.position_description()
+            ))),
+        }
+    }
+
+    fn parse_repetition(&mut self) -> Result<Fragment> {
+        let mut fragment = self.parse_atom()?;
+
+        while let Some((_, operator)) = self.chars.next_if(|(_, c)| matches!(c, '*' | '+' | '?')) {
+            let start = self.add_state();
+            let end = self.add_state();
+            self.add_epsilon(start, fragment.start);
+            self.add_epsilon(fragment.end, end);
+
+            // zero times
+            if operator != '+' {
+                self.add_epsilon(start, end);
+            }
+            // more than one time
+            if operator != '?' {
+                self.add_epsilon(fragment.end, fragment.start);
+            }
+
+            fragment = Fragment { start, end };
+        }
+
+        Ok(fragment)
+    }
+
+    fn parse_atom(&mut self) -> Result<Fragment> {
+        match self.peek() {
+            Some('(') => {
+                self.chars.next();
+                let fragment = self.parse_alternation()?;
+                self.skip_separators();
+
+                match self.chars.next() {
+                    Some((_, ')')) => Ok(fragment),
+                    _ => Err(NoteGraphError::new(
+                        "failed to parse edge type pattern, missing closing parenthesis",
+                    )),
+                }
+            }
+            Some('.') => {
+                self.chars.next();
+                Ok(self.add_matcher(EdgeMatcher::Any))
+            }
+            Some('"') => {
+                self.chars.next();
+                let mut edge_type = String::new();
+
+                loop {
+                    match self.chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) if matches!(self.peek(), Some('"' | '\\')) => {
+                            edge_type.push(self.chars.next().unwrap().1);
+                        }
+                        Some((_, c)) => edge_type.push(c),
+                        None => {
+                            return Err(NoteGraphError::new(
+                                "failed to parse edge type pattern, missing closing quote",
+                            ))
+                        }
+                    }
+                }
+
+                Ok(self.add_matcher(EdgeMatcher::EdgeType(Rc::from(edge_type))))
+            }
+            _ => {
+                let mut edge_type = String::new();
+
+                while let Some((_, c)) = self.chars.next_if(|(_, c)| is_unquoted_char(*c)) {
+                    edge_type.push(c);
+                }
+
+                if edge_type.is_empty() {
+                    return Err(NoteGraphError::new(&format!(
+                        "failed to parse edge type pattern, expected an edge type {}",
+                        self.position_description()
+                    )));
+                }
+
+                Ok(self.add_matcher(EdgeMatcher::EdgeType(Rc::from(edge_type))))
+            }
+        }
+    }
+
+    fn add_matcher(&mut self, matcher: EdgeMatcher) -> Fragment {
+        let start = self.add_state();
+        let end = self.add_state();
+        self.states[start].transitions.push((matcher, end));
+
+        Fragment { start, end }
+    }
+
+    fn position_description(&mut self) -> String {
+        match self.chars.peek() {
+            Some((position, c)) => format!("at position {position}, found \"{c}\""),
+            None => "at the end of the pattern".to_string(),
+        }
+    }
+}
+
+/// Whether a character may be part of an edge type without quotes.
+fn is_unquoted_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '|' | '*' | '+' | '?' | ',' | '.' | '"')
+}
+
+/// Writes an edge type so that the pattern parser reads it back as the same
+/// edge type, quoting it only if needed.
+fn quote_edge_type(edge_type: &str) -> String {
+    if !edge_type.is_empty() && edge_type.chars().all(is_unquoted_char) {
+        return edge_type.to_owned();
+    }
+
+    let mut quoted = String::from('"');
+    for c in edge_type.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+
+    quoted
+}
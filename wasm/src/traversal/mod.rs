@@ -11,17 +11,98 @@ use crate::{
         NGEdgeIndex, NGEdgeRef, NGNodeIndex,
     },
     graph::{edge_matches_edge_filter, NoteGraph},
-    traversal::options::{TraversalOptions, TraversalPostprocessOptions},
+    traversal::{
+        edge_pattern::EdgePatternStates,
+        node_filter::NodeFilter,
+        options::{TraversalOptions, TraversalPostprocessOptions},
+    },
     utils::{
         BreadthFirstTraversalDataStructure, DepthFirstTraversalDataStructure,
         GraphTraversalDataStructure, NoteGraphError, Result,
     },
 };
 
+pub mod cycles;
+pub mod edge_pattern;
+pub mod node_filter;
 pub mod options;
 pub mod path;
+pub mod shortest_path;
+
+/// The mutable state of a recursive traversal.
+struct RecTraversalState {
+    /// the number of edges traversed so far
+    traversal_count: u32,
+    /// whether the traversal was stopped because it exceeded the max traversal
+    /// count
+    truncated: bool,
+    /// the nodes on the path from the entry node to the current node
+    ancestors: Vec<NGNodeIndex>,
+    /// the states of the edge pattern automaton for the path from the entry
+    /// node to the current node, only used if the options set an edge pattern
+    pattern_states: Vec<EdgePatternStates>,
+}
 
-const TRAVERSAL_COUNT_LIMIT: u32 = 10_000;
+impl RecTraversalState {
+    fn new() -> RecTraversalState {
+        RecTraversalState {
+            traversal_count: 0,
+            truncated: false,
+            ancestors: Vec::new(),
+            pattern_states: Vec::new(),
+        }
+    }
+
+    /// Counts a traversed edge. Returns false and marks the traversal as
+    /// truncated if the max traversal count is exceeded.
+    fn count_traversal(&mut self, options: &TraversalOptions) -> bool {
+        if self.traversal_count >= options.max_traversal_count {
+            self.truncated = true;
+            return false;
+        }
+
+        self.traversal_count += 1;
+        true
+    }
+
+    /// Advances the edge pattern automaton along an edge. Returns None if the
+    /// pattern does not allow following the edge. If the options don't set an
+    /// edge pattern, every edge may be followed.
+    fn step_pattern(
+        &self,
+        options: &TraversalOptions,
+        edge_type: &str,
+    ) -> Option<EdgePatternStates> {
+        let Some(pattern) = &options.edge_pattern else {
+            return Some(Vec::new());
+        };
+
+        let states = pattern.step(self.pattern_states.last()?, edge_type);
+        if states.is_empty() {
+            None
+        } else {
+            Some(states)
+        }
+    }
+}
+
+/// Whether a branch of a traversal with an edge pattern should be kept. A
+/// branch is kept if the path to its root matches the pattern, or if it
+/// contains a match further down.
+fn keep_pattern_branch(
+    options: &TraversalOptions,
+    states: &[usize],
+    branch: &TraversalData,
+) -> bool {
+    match &options.edge_pattern {
+        Some(pattern) => {
+            pattern.is_accepting(states)
+                || !branch.children.is_empty()
+                || branch.has_cut_of_children
+        }
+        None => true,
+    }
+}
 
 pub type NodeVec<T> = Vec<(NGNodeIndex, T)>;
 pub type EdgeVec<T> = Vec<(NGEdgeIndex, T)>;
@@ -30,27 +111,48 @@ pub type NodeEdgeVec<N, E> = (NodeVec<N>, EdgeVec<E>);
 #[wasm_bindgen]
 impl NoteGraph {
     /// Runs a recursive traversal of the graph.
+    /// If the traversal visits more than `max_traversal_count` edges, it stops
+    /// and the partial result is marked as truncated. Since the traversal is
+    /// depth first, the result then only contains the first siblings of the
+    /// last visited level, see [TraversalOptions::max_traversal_count].
     pub fn rec_traverse(&self, options: TraversalOptions) -> Result<TraversalResult> {
         let now = Instant::now();
 
         let mut result = Vec::new();
-        let mut traversal_count = 0;
+        let mut state = RecTraversalState::new();
 
         let edge_types = options.edge_types_as_rcs().unwrap_or(self.int_edge_types());
+        let start_states = options
+            .edge_pattern
+            .as_ref()
+            .map(|pattern| pattern.start_states())
+            .unwrap_or_default();
 
-        for entry_node in &options.entry_nodes {
+        'entry_nodes: for entry_node in &options.entry_nodes {
             let start_node = self
                 .int_get_node_index(entry_node)
                 .ok_or(NoteGraphError::new(&format!(
                     "Node \"{entry_node}\" not found"
                 )))?;
 
-            for edge in self.graph.edges(start_node) {
-                if !edge_matches_edge_filter(edge.weight(), Some(&edge_types)) {
+            for (edge, target) in
+                self.int_iter_traversable_edges_directed(start_node, options.direction)
+            {
+                if !edge_matches_edge_filter(edge.weight(), Some(&edge_types))
+                    || !options.node_passes_filter(self, target)
+                {
                     continue;
                 }
 
-                let target = edge.target();
+                state.pattern_states = vec![start_states.clone()];
+                let Some(next_states) = state.step_pattern(&options, &edge.weight().edge_type)
+                else {
+                    continue;
+                };
+
+                if !state.count_traversal(&options) {
+                    break 'entry_nodes;
+                }
 
                 let edge_struct = EdgeStruct::new(
                     start_node,
@@ -60,38 +162,49 @@ impl NoteGraph {
                     self.get_revision(),
                 );
 
-                traversal_count += 1;
+                if options.detect_cycles && target == start_node {
+                    let back_reference = TraversalData::new_cycle_back_reference(edge_struct, 1);
+                    if keep_pattern_branch(&options, &next_states, &back_reference) {
+                        result.push(back_reference);
+                    }
+                    continue;
+                }
+
+                state.ancestors = vec![start_node];
+                state.pattern_states.push(next_states.clone());
 
-                if options.separate_edges {
-                    result.push(self.int_rec_traverse(
+                let branch = if options.separate_edges {
+                    self.int_rec_traverse(
                         target,
                         edge_struct.clone(),
                         Some(&vec![edge_struct.edge_type]),
                         1,
-                        options.max_depth,
-                        &mut traversal_count,
-                        options.max_traversal_count,
-                    )?);
+                        &options,
+                        &mut state,
+                    )
                 } else {
-                    result.push(self.int_rec_traverse(
+                    self.int_rec_traverse(
                         target,
                         edge_struct,
                         Some(&edge_types),
                         1,
-                        options.max_depth,
-                        &mut traversal_count,
-                        options.max_traversal_count,
-                    )?);
+                        &options,
+                        &mut state,
+                    )
+                };
+
+                if keep_pattern_branch(&options, &next_states, &branch) {
+                    result.push(branch);
                 }
             }
         }
 
         let total_elapsed = now.elapsed();
 
-        Ok(TraversalResult::new(
-            result,
-            total_elapsed.as_millis() as u64,
-        ))
+        let mut result = TraversalResult::new(result, total_elapsed.as_millis() as u64);
+        result.truncated = state.truncated;
+
+        Ok(result)
     }
 
     /// Runs a recursive traversal of the graph and post-processes the result.
@@ -118,71 +231,109 @@ impl NoteGraph {
 
 impl NoteGraph {
     /// Recursively traverses the graph using DFS and builds a tree structure.
-    ///
-    /// Will return an error if the node weight for any node along the traversal
-    /// is not found.
+    /// Edges are followed in the direction set in the options. The edge
+    /// structs go from the node closer to the entry node to the node further
+    /// away, so for incoming edges they point against the edge direction.
+    /// If `detect_cycles` is set in the options, nodes that already appear on
+    /// the path from the entry node are not descended into again, but marked
+    /// as cycle back references.
+    /// If the options set an edge pattern, only edges allowed by the pattern
+    /// are followed and branches without a match are dropped. Nodes that
+    /// don't pass the node filter are skipped.
     fn int_rec_traverse(
         &self,
         node: NGNodeIndex,
         edge: EdgeStruct,
         edge_types: Option<&Vec<Rc<str>>>,
         depth: u32,
-        max_depth: u32,
-        traversal_count: &mut u32,
-        max_traversal_count: u32,
-    ) -> Result<TraversalData> {
+        options: &TraversalOptions,
+        state: &mut RecTraversalState,
+    ) -> TraversalData {
         let mut new_children = Vec::new();
-        let stop_traversal = depth >= max_depth || *traversal_count >= max_traversal_count;
+        let mut stop_traversal =
+            depth >= options.max_depth || state.traversal_count >= options.max_traversal_count;
 
         if !stop_traversal {
-            for outgoing_edge in self.graph.edges(node) {
-                let edge_data = outgoing_edge.weight();
-
-                if edge_matches_edge_filter(edge_data, edge_types) {
-                    let target = outgoing_edge.target();
+            state.ancestors.push(node);
+
+            for (next_edge, target) in
+                self.int_iter_traversable_edges_directed(node, options.direction)
+            {
+                let edge_data = next_edge.weight();
+
+                if edge_matches_edge_filter(edge_data, edge_types)
+                    && options.node_passes_filter(self, target)
+                {
+                    let Some(next_states) = state.step_pattern(options, &edge_data.edge_type)
+                    else {
+                        continue;
+                    };
+
+                    if !state.count_traversal(options) {
+                        stop_traversal = true;
+                        break;
+                    }
 
                     // assert!(*self.int_get_node_weight(node).unwrap() == edge.target);
 
                     let edge_struct = EdgeStruct::new(
                         edge.target_index,
                         target,
-                        outgoing_edge.id(),
+                        next_edge.id(),
                         edge_data.edge_type.clone(),
                         self.get_revision(),
                     );
 
-                    *traversal_count += 1;
-
-                    if *traversal_count > TRAVERSAL_COUNT_LIMIT {
-                        return Err(NoteGraphError::new("Traversal exceeded limit of 10,000 nodes. Try decreasing the max depth."));
+                    if options.detect_cycles && state.ancestors.contains(&target) {
+                        let back_reference =
+                            TraversalData::new_cycle_back_reference(edge_struct, depth + 1);
+                        if keep_pattern_branch(options, &next_states, &back_reference) {
+                            new_children.push(back_reference);
+                        }
+                        continue;
                     }
 
-                    new_children.push(self.int_rec_traverse(
+                    state.pattern_states.push(next_states);
+                    let child = self.int_rec_traverse(
                         target,
                         edge_struct,
                         edge_types,
                         depth + 1,
-                        max_depth,
-                        traversal_count,
-                        max_traversal_count,
-                    )?)
+                        options,
+                        state,
+                    );
+                    let next_states = state.pattern_states.pop().unwrap_or_default();
+
+                    if keep_pattern_branch(options, &next_states, &child) {
+                        new_children.push(child);
+                    }
+
+                    if state.truncated {
+                        break;
+                    }
                 }
             }
+
+            state.ancestors.pop();
         }
 
-        Ok(TraversalData::new(
-            edge,
-            depth,
-            0,
-            new_children,
-            stop_traversal,
-        ))
+        TraversalData::new(edge, depth, 0, new_children, stop_traversal)
     }
 
+    /// Traverses the graph breadth first, visiting each node and edge once.
+    /// Fails if the options set an edge pattern, since a node can be reached
+    /// by paths in different states of the pattern. Use
+    /// [NoteGraph::rec_traverse] for edge patterns.
     pub fn int_traverse_basic(
         &self,
         options: &TraversalOptions,
     ) -> Result<NodeEdgeVec<u32, NGEdgeRef<'_>>> {
+        if options.edge_pattern.is_some() {
+            return Err(NoteGraphError::new(
+                "failed to traverse, edge patterns are only supported by the recursive traversal",
+            ));
+        }
+
         let entry_nodes = options.indices_of_entry_nodes(self)?;
         let opt_edge_types = options.edge_types_as_rcs();
 
@@ -197,7 +348,7 @@ impl NoteGraph {
                 let (nodes, edges) = self.int_traverse_breadth_first(
                     entry_nodes.clone(),
                     Some(&vec![edge_type]),
-                    options.max_depth,
+                    options,
                     |_, depth| depth,
                     |edge| edge,
                 );
@@ -211,13 +362,25 @@ impl NoteGraph {
             Ok(self.int_traverse_breadth_first(
                 entry_nodes,
                 opt_edge_types.as_ref(),
-                options.max_depth,
+                options,
                 |_, depth| depth,
                 |edge| edge,
             ))
         }
     }
 
+    /// Checks if a node passes the node filter. Missing nodes never pass.
+    pub fn int_node_passes_filter(
+        &self,
+        node: NGNodeIndex,
+        node_filter: Option<&NodeFilter>,
+    ) -> bool {
+        node_filter.is_none_or(|node_filter| {
+            self.int_get_node_weight(node)
+                .is_ok_and(|node_data| node_filter.matches(node_data))
+        })
+    }
+
     /// Traverses the tree in a depth first manner and calls the provided
     /// callbacks for each node and edge. The depth metric **might not**
     /// accurately represent the intuitive understanding of depth on a graph.
@@ -226,12 +389,16 @@ impl NoteGraph {
     /// returned. These lists are ordered by the order in which the nodes
     /// and edges were visited. Each node and edge is only visited once.
     /// At the depth limit, edges are only visited if they point to already
-    /// visited nodes.
+    /// visited nodes. The max depth, direction and node filter are taken from
+    /// `options`. Edges are followed in the direction, but the edge
+    /// callback always receives the edge in its original direction.
+    /// Nodes that don't pass the node filter are skipped, except for the entry
+    /// nodes.
     pub fn int_traverse_depth_first<'a, N, E>(
         &'a self,
         entry_nodes: Vec<NGNodeIndex>,
         edge_types: Option<&Vec<Rc<str>>>,
-        max_depth: u32,
+        options: &TraversalOptions,
         node_callback: fn(NGNodeIndex, u32) -> N,
         edge_callback: fn(NGEdgeRef<'a>) -> E,
     ) -> NodeEdgeVec<N, E> {
@@ -241,7 +408,7 @@ impl NoteGraph {
             &mut data_structure,
             entry_nodes,
             edge_types,
-            max_depth,
+            options,
             node_callback,
             edge_callback,
         )
@@ -255,12 +422,16 @@ impl NoteGraph {
     /// These lists are ordered by the order in which the nodes and edges were
     /// visited. Each node and edge is only visited once.
     /// At the depth limit, edges are only visited if they point to already
-    /// visited nodes.
+    /// visited nodes. The max depth, direction and node filter are taken from
+    /// `options`. Edges are followed in the direction, but the edge
+    /// callback always receives the edge in its original direction.
+    /// Nodes that don't pass the node filter are skipped, except for the entry
+    /// nodes.
     pub fn int_traverse_breadth_first<'a, N, E>(
         &'a self,
         entry_nodes: Vec<NGNodeIndex>,
         edge_types: Option<&Vec<Rc<str>>>,
-        max_depth: u32,
+        options: &TraversalOptions,
         node_callback: fn(NGNodeIndex, u32) -> N,
         edge_callback: fn(NGEdgeRef<'a>) -> E,
     ) -> NodeEdgeVec<N, E> {
@@ -270,7 +441,7 @@ impl NoteGraph {
             &mut data_structure,
             entry_nodes,
             edge_types,
-            max_depth,
+            options,
             node_callback,
             edge_callback,
         )
@@ -281,13 +452,19 @@ impl NoteGraph {
         traversal_data_structure: &mut impl GraphTraversalDataStructure<(NGNodeIndex, u32)>,
         entry_nodes: Vec<NGNodeIndex>,
         edge_types: Option<&Vec<Rc<str>>>,
-        max_depth: u32,
+        options: &TraversalOptions,
         node_callback: fn(NGNodeIndex, u32) -> N,
         edge_callback: fn(NGEdgeRef<'a>) -> E,
     ) -> NodeEdgeVec<N, E> {
         let mut node_list: NodeVec<N> = Vec::new();
         let mut edge_list: EdgeVec<E> = Vec::new();
         let mut visited_nodes: HashSet<NGNodeIndex> = HashSet::new();
+        // when following edges in both directions, an edge can be reached from both of
+        // its nodes
+        let mut visited_edges: HashSet<NGEdgeIndex> = HashSet::new();
+        // nodes that didn't pass the node filter, so that the filter is only run once
+        // per node
+        let mut filtered_nodes: HashSet<NGNodeIndex> = HashSet::new();
 
         for node in entry_nodes {
             node_list.push((node, node_callback(node, 0)));
@@ -297,20 +474,29 @@ impl NoteGraph {
 
         while !traversal_data_structure.is_empty() {
             let (current_node, current_depth) = traversal_data_structure.pop().unwrap();
-            let at_depth_limit = current_depth >= max_depth;
+            let at_depth_limit = current_depth >= options.max_depth;
 
-            for edge in self.graph.edges(current_node) {
-                let target = edge.target();
+            for (edge, target) in
+                self.int_iter_traversable_edges_directed(current_node, options.direction)
+            {
                 let edge_data = edge.weight();
 
                 if edge_matches_edge_filter(edge_data, edge_types) {
                     let already_visited = visited_nodes.contains(&target);
 
+                    if !already_visited
+                        && (filtered_nodes.contains(&target)
+                            || !self.int_node_passes_filter(target, options.node_filter.as_ref()))
+                    {
+                        filtered_nodes.insert(target);
+                        continue;
+                    }
+
                     // we only add the edge if we are not at the depth limit or if we are at the
                     // depth limit and the target node is already in the depth map
                     // this captures all the outgoing edges from the nodes at the depth limit to
                     // nodes already present in the depth map
-                    if !at_depth_limit || already_visited {
+                    if (!at_depth_limit || already_visited) && visited_edges.insert(edge.id()) {
                         edge_list.push((edge.id(), edge_callback(edge)));
                     }
 
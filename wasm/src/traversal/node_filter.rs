@@ -0,0 +1,247 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{node::NodeData, property::PropertyValue},
+    utils::{Result, LOGGER},
+};
+
+/// Filters the nodes a traversal visits. Nodes that don't pass the filter are
+/// neither emitted nor expanded. The entry nodes of a traversal are not
+/// filtered.
+///
+/// Path patterns are globs, where `*` matches any characters except `/`, `**`
+/// matches any characters and `?` matches a single character except `/`. A
+/// pattern ending in `/` matches everything inside that folder.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct NodeFilter {
+    /// if true, unresolved nodes are skipped
+    pub skip_unresolved: bool,
+    /// if set, only nodes with a path matching one of these patterns are
+    /// visited
+    #[wasm_bindgen(getter_with_clone)]
+    pub include_paths: Option<Vec<String>>,
+    /// nodes with a path matching one of these patterns are skipped
+    #[wasm_bindgen(getter_with_clone)]
+    pub exclude_paths: Vec<String>,
+    /// if set, only nodes with one of these aliases are visited
+    #[wasm_bindgen(getter_with_clone)]
+    pub include_aliases: Option<Vec<String>>,
+    /// nodes with one of these aliases are skipped
+    #[wasm_bindgen(getter_with_clone)]
+    pub exclude_aliases: Vec<String>,
+    /// only nodes matching all of these property conditions are visited
+    #[wasm_bindgen(skip)]
+    pub required_properties: Vec<PropertyCondition>,
+    /// nodes matching any of these property conditions are skipped
+    #[wasm_bindgen(skip)]
+    pub excluded_properties: Vec<PropertyCondition>,
+    /// a function that is called with the `NodeData` of a node and should
+    /// return true if the node should be visited
+    #[wasm_bindgen(getter_with_clone)]
+    pub predicate: Option<js_sys::Function>,
+}
+
+#[wasm_bindgen]
+impl NodeFilter {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> NodeFilter {
+        NodeFilter::default()
+    }
+
+    /// Only visit nodes that have the property `key`. If `value` is not
+    /// `null` or `undefined`, the property must also equal `value`, or be a
+    /// list containing it.
+    ///
+    /// Will return an error if the value has an unsupported type.
+    pub fn require_property(&mut self, key: String, value: JsValue) -> Result<()> {
+        self.required_properties.push(PropertyCondition {
+            key,
+            value: PropertyValue::from_js(&value)?,
+        });
+
+        Ok(())
+    }
+
+    /// Skip nodes that have the property `key`. If `value` is not `null` or
+    /// `undefined`, only nodes where the property equals `value`, or is a list
+    /// containing it, are skipped.
+    ///
+    /// Will return an error if the value has an unsupported type.
+    pub fn exclude_property(&mut self, key: String, value: JsValue) -> Result<()> {
+        self.excluded_properties.push(PropertyCondition {
+            key,
+            value: PropertyValue::from_js(&value)?,
+        });
+
+        Ok(())
+    }
+
+    /// Checks if a node passes the filter.
+    pub fn matches(&self, node: &NodeData) -> bool {
+        if self.skip_unresolved && !node.resolved {
+            return false;
+        }
+
+        if let Some(include_paths) = &self.include_paths {
+            if !include_paths
+                .iter()
+                .any(|pattern| path_matches(pattern, &node.path))
+            {
+                return false;
+            }
+        }
+
+        if self
+            .exclude_paths
+            .iter()
+            .any(|pattern| path_matches(pattern, &node.path))
+        {
+            return false;
+        }
+
+        if let Some(include_aliases) = &self.include_aliases {
+            if !node
+                .aliases
+                .iter()
+                .any(|alias| include_aliases.contains(alias))
+            {
+                return false;
+            }
+        }
+
+        if node
+            .aliases
+            .iter()
+            .any(|alias| self.exclude_aliases.contains(alias))
+        {
+            return false;
+        }
+
+        if !self
+            .required_properties
+            .iter()
+            .all(|condition| condition.matches(node))
+        {
+            return false;
+        }
+
+        if self
+            .excluded_properties
+            .iter()
+            .any(|condition| condition.matches(node))
+        {
+            return false;
+        }
+
+        match &self.predicate {
+            Some(predicate) => match predicate.call1(&JsValue::NULL, &node.clone().into()) {
+                Ok(value) => value.is_truthy(),
+                Err(e) => {
+                    LOGGER.with(|l| {
+                        l.warn(&format!(
+                            "Node filter predicate threw for node \"{}\": {e:?}",
+                            node.path
+                        ))
+                    });
+                    false
+                }
+            },
+            None => true,
+        }
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+/// A condition on a node property, either that the property exists, or that
+/// it has a value.
+#[derive(Clone, Debug)]
+pub struct PropertyCondition {
+    pub key: String,
+    pub value: Option<PropertyValue>,
+}
+
+impl PropertyCondition {
+    pub fn matches(&self, node: &NodeData) -> bool {
+        match (node.properties.get(&self.key), &self.value) {
+            (Some(property), Some(value)) => property.contains(value),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Checks if a path matches a path pattern. A pattern ending in `/` is a
+/// folder prefix, otherwise the pattern is a glob.
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    if pattern.ends_with('/') {
+        path.starts_with(pattern)
+    } else {
+        glob_matches(pattern.as_bytes(), path.as_bytes())
+    }
+}
+
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    GlobMatcher {
+        pattern,
+        path,
+        memo: vec![None; (pattern.len() + 1) * (path.len() + 1)],
+    }
+    .matches(0, 0)
+}
+
+/// Matches a glob against a path with backtracking. The results are memoized
+/// by the positions in the pattern and the path, so that patterns with many
+/// stars don't take exponential time.
+struct GlobMatcher<'a> {
+    pattern: &'a [u8],
+    path: &'a [u8],
+    memo: Vec<Option<bool>>,
+}
+
+impl GlobMatcher<'_> {
+    /// Checks if the pattern from `pattern_pos` matches the path from
+    /// `path_pos`.
+    fn matches(&mut self, pattern_pos: usize, path_pos: usize) -> bool {
+        let key = pattern_pos * (self.path.len() + 1) + path_pos;
+        if let Some(result) = self.memo[key] {
+            return result;
+        }
+
+        let path = &self.path[path_pos..];
+        let result = match &self.pattern[pattern_pos..] {
+            [] => path.is_empty(),
+            [b'*', b'*', ..] => {
+                (0..=path.len()).any(|i| self.matches(pattern_pos + 2, path_pos + i))
+            }
+            [b'*', ..] => {
+                // a single star may not match across folders
+                let segment_len = path.iter().position(|&c| c == b'/').unwrap_or(path.len());
+                (0..=segment_len).any(|i| self.matches(pattern_pos + 1, path_pos + i))
+            }
+            [b'?', ..] => match path {
+                // skip a whole UTF-8 character
+                [c, ..] if *c != b'/' => {
+                    let char_len = path[1..]
+                        .iter()
+                        .take_while(|&&c| (c & 0b1100_0000) == 0b1000_0000)
+                        .count()
+                        + 1;
+                    self.matches(pattern_pos + 1, path_pos + char_len)
+                }
+                _ => false,
+            },
+            [c, ..] => match path {
+                [p, ..] if p == c => self.matches(pattern_pos + 1, path_pos + 1),
+                _ => false,
+            },
+        };
+
+        self.memo[key] = Some(result);
+        result
+    }
+}
@@ -6,9 +6,43 @@ use crate::{
     data::NGNodeIndex,
     edge_sorting::EdgeSorter,
     graph::NoteGraph,
+    traversal::{edge_pattern::EdgeTypePattern, node_filter::NodeFilter},
     utils::{NoteGraphError, Result},
 };
 
+/// The number of edges a traversal or path search follows before it stops,
+/// unless the options set a different limit.
+pub const DEFAULT_MAX_TRAVERSAL_COUNT: u32 = 10_000;
+
+/// The direction in which edges are followed during a traversal.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TraversalDirection {
+    /// follow edges from their source to their target
+    #[default]
+    Outgoing,
+    /// follow edges from their target to their source
+    Incoming,
+    /// follow edges in both directions
+    Both,
+}
+
+impl TraversalDirection {
+    pub fn includes_outgoing(&self) -> bool {
+        matches!(
+            self,
+            TraversalDirection::Outgoing | TraversalDirection::Both
+        )
+    }
+
+    pub fn includes_incoming(&self) -> bool {
+        matches!(
+            self,
+            TraversalDirection::Incoming | TraversalDirection::Both
+        )
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
 pub struct TraversalOptions {
@@ -18,11 +52,34 @@ pub struct TraversalOptions {
     #[wasm_bindgen(getter_with_clone)]
     pub edge_types: Option<Vec<String>>,
     pub max_depth: u32,
+    /// the maximum number of edges a traversal visits. if this is exceeded,
+    /// the traversal stops and the result is marked as truncated. the
+    /// recursive traversal is depth first, so it stops in the middle of a
+    /// level: the remaining siblings of the last visited node and all later
+    /// branches are missing. use a limit well above the expected size of the
+    /// result, e.g. [DEFAULT_MAX_TRAVERSAL_COUNT], to only guard against huge
+    /// graphs
     pub max_traversal_count: u32,
     /// if true, multiple traversals - one for each edge type - will be
     /// performed and the results will be combined. if false, one traversal
     /// over all edge types will be performed
     pub separate_edges: bool,
+    /// if true, the recursive traversal does not descend into nodes that
+    /// already appear on the path from the entry node, instead they are marked
+    /// as cycle back references
+    pub detect_cycles: bool,
+    /// the direction in which edges are followed, defaults to outgoing
+    pub direction: TraversalDirection,
+    /// if set, the recursive traversal only follows sequences of edges whose
+    /// edge types match this pattern. branches that don't lead to a match are
+    /// removed from the result. other traversals, like the one of the mermaid
+    /// graph, fail if this is set
+    #[wasm_bindgen(getter_with_clone)]
+    pub edge_pattern: Option<EdgeTypePattern>,
+    /// if set, nodes that don't pass this filter are neither emitted nor
+    /// expanded
+    #[wasm_bindgen(getter_with_clone)]
+    pub node_filter: Option<NodeFilter>,
 }
 
 #[wasm_bindgen]
@@ -41,9 +98,30 @@ impl TraversalOptions {
             max_depth,
             max_traversal_count,
             separate_edges,
+            detect_cycles: false,
+            direction: TraversalDirection::Outgoing,
+            edge_pattern: None,
+            node_filter: None,
         }
     }
 
+    /// Creates options that visit at most [DEFAULT_MAX_TRAVERSAL_COUNT]
+    /// edges.
+    pub fn with_defaults(
+        entry_nodes: Vec<String>,
+        edge_types: Option<Vec<String>>,
+        max_depth: u32,
+        separate_edges: bool,
+    ) -> TraversalOptions {
+        TraversalOptions::new(
+            entry_nodes,
+            edge_types,
+            max_depth,
+            DEFAULT_MAX_TRAVERSAL_COUNT,
+            separate_edges,
+        )
+    }
+
     #[wasm_bindgen(js_name = toString)]
     pub fn to_fancy_string(&self) -> String {
         format!("{self:#?}")
@@ -58,6 +136,11 @@ impl TraversalOptions {
             .map(|edge_types| edge_types.iter().map(|s| Rc::from(s.clone())).collect())
     }
 
+    /// Checks if a node passes the node filter.
+    pub fn node_passes_filter(&self, graph: &NoteGraph, node: NGNodeIndex) -> bool {
+        graph.int_node_passes_filter(node, self.node_filter.as_ref())
+    }
+
     /// Gets the node indices of the entry nodes.
     pub fn indices_of_entry_nodes(&self, graph: &NoteGraph) -> Result<Vec<NGNodeIndex>> {
         self.entry_nodes
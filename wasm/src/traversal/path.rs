@@ -67,6 +67,9 @@ impl Path {
 #[derive(Clone, Debug, PartialEq)]
 pub struct PathList {
     paths: Vec<Path>,
+    /// whether the search for the paths was stopped early because it exceeded
+    /// the max traversal count, so that some paths may be missing
+    truncated: bool,
 }
 
 #[wasm_bindgen]
@@ -75,6 +78,11 @@ impl PathList {
         self.paths.clone()
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
     #[wasm_bindgen(js_name = toString)]
     pub fn to_fancy_string(&self) -> String {
         format!("{self:#?}")
@@ -131,22 +139,29 @@ impl PathList {
     /// creates new path list, assumes that the paths are already sorted by
     /// length
     pub fn new(paths: Vec<Path>) -> PathList {
-        PathList { paths }
+        PathList {
+            paths,
+            truncated: false,
+        }
+    }
+
+    /// creates a new path list of paths from a search that may have been
+    /// stopped early, assumes that the paths are already sorted by length
+    pub fn new_truncated(paths: Vec<Path>, truncated: bool) -> PathList {
+        PathList { paths, truncated }
     }
 
     pub fn shortest(&self) -> PathList {
-        if let Some(shortest) = self.paths.first() {
-            PathList::new(vec![shortest.clone()])
-        } else {
-            PathList::new(Vec::new())
-        }
+        PathList::new_truncated(
+            self.paths.first().cloned().into_iter().collect(),
+            self.truncated,
+        )
     }
 
     pub fn longest(&self) -> PathList {
-        if let Some(longest) = self.paths.last() {
-            PathList::new(vec![longest.clone()])
-        } else {
-            PathList::new(Vec::new())
-        }
+        PathList::new_truncated(
+            self.paths.last().cloned().into_iter().collect(),
+            self.truncated,
+        )
     }
 }
@@ -0,0 +1,283 @@
+use std::{collections::VecDeque, rc::Rc};
+
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+use petgraph::visit::EdgeRef;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{edge_struct::EdgeStruct, NGEdgeIndex, NGNodeIndex},
+    graph::{edge_matches_edge_filter, NoteGraph},
+    traversal::{
+        options::DEFAULT_MAX_TRAVERSAL_COUNT,
+        path::{Path, PathList},
+    },
+    utils::{NoteGraphError, Result},
+};
+
+#[wasm_bindgen]
+impl NoteGraph {
+    /// Finds the shortest path from one node to another, following outgoing
+    /// edges of the given edge types. If `edge_types` is None, all edge types
+    /// are followed.
+    ///
+    /// Returns None if there is no path with at most `max_depth` edges, or if
+    /// the search followed more than [DEFAULT_MAX_TRAVERSAL_COUNT] edges
+    /// without finding one.
+    pub fn shortest_path(
+        &self,
+        from: String,
+        to: String,
+        edge_types: Option<Vec<String>>,
+        max_depth: u32,
+    ) -> Result<Option<Path>> {
+        let paths = self.k_shortest_paths(from, to, edge_types, max_depth, 1)?;
+
+        Ok(paths.to_paths().into_iter().next())
+    }
+
+    /// Finds up to `k` shortest paths from one node to another, like
+    /// [NoteGraph::shortest_path]. The paths don't visit any node twice.
+    ///
+    /// The paths are sorted by length. If the search follows more than
+    /// [DEFAULT_MAX_TRAVERSAL_COUNT] edges, it stops and the paths found so
+    /// far are returned, marked as truncated.
+    pub fn k_shortest_paths(
+        &self,
+        from: String,
+        to: String,
+        edge_types: Option<Vec<String>>,
+        max_depth: u32,
+        k: u32,
+    ) -> Result<PathList> {
+        let from = self
+            .int_get_node_index(&from)
+            .ok_or(NoteGraphError::new(&format!("Node \"{from}\" not found")))?;
+        let to = self
+            .int_get_node_index(&to)
+            .ok_or(NoteGraphError::new(&format!("Node \"{to}\" not found")))?;
+        let edge_types: Option<Vec<Rc<str>>> =
+            edge_types.map(|edge_types| edge_types.into_iter().map(Rc::from).collect());
+
+        Ok(self.int_k_shortest_paths(
+            from,
+            to,
+            edge_types.as_ref(),
+            max_depth,
+            k,
+            DEFAULT_MAX_TRAVERSAL_COUNT,
+        ))
+    }
+}
+
+impl NoteGraph {
+    /// Finds up to `k` shortest paths that don't visit a node twice, using
+    /// Yen's algorithm. The search stops once it followed
+    /// `max_traversal_count` edges.
+    ///
+    /// Each path after the first is found by taking a prefix of a previous
+    /// path, the root path, and searching the shortest spur path from the end
+    /// of the root path to the target. The spur path may not use the nodes of
+    /// the root path or continue the root path like a previous path does.
+    pub fn int_k_shortest_paths(
+        &self,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        edge_types: Option<&Vec<Rc<str>>>,
+        max_depth: u32,
+        k: u32,
+        max_traversal_count: u32,
+    ) -> PathList {
+        let mut search = PathSearch::new(self, edge_types, max_traversal_count);
+        let mut paths: Vec<Vec<EdgeStruct>> = Vec::new();
+
+        if k == 0 {
+            return PathList::new(Vec::new());
+        }
+
+        if let Some(path) =
+            search.shortest_path(from, to, max_depth, &HashSet::new(), &HashSet::new())
+        {
+            paths.push(path);
+        }
+
+        // candidates for the next path, the shortest one is taken in each iteration
+        let mut candidates: Vec<Vec<EdgeStruct>> = Vec::new();
+
+        while !paths.is_empty() && paths.len() < k as usize && !search.truncated {
+            let previous_path = paths.last().unwrap();
+
+            for i in 0..previous_path.len() {
+                let root_path = &previous_path[..i];
+                let spur_node = previous_path[i].source_index;
+
+                // the spur path may not continue the root path like a previous path
+                let removed_edges: HashSet<NGEdgeIndex> = paths
+                    .iter()
+                    .filter(|path| path.len() > i && same_edges(&path[..i], root_path))
+                    .map(|path| path[i].edge_index)
+                    .collect();
+                // and it may not visit the nodes of the root path again
+                let removed_nodes: HashSet<NGNodeIndex> =
+                    root_path.iter().map(|edge| edge.source_index).collect();
+
+                let Some(spur_path) = search.shortest_path(
+                    spur_node,
+                    to,
+                    max_depth - i as u32,
+                    &removed_nodes,
+                    &removed_edges,
+                ) else {
+                    if search.truncated {
+                        break;
+                    }
+                    continue;
+                };
+
+                let mut path = root_path.to_vec();
+                path.extend(spur_path);
+
+                if !candidates
+                    .iter()
+                    .chain(paths.iter())
+                    .any(|other| same_edges(other, &path))
+                {
+                    candidates.push(path);
+                }
+            }
+
+            if search.truncated {
+                break;
+            }
+
+            // the first of the shortest candidates, so that the result is deterministic
+            let Some(position) = candidates.iter().position_min_by_key(|path| path.len()) else {
+                break;
+            };
+            paths.push(candidates.remove(position));
+        }
+
+        PathList::new_truncated(paths.into_iter().map(Path::new).collect(), search.truncated)
+    }
+}
+
+/// A search for shortest paths. The traversal count is shared between all
+/// searches, so that the max traversal count applies to all of them
+/// together.
+struct PathSearch<'a> {
+    graph: &'a NoteGraph,
+    edge_types: Option<&'a Vec<Rc<str>>>,
+    max_traversal_count: u32,
+    traversal_count: u32,
+    /// whether a search was stopped because it exceeded the max traversal
+    /// count
+    truncated: bool,
+}
+
+impl<'a> PathSearch<'a> {
+    fn new(
+        graph: &'a NoteGraph,
+        edge_types: Option<&'a Vec<Rc<str>>>,
+        max_traversal_count: u32,
+    ) -> PathSearch<'a> {
+        PathSearch {
+            graph,
+            edge_types,
+            max_traversal_count,
+            traversal_count: 0,
+            truncated: false,
+        }
+    }
+
+    /// Finds the shortest path with at most `max_depth` edges using a breadth
+    /// first search, without visiting the removed nodes or following the
+    /// removed edges.
+    fn shortest_path(
+        &mut self,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        max_depth: u32,
+        removed_nodes: &HashSet<NGNodeIndex>,
+        removed_edges: &HashSet<NGEdgeIndex>,
+    ) -> Option<Vec<EdgeStruct>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        // maps each visited node to the edge it was first reached by
+        let mut predecessors: HashMap<NGNodeIndex, EdgeStruct> = HashMap::new();
+        let mut queue: VecDeque<(NGNodeIndex, u32)> = VecDeque::new();
+
+        queue.push_back((from, 0));
+
+        while let Some((current_node, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+
+            for edge in self.graph.int_iter_traversable_outgoing_edges(current_node) {
+                let target = edge.target();
+                if !edge_matches_edge_filter(edge.weight(), self.edge_types)
+                    || target == from
+                    || predecessors.contains_key(&target)
+                    || removed_nodes.contains(&target)
+                    || removed_edges.contains(&edge.id())
+                {
+                    continue;
+                }
+
+                if self.traversal_count >= self.max_traversal_count {
+                    self.truncated = true;
+                    return None;
+                }
+                self.traversal_count += 1;
+
+                predecessors.insert(
+                    target,
+                    EdgeStruct::new(
+                        current_node,
+                        target,
+                        edge.id(),
+                        edge.weight().edge_type.clone(),
+                        self.graph.get_revision(),
+                    ),
+                );
+
+                if target == to {
+                    return Some(collect_path(&predecessors, from, to));
+                }
+
+                queue.push_back((target, depth + 1));
+            }
+        }
+
+        None
+    }
+}
+
+fn same_edges(a: &[EdgeStruct], b: &[EdgeStruct]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.edge_index == b.edge_index && a.source_index == b.source_index)
+}
+
+/// Walks the predecessors back from `to` to `from` and collects the edges of
+/// the path.
+fn collect_path(
+    predecessors: &HashMap<NGNodeIndex, EdgeStruct>,
+    from: NGNodeIndex,
+    to: NGNodeIndex,
+) -> Vec<EdgeStruct> {
+    let mut edges = Vec::new();
+    let mut current_node = to;
+
+    while current_node != from {
+        let edge = &predecessors[&current_node];
+        current_node = edge.source_index;
+        edges.push(edge.clone());
+    }
+
+    edges.reverse();
+    edges
+}
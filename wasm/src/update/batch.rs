@@ -32,9 +32,22 @@ impl BatchGraphUpdate {
         self.updates.push(update);
     }
 
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Applies all updates in order. If an update fails, the returned error
+    /// carries the index of the failing update and the remaining updates are
+    /// not applied.
     pub fn apply(self, graph: &mut NoteGraph) -> utils::Result<()> {
-        for update in self.updates {
-            update.apply(graph)?;
+        for (index, update) in self.updates.into_iter().enumerate() {
+            update
+                .apply(graph)
+                .map_err(|error| error.with_update_index(index))?;
         }
 
         Ok(())
@@ -0,0 +1,408 @@
+use std::rc::Rc;
+
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    data::{
+        construction::GCEdgeData,
+        edge::{EdgeData, EdgeStep},
+        node::NodeData,
+        NGEdgeIndex, NGNodeIndex,
+    },
+    graph::NoteGraph,
+    update::undo::UndoEntry,
+};
+
+/// An edge, identified by source path, target path, edge type and edge
+/// source. Explicit edges asserted by multiple edge sources have one key per
+/// edge source.
+pub type EdgeKey = (String, String, String, String);
+
+/// A node that was renamed by an update.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenamedNode {
+    #[wasm_bindgen(getter_with_clone)]
+    pub old_path: String,
+    #[wasm_bindgen(getter_with_clone)]
+    pub new_path: String,
+}
+
+/// The changes an update made to the graph. Passed to the change callback, see
+/// [NoteGraph::set_change_callback].
+///
+/// Nodes are identified by their path after the update, so renamed nodes and
+/// their edges only show up in `renamed_nodes`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct GraphChangeSet {
+    #[wasm_bindgen(skip)]
+    pub old_revision: u32,
+    #[wasm_bindgen(skip)]
+    pub new_revision: u32,
+    #[wasm_bindgen(skip)]
+    pub added_nodes: Vec<String>,
+    #[wasm_bindgen(skip)]
+    pub removed_nodes: Vec<String>,
+    #[wasm_bindgen(skip)]
+    pub renamed_nodes: Vec<RenamedNode>,
+    /// nodes that exist before and after the update, but with different data,
+    /// e.g. a resolved node that became unresolved
+    #[wasm_bindgen(skip)]
+    pub changed_nodes: Vec<String>,
+    #[wasm_bindgen(skip)]
+    pub added_edges: Vec<GCEdgeData>,
+    #[wasm_bindgen(skip)]
+    pub removed_edges: Vec<GCEdgeData>,
+    /// implied edges that were created by the update, the edge source is the
+    /// name of the rule
+    #[wasm_bindgen(skip)]
+    pub gained_implied_edges: Vec<GCEdgeData>,
+    /// implied edges that no longer exist after the update
+    #[wasm_bindgen(skip)]
+    pub lost_implied_edges: Vec<GCEdgeData>,
+}
+
+#[wasm_bindgen]
+impl GraphChangeSet {
+    #[wasm_bindgen(js_name = old_revision, getter)]
+    pub fn get_old_revision(&self) -> u32 {
+        self.old_revision
+    }
+
+    #[wasm_bindgen(js_name = new_revision, getter)]
+    pub fn get_new_revision(&self) -> u32 {
+        self.new_revision
+    }
+
+    #[wasm_bindgen(js_name = added_nodes, getter)]
+    pub fn get_added_nodes(&self) -> Vec<String> {
+        self.added_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = removed_nodes, getter)]
+    pub fn get_removed_nodes(&self) -> Vec<String> {
+        self.removed_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = renamed_nodes, getter)]
+    pub fn get_renamed_nodes(&self) -> Vec<RenamedNode> {
+        self.renamed_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = changed_nodes, getter)]
+    pub fn get_changed_nodes(&self) -> Vec<String> {
+        self.changed_nodes.clone()
+    }
+
+    #[wasm_bindgen(js_name = added_edges, getter)]
+    pub fn get_added_edges(&self) -> Vec<GCEdgeData> {
+        self.added_edges.clone()
+    }
+
+    #[wasm_bindgen(js_name = removed_edges, getter)]
+    pub fn get_removed_edges(&self) -> Vec<GCEdgeData> {
+        self.removed_edges.clone()
+    }
+
+    #[wasm_bindgen(js_name = gained_implied_edges, getter)]
+    pub fn get_gained_implied_edges(&self) -> Vec<GCEdgeData> {
+        self.gained_implied_edges.clone()
+    }
+
+    #[wasm_bindgen(js_name = lost_implied_edges, getter)]
+    pub fn get_lost_implied_edges(&self) -> Vec<GCEdgeData> {
+        self.lost_implied_edges.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.renamed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.gained_implied_edges.is_empty()
+            && self.lost_implied_edges.is_empty()
+    }
+
+    /// Checks if the node itself or any edge from or to the node changed.
+    /// Views can use this to only re-render when the neighbourhood of their
+    /// active note changed.
+    pub fn touches_node(&self, node: &str) -> bool {
+        let edge_touches_node = |edge: &GCEdgeData| edge.source == node || edge.target == node;
+
+        self.added_nodes.iter().any(|path| path == node)
+            || self.removed_nodes.iter().any(|path| path == node)
+            || self.changed_nodes.iter().any(|path| path == node)
+            || self
+                .renamed_nodes
+                .iter()
+                .any(|renamed| renamed.old_path == node || renamed.new_path == node)
+            || self.added_edges.iter().any(edge_touches_node)
+            || self.removed_edges.iter().any(edge_touches_node)
+            || self.gained_implied_edges.iter().any(edge_touches_node)
+            || self.lost_implied_edges.iter().any(edge_touches_node)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_fancy_string(&self) -> String {
+        format!("{self:#?}")
+    }
+}
+
+/// The keys of the implied edges that a rebuild of the implied edges can
+/// change, before and after the rebuild. Collected by
+/// [NoteGraph::int_rebuild_implied_edges].
+#[derive(Clone, Debug, Default)]
+pub struct ImpliedEdgeKeys {
+    pub before: HashSet<EdgeKey>,
+    pub after: HashSet<EdgeKey>,
+}
+
+impl ImpliedEdgeKeys {
+    /// Builds the key of an implied edge.
+    pub fn key(graph: &NoteGraph, edge: NGEdgeIndex) -> Option<EdgeKey> {
+        let (source, target) = graph.graph.edge_endpoints(edge)?;
+        let edge_data = &graph.graph[edge];
+
+        Some((
+            graph.graph[source].path.clone(),
+            graph.graph[target].path.clone(),
+            edge_data.edge_type.to_string(),
+            edge_data.edge_source.to_string(),
+        ))
+    }
+}
+
+/// The changes an update made to the nodes and explicit edges, used to compute
+/// the [GraphChangeSet] after the implied edges were rebuilt.
+///
+/// The state is built from the undo log of the update and the change tracker,
+/// so it only looks at the nodes and edges the update touched.
+pub struct GraphChangeState {
+    revision: u32,
+    /// the nodes changed by the update, with their data before the update, or
+    /// None if they were added by the update
+    nodes: HashMap<NGNodeIndex, Option<NodeData>>,
+    /// nodes that existed before the update and may be removed together with
+    /// the stale implied edges, with their path
+    orphan_candidates: Vec<(NGNodeIndex, String)>,
+    added_edges: Vec<EdgeKey>,
+    removed_edges: Vec<EdgeKey>,
+    pub implied_edges: ImpliedEdgeKeys,
+}
+
+impl GraphChangeState {
+    /// Creates the state after the updates of a batch were applied, but before
+    /// the implied edges are rebuilt. `undo_entries` are the entries the undo
+    /// log recorded for the updates.
+    pub fn new(graph: &NoteGraph, undo_entries: &[UndoEntry]) -> GraphChangeState {
+        let mut nodes: HashMap<NGNodeIndex, Option<NodeData>> = HashMap::new();
+        // the data of the explicit edges the update touched before the update, by
+        // source, target and edge type, or None if there was no such edge
+        let mut old_edges: HashMap<EdgeStep, Option<&EdgeData>> = HashMap::new();
+        // the edges as they were at the current undo entry, or None if the edge
+        // didn't exist. Edges that are not in here are as they are now.
+        let mut edge_states: HashMap<NGEdgeIndex, Option<(NGNodeIndex, NGNodeIndex, &EdgeData)>> =
+            HashMap::new();
+
+        // go through the entries backwards, so that the entry of the first change
+        // of a node or edge is applied last
+        for entry in undo_entries.iter().rev() {
+            let mut edge_state = |edge: NGEdgeIndex| {
+                *edge_states.entry(edge).or_insert_with(|| {
+                    let (source, target) = graph.graph.edge_endpoints(edge)?;
+                    let data = &graph.graph[edge];
+                    // the edge didn't exist in its current form if no earlier entry says so
+                    old_edges
+                        .entry((source, target, Rc::clone(&data.edge_type)))
+                        .or_insert(None);
+                    Some((source, target, data))
+                })
+            };
+
+            match entry {
+                UndoEntry::AddNode(node) => {
+                    nodes.insert(*node, None);
+                }
+                UndoEntry::NodeWeight(node, data) => {
+                    nodes.insert(*node, Some(data.clone()));
+                }
+                UndoEntry::AddEdge(edge) => {
+                    if let Some((source, target, data)) = edge_state(*edge) {
+                        old_edges.insert((source, target, Rc::clone(&data.edge_type)), None);
+                    }
+                    edge_states.insert(*edge, None);
+                }
+                UndoEntry::RemoveEdge {
+                    edge,
+                    from,
+                    to,
+                    data,
+                    ..
+                } => {
+                    edge_state(*edge);
+                    old_edges.insert((*from, *to, Rc::clone(&data.edge_type)), Some(data));
+                    edge_states.insert(*edge, Some((*from, *to, data)));
+                }
+                UndoEntry::EdgeWeight(edge, data) => {
+                    if let Some((source, target, _)) = edge_state(*edge) {
+                        old_edges.insert((source, target, Rc::clone(&data.edge_type)), Some(data));
+                        edge_states.insert(*edge, Some((source, target, data)));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut added_edges = Vec::new();
+        let mut removed_edges = Vec::new();
+        for ((source, target, edge_type), old_data) in old_edges {
+            let new_data = graph
+                .int_get_explicit_edge(source, target, &edge_type)
+                .map(|edge| edge.weight());
+            let old_sources = edge_sources(old_data);
+            let new_sources = edge_sources(new_data);
+            let key = |edge_source: &str| {
+                (
+                    graph.graph[source].path.clone(),
+                    graph.graph[target].path.clone(),
+                    edge_type.to_string(),
+                    edge_source.to_owned(),
+                )
+            };
+
+            removed_edges.extend(old_sources.difference(&new_sources).map(|s| key(s)));
+            added_edges.extend(new_sources.difference(&old_sources).map(|s| key(s)));
+        }
+
+        let tracker = &graph.change_tracker;
+        let orphan_candidates = tracker
+            .touched_nodes
+            .union(&tracker.unresolved_nodes)
+            .filter(|node| !matches!(nodes.get(*node), Some(None)))
+            .filter_map(|&node| Some((node, graph.graph.node_weight(node)?.path.clone())))
+            .collect();
+
+        GraphChangeState {
+            revision: graph.get_revision(),
+            nodes,
+            orphan_candidates,
+            added_edges,
+            removed_edges,
+            implied_edges: ImpliedEdgeKeys::default(),
+        }
+    }
+
+    /// Compares the state with the current state of the graph. `renames` are
+    /// the renames made by the update, in the order they were applied.
+    pub fn into_change_set(
+        self,
+        graph: &NoteGraph,
+        renames: &[(String, String)],
+    ) -> GraphChangeSet {
+        let mut change_set = GraphChangeSet {
+            old_revision: self.revision,
+            new_revision: graph.get_revision(),
+            ..GraphChangeSet::default()
+        };
+
+        // map the old paths to the paths after the update
+        let mut renamed_paths: HashMap<String, String> = HashMap::new();
+        for (old_path, new_path) in renames {
+            let original_path = renamed_paths
+                .iter()
+                .find(|(_, path)| *path == old_path)
+                .map(|(original_path, _)| original_path.clone())
+                .unwrap_or_else(|| old_path.clone());
+
+            renamed_paths.insert(original_path, new_path.clone());
+        }
+
+        // nodes that were added and renamed by the same update are only added
+        for (old_path, new_path) in renamed_paths.into_iter().sorted() {
+            let renamed = graph
+                .int_get_node_index(&new_path)
+                .is_some_and(|node| !matches!(self.nodes.get(&node), Some(None)));
+
+            if old_path != new_path && renamed {
+                change_set
+                    .renamed_nodes
+                    .push(RenamedNode { old_path, new_path });
+            }
+        }
+
+        for (node, old_node) in self.nodes.into_iter().sorted_by_key(|(node, _)| *node) {
+            let Some(node_data) = graph.graph.node_weight(node) else {
+                continue;
+            };
+
+            match old_node {
+                None => change_set.added_nodes.push(node_data.path.clone()),
+                Some(mut old_node) => {
+                    // renames are reported separately
+                    old_node.path.clone_from(&node_data.path);
+                    if old_node != *node_data {
+                        change_set.changed_nodes.push(node_data.path.clone());
+                    }
+                }
+            }
+        }
+
+        for (node, path) in self.orphan_candidates {
+            if graph.graph.node_weight(node).is_none() {
+                change_set.removed_nodes.push(path);
+            }
+        }
+        change_set.removed_nodes.sort();
+
+        push_edges(self.removed_edges, &mut change_set.removed_edges);
+        push_edges(self.added_edges, &mut change_set.added_edges);
+
+        let implied_edges = self.implied_edges;
+        push_edges(
+            implied_edges
+                .before
+                .difference(&implied_edges.after)
+                .cloned()
+                .collect(),
+            &mut change_set.lost_implied_edges,
+        );
+        push_edges(
+            implied_edges
+                .after
+                .difference(&implied_edges.before)
+                .cloned()
+                .collect(),
+            &mut change_set.gained_implied_edges,
+        );
+
+        change_set
+    }
+}
+
+/// The edge sources asserting an explicit edge.
+fn edge_sources(edge_data: Option<&EdgeData>) -> HashSet<&str> {
+    edge_data
+        .filter(|edge_data| edge_data.explicit)
+        .map(|edge_data| {
+            edge_data
+                .sources
+                .iter()
+                .map(|source| source.edge_source.as_ref())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Pushes the edges with the given keys, sorted by their key.
+fn push_edges(mut keys: Vec<EdgeKey>, result: &mut Vec<GCEdgeData>) {
+    keys.sort();
+
+    for (source, target, edge_type, edge_source) in keys {
+        result.push(GCEdgeData::new(source, target, edge_type, edge_source));
+    }
+}
@@ -1,11 +1,15 @@
+use std::rc::Rc;
+
 use petgraph::prelude::EdgeRef;
 
 use crate::{
     data::{
         construction::{GCEdgeData, GCNodeData},
         node::NodeData,
+        property::PropertyValue,
         NGEdgeIndex, NGNodeIndex,
     },
+    dynamic_edges::{folder_of, DynamicEdgeSource, NodeMatcher},
     graph::NoteGraph,
     utils,
     utils::{NoteGraphError, LOGGER},
@@ -15,10 +19,38 @@ pub(super) trait UpdateableGraph {
     /// Adds a node to the graph.
     /// Throws an error if the node already exists and is resolved.
     fn upd_add_node(&mut self, data: GCNodeData) -> utils::Result<()>;
+    /// Removes a note, turning its node into an unresolved node.
+    /// Everything that comes from the note itself is removed: its aliases,
+    /// tags, properties, ignore flags, outgoing explicit edges and dynamic edge
+    /// sources. Adding the note again does not restore any of them, they
+    /// have to be added again like the rest of the note.
     fn upd_remove_node(&mut self, name: &str) -> utils::Result<()>;
     fn upd_rename_node(&mut self, old_name: &str, new_name: &str) -> utils::Result<()>;
+    /// Sets a property of a node, or removes it if `value` is `None`.
+    fn upd_set_node_property(
+        &mut self,
+        name: &str,
+        key: String,
+        value: Option<PropertyValue>,
+    ) -> utils::Result<()>;
+    /// Replaces the data of a resolved node, keeping its edges.
+    /// Throws an error if the node does not exist, is unresolved or the new
+    /// data is unresolved.
+    fn upd_set_node_data(&mut self, data: GCNodeData) -> utils::Result<()>;
     fn upd_add_edge(&mut self, data: GCEdgeData) -> utils::Result<()>;
-    fn upd_remove_edge(&mut self, from: &str, to: &str, edge_type: &str) -> utils::Result<()>;
+    /// Removes an edge. If `edge_source` is set, only that edge source stops
+    /// asserting the edge, and the edge is kept while other edge sources
+    /// still assert it. Without an edge source, the edge must be asserted by a
+    /// single edge source, which is then removed.
+    fn upd_remove_edge(
+        &mut self,
+        from: &str,
+        to: &str,
+        edge_type: &str,
+        edge_source: Option<&str>,
+    ) -> utils::Result<()>;
+    fn upd_add_dynamic_edge_source(&mut self, data: DynamicEdgeSource) -> utils::Result<()>;
+    fn upd_remove_dynamic_edge_sources(&mut self, source: &str) -> utils::Result<()>;
 }
 
 /// INVARIANT: These update methods should keep the node_hash intact, but the
@@ -36,12 +68,28 @@ impl UpdateableGraph for NoteGraph {
                 }
 
                 let node = self.int_get_node_weight_mut(node_index)?;
+                let ignore_flags_changed = node.ignore_in_edges != data.ignore_in_edges
+                    || node.ignore_out_edges != data.ignore_out_edges;
                 node.override_with_construction_data(data);
+                let resolved = node.resolved;
+
+                if ignore_flags_changed {
+                    self.touch_incident_edges(node_index);
+                }
+                if !resolved {
+                    self.change_tracker.touch_unresolved(node_index);
+                }
+
+                self.int_sync_dynamic_edges_of_node(node_index);
             }
             None => {
-                let node_path = data.path.clone();
-                let node_index = self.graph.add_node(data.into());
-                self.node_hash.insert(node_path, node_index);
+                let resolved = data.resolved;
+                let node_index = self.int_add_node(data.into());
+                if !resolved {
+                    self.change_tracker.touch_unresolved(node_index);
+                }
+
+                self.int_sync_dynamic_edges_of_node(node_index);
             }
         }
 
@@ -62,16 +110,40 @@ impl UpdateableGraph for NoteGraph {
                     });
                 }
 
-                node_weight.resolved = false;
+                let ignore_flags_changed =
+                    node_weight.ignore_in_edges || node_weight.ignore_out_edges;
+                node_weight.clear_note_data();
 
-                let edges_to_remove: Vec<NGEdgeIndex> = self
+                if ignore_flags_changed {
+                    self.touch_incident_edges(index);
+                }
+
+                let edges_to_remove: Vec<(NGEdgeIndex, NGNodeIndex)> = self
                     .int_iter_outgoing_edges(index)
-                    .map(|edge| edge.id())
+                    .filter(|edge| edge.weight().explicit)
+                    .map(|edge| (edge.id(), edge.target()))
                     .collect();
 
-                for edge in edges_to_remove {
-                    self.graph.remove_edge(edge);
+                for (edge, target) in edges_to_remove {
+                    if let Some(edge_data) = self.int_remove_edge(edge) {
+                        self.change_tracker
+                            .touch_edge(index, target, &edge_data.edge_type);
+                    }
                 }
+
+                // the dynamic edge sources come from the note itself, so they are removed
+                // as well, their edges were already removed above
+                let mut i = 0;
+                while i < self.dynamic_edge_sources.len() {
+                    if self.dynamic_edge_sources[i].source == name {
+                        self.int_remove_dynamic_edge_source(i);
+                    } else {
+                        i += 1;
+                    }
+                }
+                // unresolved nodes are not matched by dynamic edge sources
+                self.int_sync_dynamic_edges_of_node(index);
+                self.change_tracker.touch_unresolved(index);
             }
             None => {
                 return Err(NoteGraphError::new("failed to remove node, node not found"));
@@ -88,9 +160,76 @@ impl UpdateableGraph for NoteGraph {
                 "failed to rename node, old node not found",
             ))?;
 
-        self.graph.node_weight_mut(node_index).unwrap().path = new_name.to_owned();
-        self.node_hash.remove(old_name);
-        self.node_hash.insert(new_name.to_owned(), node_index);
+        self.int_set_node_path(node_index, new_name);
+        self.change_tracker.touch_rename(old_name, new_name);
+
+        // the edges of the renamed note stay with it, but folder edge sources match
+        // other nodes when the note was moved to another folder
+        let folder_changed = folder_of(old_name) != folder_of(new_name);
+        for i in 0..self.dynamic_edge_sources.len() {
+            if self.dynamic_edge_sources[i].source == old_name {
+                new_name.clone_into(&mut self.int_get_dynamic_edge_source_mut(i).source);
+                let is_folder_source = matches!(
+                    self.dynamic_edge_sources[i].matcher,
+                    NodeMatcher::Folder { .. }
+                );
+                if folder_changed && is_folder_source {
+                    self.int_evaluate_dynamic_edge_source(i);
+                }
+            }
+        }
+        self.int_sync_dynamic_edges_of_node(node_index);
+
+        Ok(())
+    }
+
+    fn upd_set_node_property(
+        &mut self,
+        name: &str,
+        key: String,
+        value: Option<PropertyValue>,
+    ) -> utils::Result<()> {
+        let node_index = self.int_get_node_index(name).ok_or(NoteGraphError::new(
+            "failed to set property, node not found",
+        ))?;
+        let node = self.int_get_node_weight_mut(node_index)?;
+
+        match value {
+            Some(value) => {
+                node.properties.insert(key, value);
+            }
+            None => {
+                node.properties.remove(&key);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn upd_set_node_data(&mut self, data: GCNodeData) -> utils::Result<()> {
+        let node_index = self
+            .int_get_node_index(&data.path)
+            .ok_or(NoteGraphError::new(
+                "failed to set node data, node not found",
+            ))?;
+
+        if !data.resolved || !self.int_get_node_weight(node_index)?.resolved {
+            return Err(NoteGraphError::new(
+                "failed to set node data, only the data of resolved nodes can be set",
+            ));
+        }
+
+        let node = self.int_get_node_weight_mut(node_index)?;
+        let ignore_flags_changed = node.ignore_in_edges != data.ignore_in_edges
+            || node.ignore_out_edges != data.ignore_out_edges;
+        node.override_with_construction_data(data);
+
+        if ignore_flags_changed {
+            self.touch_incident_edges(node_index);
+        }
+
+        // the node may now match other dynamic edge sources, e.g. by its tags
+        self.int_sync_dynamic_edges_of_node(node_index);
 
         Ok(())
     }
@@ -103,16 +242,31 @@ impl UpdateableGraph for NoteGraph {
             ))?;
         let target = self.get_node_index_or_create_unresolved(&data.target);
 
-        if self.int_has_edge(source, target, &data.edge_type) {
+        // if the explicit edge already exists, another edge source asserts it as well
+        if let Some(edge) = self
+            .int_get_explicit_edge(source, target, &data.edge_type)
+            .map(|edge| edge.id())
+        {
+            self.int_get_edge_weight_mut(edge)?
+                .add_edge_source(data.to_edge_source_data());
             return Ok(());
         }
 
-        self.graph.add_edge(source, target, data.to_edge_data());
+        let edge_data = data.to_edge_data();
+        self.change_tracker
+            .touch_edge(source, target, &edge_data.edge_type);
+        self.int_add_edge(source, target, edge_data);
 
         Ok(())
     }
 
-    fn upd_remove_edge(&mut self, from: &str, to: &str, edge_type: &str) -> utils::Result<()> {
+    fn upd_remove_edge(
+        &mut self,
+        from: &str,
+        to: &str,
+        edge_type: &str,
+        edge_source: Option<&str>,
+    ) -> utils::Result<()> {
         let from = self.int_get_node_index(from).ok_or(NoteGraphError::new(
             "failed to delete edge, source node not found",
         ))?;
@@ -120,33 +274,117 @@ impl UpdateableGraph for NoteGraph {
             "failed to delete edge, target node not found",
         ))?;
 
-        match self.int_get_edge(from, to, edge_type) {
-            Some(edge) => {
-                self.graph.remove_edge(edge.id());
-                Ok(())
+        let edge = self
+            .int_get_explicit_edge(from, to, edge_type)
+            .map(|edge| edge.id())
+            .ok_or(NoteGraphError::new("failed to delete edge, edge not found"))?;
+
+        let edge_source = match edge_source {
+            Some(edge_source) => {
+                if !self.graph[edge].has_edge_source(edge_source) {
+                    return Err(NoteGraphError::new(
+                        "failed to delete edge, edge source not found",
+                    ));
+                }
+
+                Rc::from(edge_source)
             }
-            None => Err(NoteGraphError::new("failed to delete edge, edge not found")),
+            // we don't know which edge source to remove, so we only allow this for edges
+            // with a single edge source, like before edges could have multiple edge sources
+            None => match self.graph[edge].sources.as_slice() {
+                [source] => Rc::clone(&source.edge_source),
+                _ => {
+                    return Err(NoteGraphError::new(
+                        "failed to delete edge, the edge has multiple edge sources, specify which one to remove",
+                    ))
+                }
+            },
+        };
+
+        self.int_remove_edge_source(edge, &edge_source);
+
+        Ok(())
+    }
+
+    fn upd_add_dynamic_edge_source(&mut self, mut data: DynamicEdgeSource) -> utils::Result<()> {
+        if self.int_get_node_index(&data.source).is_none() {
+            return Err(NoteGraphError::new(
+                "failed to add dynamic edge source, source node not found",
+            ));
         }
+
+        // the edges of the edge source are added below
+        data.targets.clear();
+
+        self.int_push_dynamic_edge_source(data);
+        self.int_evaluate_dynamic_edge_source(self.dynamic_edge_sources.len() - 1);
+
+        Ok(())
+    }
+
+    fn upd_remove_dynamic_edge_sources(&mut self, source: &str) -> utils::Result<()> {
+        let mut i = 0;
+        while i < self.dynamic_edge_sources.len() {
+            if self.dynamic_edge_sources[i].source == source {
+                let edge_source = self.int_remove_dynamic_edge_source(i);
+                self.int_remove_dynamic_edges(&edge_source);
+            } else {
+                i += 1;
+            }
+        }
+
+        Ok(())
     }
 }
 
 /// Helper methods for the impl above.
 impl NoteGraph {
+    /// Records all explicit edges of a node in the change tracker, e.g. because
+    /// edges through the node are now ignored or no longer ignored.
+    fn touch_incident_edges(&mut self, node: NGNodeIndex) {
+        let edges: Vec<(NGNodeIndex, NGNodeIndex, Rc<str>)> = self
+            .int_iter_outgoing_edges(node)
+            .chain(self.int_iter_incoming_edges(node))
+            .filter(|edge| edge.weight().explicit)
+            .map(|edge| {
+                (
+                    edge.source(),
+                    edge.target(),
+                    Rc::clone(&edge.weight().edge_type),
+                )
+            })
+            .collect();
+
+        for (source, target, edge_type) in edges {
+            self.change_tracker.touch_edge(source, target, &edge_type);
+        }
+    }
+
+    /// Records that an edge source asserts an explicit edge once less, and
+    /// removes the edge if no edge source asserts it anymore.
+    pub(crate) fn int_remove_edge_source(&mut self, edge: NGEdgeIndex, edge_source: &str) {
+        let Ok(edge_weight) = self.int_get_edge_weight_mut(edge) else {
+            return;
+        };
+        if edge_weight.remove_edge_source(edge_source) {
+            return;
+        }
+
+        if let Some((from, to)) = self.graph.edge_endpoints(edge) {
+            if let Some(edge_data) = self.int_remove_edge(edge) {
+                self.change_tracker
+                    .touch_edge(from, to, &edge_data.edge_type);
+            }
+        }
+    }
+
     /// Gets the node index for a specific node.
     /// If the node does not exist, a new unresolved node will be created and
     /// the index of the new node returned.
     pub fn get_node_index_or_create_unresolved(&mut self, node: &str) -> NGNodeIndex {
         match self.int_get_node_index(node) {
             Some(node_index) => node_index,
-            None => {
-                let node_index = self
-                    .graph
-                    .add_node(NodeData::new_unresolved(node.to_owned()));
-
-                self.node_hash.insert(node.to_owned(), node_index);
-
-                node_index
-            }
+            None => self.int_add_node(NodeData::new_unresolved(node.to_owned())),
         }
     }
 }
@@ -5,15 +5,20 @@ use wasm_bindgen::prelude::*;
 use crate::{
     data::{
         construction::{GCEdgeData, GCNodeData},
+        property::PropertyValue,
         rules::TransitiveGraphRule,
     },
+    dynamic_edges::DynamicEdgeSource,
     graph::NoteGraph,
     update::batch::BatchGraphUpdate,
     utils::Result,
 };
 
 pub mod batch;
+pub mod change_set;
 pub mod graph;
+pub mod tracker;
+pub mod undo;
 
 #[enum_dispatch]
 pub trait GraphUpdate {
@@ -25,9 +30,13 @@ pub enum Update {
     AddNoteGraphUpdate,
     RemoveNoteGraphUpdate,
     RenameNoteGraphUpdate,
+    SetNotePropertyGraphUpdate,
+    SetNoteDataGraphUpdate,
     AddEdgeGraphUpdate,
     RemoveEdgeGraphUpdate,
     TransitiveRulesGraphUpdate,
+    AddDynamicEdgeSourceGraphUpdate,
+    RemoveDynamicEdgeSourcesGraphUpdate,
 }
 
 #[wasm_bindgen]
@@ -103,6 +112,75 @@ impl GraphUpdate for RenameNoteGraphUpdate {
     }
 }
 
+/// Sets or removes a metadata property of a note.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct SetNotePropertyGraphUpdate {
+    node: String,
+    key: String,
+    value: Option<PropertyValue>,
+}
+
+#[wasm_bindgen]
+impl SetNotePropertyGraphUpdate {
+    /// The value may be a string, number, boolean, `Date` or an array of
+    /// those. A value of `null` or `undefined` removes the property.
+    ///
+    /// Will return an error if the value has an unsupported type.
+    #[wasm_bindgen(constructor)]
+    pub fn new(node: String, key: String, value: JsValue) -> Result<SetNotePropertyGraphUpdate> {
+        Ok(Self {
+            node,
+            key,
+            value: PropertyValue::from_js(&value)?,
+        })
+    }
+
+    pub fn add_to_batch(self, batch: &mut BatchGraphUpdate) {
+        batch.add_update(self.into());
+    }
+}
+
+impl SetNotePropertyGraphUpdate {
+    /// Sets the property to a value that was already converted, or removes
+    /// it if `value` is `None`.
+    pub fn with_value(node: String, key: String, value: Option<PropertyValue>) -> Self {
+        Self { node, key, value }
+    }
+}
+
+impl GraphUpdate for SetNotePropertyGraphUpdate {
+    fn apply(self, graph: &mut NoteGraph) -> Result<()> {
+        graph.upd_set_node_property(&self.node, self.key, self.value)
+    }
+}
+
+/// Replaces the data of a resolved note, e.g. its aliases, tags and ignore
+/// flags, without touching its edges.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct SetNoteDataGraphUpdate {
+    data: GCNodeData,
+}
+
+#[wasm_bindgen]
+impl SetNoteDataGraphUpdate {
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: GCNodeData) -> Self {
+        Self { data }
+    }
+
+    pub fn add_to_batch(self, batch: &mut BatchGraphUpdate) {
+        batch.add_update(self.into());
+    }
+}
+
+impl GraphUpdate for SetNoteDataGraphUpdate {
+    fn apply(self, graph: &mut NoteGraph) -> Result<()> {
+        graph.upd_set_node_data(self.data)
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct AddEdgeGraphUpdate {
@@ -133,16 +211,31 @@ pub struct RemoveEdgeGraphUpdate {
     from: String,
     to: String,
     edge_type: String,
+    edge_source: Option<String>,
 }
 
 #[wasm_bindgen]
 impl RemoveEdgeGraphUpdate {
+    /// Removes an edge that is asserted by a single edge source. Fails if
+    /// multiple edge sources assert the edge, use `with_source` for those.
     #[wasm_bindgen(constructor)]
     pub fn new(from: String, to: String, edge_type: String) -> Self {
         Self {
             from,
             to,
             edge_type,
+            edge_source: None,
+        }
+    }
+
+    /// Removes an edge source from an edge. The edge is only removed once no
+    /// edge source asserts it anymore.
+    pub fn with_source(from: String, to: String, edge_type: String, edge_source: String) -> Self {
+        Self {
+            from,
+            to,
+            edge_type,
+            edge_source: Some(edge_source),
         }
     }
 
@@ -153,7 +246,12 @@ impl RemoveEdgeGraphUpdate {
 
 impl GraphUpdate for RemoveEdgeGraphUpdate {
     fn apply(self, graph: &mut NoteGraph) -> Result<()> {
-        graph.upd_remove_edge(&self.from, &self.to, &self.edge_type)
+        graph.upd_remove_edge(
+            &self.from,
+            &self.to,
+            &self.edge_type,
+            self.edge_source.as_deref(),
+        )
     }
 }
 
@@ -177,7 +275,57 @@ impl TransitiveRulesGraphUpdate {
 
 impl GraphUpdate for TransitiveRulesGraphUpdate {
     fn apply(self, graph: &mut NoteGraph) -> Result<()> {
-        graph.transitive_rules = self.new_rules;
+        graph.int_set_transitive_rules(self.new_rules);
+        graph.change_tracker.touch_rules();
         Ok(())
     }
 }
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct AddDynamicEdgeSourceGraphUpdate {
+    data: DynamicEdgeSource,
+}
+
+#[wasm_bindgen]
+impl AddDynamicEdgeSourceGraphUpdate {
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: DynamicEdgeSource) -> Self {
+        Self { data }
+    }
+
+    pub fn add_to_batch(self, batch: &mut BatchGraphUpdate) {
+        batch.add_update(self.into());
+    }
+}
+
+impl GraphUpdate for AddDynamicEdgeSourceGraphUpdate {
+    fn apply(self, graph: &mut NoteGraph) -> Result<()> {
+        graph.upd_add_dynamic_edge_source(self.data)
+    }
+}
+
+/// Removes all dynamic edge sources of a note, together with their edges.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct RemoveDynamicEdgeSourcesGraphUpdate {
+    source: String,
+}
+
+#[wasm_bindgen]
+impl RemoveDynamicEdgeSourcesGraphUpdate {
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: String) -> Self {
+        Self { source }
+    }
+
+    pub fn add_to_batch(self, batch: &mut BatchGraphUpdate) {
+        batch.add_update(self.into());
+    }
+}
+
+impl GraphUpdate for RemoveDynamicEdgeSourcesGraphUpdate {
+    fn apply(self, graph: &mut NoteGraph) -> Result<()> {
+        graph.upd_remove_dynamic_edge_sources(&self.source)
+    }
+}
@@ -0,0 +1,54 @@
+use std::rc::Rc;
+
+use hashbrown::HashSet;
+
+use crate::data::NGNodeIndex;
+
+/// Tracks which parts of the graph were touched by the updates of a batch.
+/// This is used to only rebuild the implied edges that can be affected by the
+/// batch.
+#[derive(Clone, Debug, Default)]
+pub struct ChangeTracker {
+    /// Nodes that had explicit edges added or removed.
+    pub touched_nodes: HashSet<NGNodeIndex>,
+    /// Nodes that were added or removed as unresolved nodes. These are removed
+    /// after the batch if they have no edges.
+    pub unresolved_nodes: HashSet<NGNodeIndex>,
+    /// Edge types of explicit edges that were added or removed.
+    pub touched_edge_types: HashSet<Rc<str>>,
+    /// Whether the transitive rules were replaced. If so, all implied edges
+    /// need to be rebuilt.
+    pub rules_changed: bool,
+    /// Nodes that were renamed, as old and new path, in the order of the
+    /// renames.
+    pub renamed_nodes: Vec<(String, String)>,
+}
+
+impl ChangeTracker {
+    pub fn new() -> ChangeTracker {
+        ChangeTracker::default()
+    }
+
+    /// Records that an explicit edge between two nodes was added or removed.
+    pub fn touch_edge(&mut self, source: NGNodeIndex, target: NGNodeIndex, edge_type: &Rc<str>) {
+        self.touched_nodes.insert(source);
+        self.touched_nodes.insert(target);
+        if !self.touched_edge_types.contains(edge_type) {
+            self.touched_edge_types.insert(Rc::clone(edge_type));
+        }
+    }
+
+    /// Records that a node was added or removed as an unresolved node.
+    pub fn touch_unresolved(&mut self, node: NGNodeIndex) {
+        self.unresolved_nodes.insert(node);
+    }
+
+    pub fn touch_rules(&mut self) {
+        self.rules_changed = true;
+    }
+
+    pub fn touch_rename(&mut self, old_path: &str, new_path: &str) {
+        self.renamed_nodes
+            .push((old_path.to_owned(), new_path.to_owned()));
+    }
+}
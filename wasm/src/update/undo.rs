@@ -0,0 +1,385 @@
+use hashbrown::{HashMap, HashSet};
+use petgraph::{
+    stable_graph::StableGraph,
+    visit::EdgeRef,
+    Directed,
+    Direction::{self, Incoming, Outgoing},
+};
+
+use crate::{
+    data::{edge::EdgeData, node::NodeData, rules::TransitiveGraphRule, NGEdgeIndex, NGNodeIndex},
+    dynamic_edges::DynamicEdgeSource,
+    graph::NoteGraph,
+};
+
+/// A change made by an update, together with the data needed to undo it.
+#[derive(Clone, Debug)]
+pub enum UndoEntry {
+    /// a node was added
+    AddNode(NGNodeIndex),
+    /// the data of a node was changed, this is the previous data
+    NodeWeight(NGNodeIndex, NodeData),
+    /// an edge was added
+    AddEdge(NGEdgeIndex),
+    /// an edge was removed
+    RemoveEdge {
+        edge: NGEdgeIndex,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        data: EdgeData,
+        /// the position in the outgoing edges of the source
+        out_position: usize,
+        /// the position in the incoming edges of the target
+        in_position: usize,
+    },
+    /// the data of an edge was changed, this is the previous data
+    EdgeWeight(NGEdgeIndex, EdgeData),
+    /// the transitive rules were replaced, these are the previous rules
+    TransitiveRules(Vec<TransitiveGraphRule>),
+    /// a dynamic edge source was added at the end of the list
+    AddDynamicEdgeSource,
+    /// the dynamic edge source at the index was removed
+    RemoveDynamicEdgeSource(usize, DynamicEdgeSource),
+    /// the dynamic edge source at the index was changed, this is the previous
+    /// edge source
+    DynamicEdgeSource(usize, DynamicEdgeSource),
+    /// a node was added to or removed from the targets of the dynamic edge
+    /// source at the index, this is whether it was a target before
+    DynamicEdgeTarget(usize, NGNodeIndex, bool),
+}
+
+/// Records the changes made by the updates of a batch, so that they can be
+/// undone if an update of the batch fails. Undoing the changes is much cheaper
+/// than copying the graph before every batch, as the log only grows with the
+/// size of the batch.
+///
+/// Only the changes made while applying the updates themselves are recorded.
+/// Implied edges and the edge type tracker are rebuilt after all updates of a
+/// batch succeeded, so they don't need to be undone.
+#[derive(Clone, Debug, Default)]
+pub struct UndoLog {
+    /// None if no changes are being recorded
+    entries: Option<Vec<UndoEntry>>,
+}
+
+impl UndoLog {
+    pub fn new() -> UndoLog {
+        UndoLog::default()
+    }
+
+    /// Starts recording changes, discarding any previously recorded changes.
+    pub fn start(&mut self) {
+        self.entries = Some(Vec::new());
+    }
+
+    /// Stops recording changes and returns the recorded changes.
+    pub fn stop(&mut self) -> Vec<UndoEntry> {
+        self.entries.take().unwrap_or_default()
+    }
+
+    /// Records a change, if changes are being recorded. The entry is only
+    /// created if it is recorded, so that no data is copied otherwise.
+    pub fn record(&mut self, entry: impl FnOnce() -> UndoEntry) {
+        if let Some(entries) = &mut self.entries {
+            entries.push(entry());
+        }
+    }
+}
+
+/// Methods that change the graph during updates and record the changes in
+/// the undo log. Updates must only change nodes, explicit edges, transitive
+/// rules and dynamic edge sources through these methods.
+impl NoteGraph {
+    /// Adds a node to the graph and the node hash.
+    pub(crate) fn int_add_node(&mut self, data: NodeData) -> NGNodeIndex {
+        let path = data.path.clone();
+        let node_index = self.graph.add_node(data);
+        self.node_hash.insert(path, node_index);
+        self.undo_log.record(|| UndoEntry::AddNode(node_index));
+
+        node_index
+    }
+
+    /// Renames a node and updates the node hash.
+    pub(crate) fn int_set_node_path(&mut self, node: NGNodeIndex, path: &str) {
+        let Some(node_weight) = self.graph.node_weight_mut(node) else {
+            return;
+        };
+
+        self.undo_log
+            .record(|| UndoEntry::NodeWeight(node, node_weight.clone()));
+        let old_path = std::mem::replace(&mut node_weight.path, path.to_owned());
+        self.node_hash.remove(&old_path);
+        self.node_hash.insert(path.to_owned(), node);
+    }
+
+    /// Adds an explicit edge to the graph.
+    pub(crate) fn int_add_edge(
+        &mut self,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        data: EdgeData,
+    ) -> NGEdgeIndex {
+        let edge = self.graph.add_edge(from, to, data);
+        self.undo_log.record(|| UndoEntry::AddEdge(edge));
+
+        edge
+    }
+
+    /// Removes an explicit edge from the graph and returns its data.
+    pub(crate) fn int_remove_edge(&mut self, edge: NGEdgeIndex) -> Option<EdgeData> {
+        let (from, to) = self.graph.edge_endpoints(edge)?;
+        let graph = &self.graph;
+        self.undo_log.record(|| UndoEntry::RemoveEdge {
+            edge,
+            from,
+            to,
+            data: graph[edge].clone(),
+            out_position: adjacency_position(graph, from, Outgoing, edge),
+            in_position: adjacency_position(graph, to, Incoming, edge),
+        });
+
+        self.graph.remove_edge(edge)
+    }
+
+    /// Replaces the transitive rules.
+    pub(crate) fn int_set_transitive_rules(&mut self, rules: Vec<TransitiveGraphRule>) {
+        let old_rules = std::mem::replace(&mut self.transitive_rules, rules);
+        self.undo_log
+            .record(|| UndoEntry::TransitiveRules(old_rules));
+    }
+
+    pub(crate) fn int_push_dynamic_edge_source(&mut self, edge_source: DynamicEdgeSource) {
+        self.dynamic_edge_sources.push(edge_source);
+        self.int_invalidate_dynamic_edge_source_index();
+        self.undo_log.record(|| UndoEntry::AddDynamicEdgeSource);
+    }
+
+    pub(crate) fn int_remove_dynamic_edge_source(&mut self, index: usize) -> DynamicEdgeSource {
+        let edge_source = self.dynamic_edge_sources.remove(index);
+        self.int_invalidate_dynamic_edge_source_index();
+        self.undo_log
+            .record(|| UndoEntry::RemoveDynamicEdgeSource(index, edge_source.clone()));
+
+        edge_source
+    }
+
+    /// Gets a mutable reference to a dynamic edge source and records its
+    /// current state.
+    pub(crate) fn int_get_dynamic_edge_source_mut(
+        &mut self,
+        index: usize,
+    ) -> &mut DynamicEdgeSource {
+        self.int_invalidate_dynamic_edge_source_index();
+        let edge_source = &mut self.dynamic_edge_sources[index];
+        self.undo_log
+            .record(|| UndoEntry::DynamicEdgeSource(index, edge_source.clone()));
+
+        edge_source
+    }
+
+    /// Adds a node to or removes it from the targets of the dynamic edge
+    /// source at the index.
+    pub(crate) fn int_set_dynamic_edge_target(
+        &mut self,
+        index: usize,
+        target: NGNodeIndex,
+        is_target: bool,
+    ) {
+        let targets = &mut self.dynamic_edge_sources[index].targets;
+        let changed = if is_target {
+            targets.insert(target)
+        } else {
+            targets.remove(&target)
+        };
+
+        if changed {
+            self.undo_log
+                .record(|| UndoEntry::DynamicEdgeTarget(index, target, !is_target));
+        }
+    }
+
+    /// Undoes recorded changes, in reverse order.
+    pub(crate) fn int_undo(&mut self, entries: Vec<UndoEntry>) {
+        for entry in entries.into_iter().rev() {
+            match entry {
+                UndoEntry::AddNode(node) => {
+                    if let Some(node_data) = self.graph.remove_node(node) {
+                        self.node_hash.remove(&node_data.path);
+                    }
+                }
+                UndoEntry::NodeWeight(node, node_data) => {
+                    if let Some(node_weight) = self.graph.node_weight_mut(node) {
+                        if node_weight.path != node_data.path {
+                            self.node_hash.remove(&node_weight.path);
+                            self.node_hash.insert(node_data.path.clone(), node);
+                        }
+                        *node_weight = node_data;
+                    }
+                }
+                UndoEntry::AddEdge(edge) => {
+                    self.graph.remove_edge(edge);
+                }
+                UndoEntry::RemoveEdge {
+                    edge,
+                    from,
+                    to,
+                    data,
+                    out_position,
+                    in_position,
+                } => {
+                    self.int_restore_edge(edge, from, to, data, out_position, in_position);
+                }
+                UndoEntry::EdgeWeight(edge, edge_data) => {
+                    if let Some(edge_weight) = self.graph.edge_weight_mut(edge) {
+                        *edge_weight = edge_data;
+                    }
+                }
+                UndoEntry::TransitiveRules(rules) => {
+                    self.transitive_rules = rules;
+                }
+                UndoEntry::AddDynamicEdgeSource => {
+                    self.dynamic_edge_sources.pop();
+                    self.int_invalidate_dynamic_edge_source_index();
+                }
+                UndoEntry::RemoveDynamicEdgeSource(index, edge_source) => {
+                    self.dynamic_edge_sources.insert(index, edge_source);
+                    self.int_invalidate_dynamic_edge_source_index();
+                }
+                UndoEntry::DynamicEdgeSource(index, edge_source) => {
+                    self.dynamic_edge_sources[index] = edge_source;
+                    self.int_invalidate_dynamic_edge_source_index();
+                }
+                UndoEntry::DynamicEdgeTarget(index, target, was_target) => {
+                    let targets = &mut self.dynamic_edge_sources[index].targets;
+                    if was_target {
+                        targets.insert(target);
+                    } else {
+                        targets.remove(&target);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds a removed edge back with its old index and at its old positions in
+    /// the outgoing edges of its source and the incoming edges of its target,
+    /// so that neighbours are iterated in the same order as before the edge
+    /// was removed. This relies on the graph being in the same state as right
+    /// after the removal.
+    ///
+    /// New edges are always added at the front of the edge lists of their
+    /// nodes and take the most recently freed index. So we remove the edge
+    /// and all edges in front of it in any edge list, transitively, and add
+    /// them again, back to front.
+    fn int_restore_edge(
+        &mut self,
+        edge: NGEdgeIndex,
+        from: NGNodeIndex,
+        to: NGNodeIndex,
+        edge_data: EdgeData,
+        out_position: usize,
+        in_position: usize,
+    ) {
+        let restored_edge = self.graph.add_edge(from, to, edge_data);
+        debug_assert_eq!(restored_edge, edge);
+        if out_position == 0 && in_position == 0 {
+            return;
+        }
+
+        let edge_list = |node: NGNodeIndex, direction: Direction| -> Vec<NGEdgeIndex> {
+            self.graph
+                .edges_directed(node, direction)
+                .map(|edge_ref| edge_ref.id())
+                .filter(|&other| other != edge)
+                .collect()
+        };
+
+        // the edge lists of the nodes as they should be, with the edge at its old
+        // positions
+        let mut edge_lists: HashMap<(NGNodeIndex, Direction), Vec<NGEdgeIndex>> = HashMap::new();
+        let mut out_list = edge_list(from, Outgoing);
+        out_list.insert(out_position.min(out_list.len()), edge);
+        edge_lists.insert((from, Outgoing), out_list);
+        let mut in_list = edge_list(to, Incoming);
+        in_list.insert(in_position.min(in_list.len()), edge);
+        edge_lists.insert((to, Incoming), in_list);
+
+        // the edges to add again, these form the front of every edge list they
+        // are in
+        let mut readded: HashSet<NGEdgeIndex> = HashSet::from([edge]);
+        let mut stack = vec![edge];
+        while let Some(current) = stack.pop() {
+            let (source, target) = self.graph.edge_endpoints(current).unwrap();
+            for key in [(source, Outgoing), (target, Incoming)] {
+                let list = edge_lists
+                    .entry(key)
+                    .or_insert_with(|| edge_list(key.0, key.1));
+                let position = list.iter().position(|&other| other == current).unwrap();
+                for &other in &list[..position] {
+                    if readded.insert(other) {
+                        stack.push(other);
+                    }
+                }
+            }
+        }
+
+        // an edge must be added before the edges in front of it, so we sort the
+        // edges topologically, starting with the edges at the back of the lists
+        let mut added_before: HashMap<NGEdgeIndex, Vec<NGEdgeIndex>> = HashMap::new();
+        let mut pending_count: HashMap<NGEdgeIndex, usize> = HashMap::new();
+        for list in edge_lists.values() {
+            let front = list.iter().take_while(|other| readded.contains(*other));
+            for (&before, &after) in front.clone().skip(1).zip(front) {
+                added_before.entry(before).or_default().push(after);
+                *pending_count.entry(after).or_default() += 1;
+            }
+        }
+
+        let mut ready: Vec<NGEdgeIndex> = readded
+            .iter()
+            .copied()
+            .filter(|other| !pending_count.contains_key(other))
+            .collect();
+        ready.sort_unstable_by(|a, b| b.cmp(a));
+        let mut order = Vec::with_capacity(readded.len());
+        while let Some(current) = ready.pop() {
+            order.push(current);
+            for &after in added_before.get(&current).into_iter().flatten() {
+                let count = pending_count.get_mut(&after).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(after);
+                }
+            }
+        }
+
+        // removing the edges in reverse order frees their indices so that adding
+        // them in order gives each edge its old index
+        let mut removed: HashMap<NGEdgeIndex, (NGNodeIndex, NGNodeIndex, EdgeData)> =
+            HashMap::new();
+        for &current in order.iter().rev() {
+            let (source, target) = self.graph.edge_endpoints(current).unwrap();
+            let data = self.graph.remove_edge(current).unwrap();
+            removed.insert(current, (source, target, data));
+        }
+        for current in order {
+            let (source, target, data) = removed.remove(&current).unwrap();
+            let index = self.graph.add_edge(source, target, data);
+            debug_assert_eq!(index, current);
+        }
+    }
+}
+
+/// The position of an edge in the outgoing or incoming edges of a node.
+fn adjacency_position(
+    graph: &StableGraph<NodeData, EdgeData, Directed, u32>,
+    node: NGNodeIndex,
+    direction: Direction,
+    edge: NGEdgeIndex,
+) -> usize {
+    graph
+        .edges_directed(node, direction)
+        .position(|edge_ref| edge_ref.id() == edge)
+        .unwrap_or(0)
+}
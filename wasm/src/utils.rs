@@ -123,6 +123,9 @@ pub type Result<T> = std::result::Result<T, NoteGraphError>;
 #[derive(Clone, Debug)]
 pub struct NoteGraphError {
     message: String,
+    /// The index of the update in a [crate::update::batch::BatchGraphUpdate]
+    /// that caused this error, if the error originated from a batch update.
+    update_index: Option<usize>,
 }
 
 #[wasm_bindgen]
@@ -131,6 +134,7 @@ impl NoteGraphError {
     pub fn new(message: &str) -> NoteGraphError {
         NoteGraphError {
             message: message.to_string(),
+            update_index: None,
         }
     }
 
@@ -139,12 +143,27 @@ impl NoteGraphError {
         self.message.clone()
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn update_index(&self) -> Option<usize> {
+        self.update_index
+    }
+
     #[wasm_bindgen(js_name = toString)]
     pub fn to_fancy_string(&self) -> String {
         format!("{self:#?}")
     }
 }
 
+impl NoteGraphError {
+    /// Marks this error as caused by the update at `index` in a batch update.
+    pub fn with_update_index(self, index: usize) -> NoteGraphError {
+        NoteGraphError {
+            message: format!("Update at index {index} failed: {}", self.message),
+            update_index: Some(index),
+        }
+    }
+}
+
 impl fmt::Display for NoteGraphError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.message)
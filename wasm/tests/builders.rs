@@ -0,0 +1,341 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use breadcrumbs_graph_wasm::{
+    builders::{
+        basename,
+        dendron::build_dendron_edges,
+        johnny_decimal::{build_johnny_decimal_edges, JohnnyDecimalPrefix},
+        BuilderNote, BuilderResult,
+    },
+    data::construction::GCNodeData,
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn notes(paths: &[&str]) -> Vec<BuilderNote> {
+    paths
+        .iter()
+        .map(|path| BuilderNote::new(path.to_string(), None))
+        .collect()
+}
+
+fn result_edges(result: &BuilderResult) -> Vec<(String, String, String)> {
+    let mut edges: Vec<_> = result
+        .edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.source.clone(),
+                edge.target.clone(),
+                edge.edge_type.clone(),
+            )
+        })
+        .collect();
+    edges.sort();
+    edges
+}
+
+fn result_nodes(result: &BuilderResult) -> Vec<String> {
+    let mut nodes: Vec<_> = result.nodes.iter().map(|node| node.path.clone()).collect();
+    nodes.sort();
+    nodes
+}
+
+fn edge(source: &str, target: &str, edge_type: &str) -> (String, String, String) {
+    (
+        source.to_string(),
+        target.to_string(),
+        edge_type.to_string(),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_edges() {
+    let result = build_dendron_edges(
+        notes(&["a.md", "a.b.md", "a.b.c.md", "folder/x.y.md", "other.md"]),
+        ".".to_string(),
+        "up".to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        result_edges(&result),
+        vec![
+            edge("a.b.c.md", "a.b.md", "up"),
+            edge("a.b.md", "a.md", "up"),
+            edge("folder/x.y.md", "folder/x.md", "up"),
+        ]
+    );
+    assert_eq!(result_nodes(&result), vec!["folder/x.md"]);
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_unresolved_intermediate_nodes() {
+    let result = build_dendron_edges(
+        notes(&["a.b.c.md", "a.b.d.md"]),
+        ".".to_string(),
+        "up".to_string(),
+    )
+    .unwrap();
+
+    // the unresolved nodes and their edges are only added once
+    assert_eq!(
+        result_edges(&result),
+        vec![
+            edge("a.b.c.md", "a.b.md", "up"),
+            edge("a.b.d.md", "a.b.md", "up"),
+            edge("a.b.md", "a.md", "up"),
+        ]
+    );
+    assert_eq!(result_nodes(&result), vec!["a.b.md", "a.md"]);
+    assert!(result.nodes.iter().all(|node| !node.resolved));
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_unresolved_nodes_use_each_field() {
+    let mut builder_notes = notes(&["a.b.c.md"]);
+    builder_notes.push(BuilderNote::new(
+        "a.b.d.md".to_string(),
+        Some("parent".to_string()),
+    ));
+
+    let result = build_dendron_edges(builder_notes, ".".to_string(), "up".to_string()).unwrap();
+
+    // the edges from the unresolved nodes use the field of each note below them
+    assert_eq!(
+        result_edges(&result),
+        vec![
+            edge("a.b.c.md", "a.b.md", "up"),
+            edge("a.b.d.md", "a.b.md", "parent"),
+            edge("a.b.md", "a.md", "parent"),
+            edge("a.b.md", "a.md", "up"),
+        ]
+    );
+    assert_eq!(result_nodes(&result), vec!["a.b.md", "a.md"]);
+}
+
+#[wasm_bindgen_test]
+fn test_basename() {
+    assert_eq!(basename("folder/note.md"), "note");
+    assert_eq!(basename("note"), "note");
+    // only the markdown extension is removed
+    assert_eq!(basename("folder/v1.2 release.md"), "v1.2 release");
+    assert_eq!(basename("folder/a.b"), "a.b");
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_custom_delimiter_and_field() {
+    let mut builder_notes = notes(&["x.md", "x__y.md"]);
+    builder_notes.push(BuilderNote::new(
+        "x__z.md".to_string(),
+        Some("parent".to_string()),
+    ));
+
+    let result = build_dendron_edges(builder_notes, "__".to_string(), "up".to_string()).unwrap();
+
+    assert_eq!(
+        result_edges(&result),
+        vec![
+            edge("x__y.md", "x.md", "up"),
+            edge("x__z.md", "x.md", "parent"),
+        ]
+    );
+    assert!(result.nodes.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_empty_field_and_delimiter() {
+    let result = build_dendron_edges(notes(&["a.b.md"]), ".".to_string(), "".to_string()).unwrap();
+    assert!(result.edges.is_empty());
+
+    assert!(build_dendron_edges(notes(&["a.b.md"]), "".to_string(), "up".to_string()).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_batch_update() {
+    let mut paths = vec!["a.md", "a.b.md", "a.b.c.md", "x.y.md"];
+    let nodes: Vec<GCNodeData> = paths
+        .iter()
+        .map(|path| GCNodeData::new(path.to_string(), vec![], true, false, false))
+        .collect();
+
+    let mut graph = common::tdata_to_graph((nodes, vec![]));
+    paths.push("x.md");
+
+    let result =
+        build_dendron_edges(notes(&paths[..4]), ".".to_string(), "up".to_string()).unwrap();
+    graph.apply_update(result.to_batch_update()).unwrap();
+
+    for path in paths {
+        assert!(graph.has_node(path.to_string()), "{path}");
+    }
+    assert_eq!(graph.int_edge_count(), 3);
+    assert!(!graph.get_node("x.md".to_string()).unwrap().resolved);
+}
+
+#[wasm_bindgen_test]
+fn test_dendron_without_hierarchy() {
+    let (nodes, _) = common::tdata_generate_tree(3, 2);
+    let builder_notes = nodes
+        .into_iter()
+        .map(|node| BuilderNote::new(format!("{}.md", node.path), None))
+        .collect();
+
+    let result = build_dendron_edges(builder_notes, ".".to_string(), "up".to_string()).unwrap();
+
+    assert!(result.nodes.is_empty());
+    assert!(result.edges.is_empty());
+}
+
+fn build_johnny_decimal(paths: &[&str]) -> BuilderResult {
+    build_johnny_decimal_edges(
+        notes(paths),
+        ".".to_string(),
+        "area".to_string(),
+        "category".to_string(),
+    )
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_johnny_decimal_prefix() {
+    let parse = |basename: &str| JohnnyDecimalPrefix::parse(basename, ".");
+
+    assert_eq!(parse("10-19 Finance"), Some(JohnnyDecimalPrefix::Area(1)));
+    assert_eq!(parse("11 Taxes"), Some(JohnnyDecimalPrefix::Category(11)));
+    assert_eq!(parse("11.04 Receipt"), Some(JohnnyDecimalPrefix::Id(11, 4)));
+    assert_eq!(parse("11.104"), Some(JohnnyDecimalPrefix::Id(11, 104)));
+    assert_eq!(
+        JohnnyDecimalPrefix::parse("11_04 Receipt", "_"),
+        Some(JohnnyDecimalPrefix::Id(11, 4))
+    );
+
+    assert_eq!(
+        parse("11.04 Receipt").and_then(|prefix| prefix.parent()),
+        Some(JohnnyDecimalPrefix::Category(11))
+    );
+    assert_eq!(
+        parse("11 Taxes").and_then(|prefix| prefix.parent()),
+        Some(JohnnyDecimalPrefix::Area(1))
+    );
+    assert_eq!(
+        parse("10-19 Finance").and_then(|prefix| prefix.parent()),
+        None
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_johnny_decimal_malformed_prefix() {
+    let parse = |basename: &str| JohnnyDecimalPrefix::parse(basename, ".");
+
+    for basename in [
+        "Finance",
+        "1 Taxes",
+        "111 Taxes",
+        "1.04 Receipt",
+        "11. Receipt",
+        "11.4 Receipt",
+        "11.0x Receipt",
+        "11.04.01 Receipt",
+        "11Taxes",
+        "10-18 Finance",
+        "11-20 Finance",
+        "10-19-29 Finance",
+        "ab-cd Finance",
+        "",
+    ] {
+        assert_eq!(parse(basename), None, "{basename}");
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_johnny_decimal_edges() {
+    let result = build_johnny_decimal(&[
+        "10-19 Finance.md",
+        "Finance/11 Taxes.md",
+        "Finance/Taxes/11.01 Returns.md",
+        "Finance/Taxes/11.02 Receipts.md",
+        "Finance/12 Banking.md",
+        "20-29 Health.md",
+        "Notes.md",
+    ]);
+
+    assert_eq!(
+        result_edges(&result),
+        vec![
+            edge("Finance/11 Taxes.md", "10-19 Finance.md", "area"),
+            edge("Finance/12 Banking.md", "10-19 Finance.md", "area"),
+            edge(
+                "Finance/Taxes/11.01 Returns.md",
+                "Finance/11 Taxes.md",
+                "category"
+            ),
+            edge(
+                "Finance/Taxes/11.02 Receipts.md",
+                "Finance/11 Taxes.md",
+                "category"
+            ),
+        ]
+    );
+    assert!(result.nodes.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_johnny_decimal_malformed_and_missing_parents() {
+    let result = build_johnny_decimal(&[
+        "10-19 Finance.md",
+        "1 Taxes.md",
+        "11.4 Receipt.md",
+        "11.04 Receipt.md",
+        "21 Doctors.md",
+    ]);
+
+    // the malformed notes are ignored and "11.04" and "21" have no parent note
+    assert!(result.edges.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_johnny_decimal_duplicated_prefix() {
+    let result = build_johnny_decimal(&[
+        "11 Taxes.md",
+        "11 Taxes (old).md",
+        "11.01 Returns.md",
+        "11.01 Returns copy.md",
+    ]);
+
+    assert_eq!(
+        result_edges(&result),
+        vec![
+            edge("11.01 Returns copy.md", "11 Taxes (old).md", "category"),
+            edge("11.01 Returns copy.md", "11 Taxes.md", "category"),
+            edge("11.01 Returns.md", "11 Taxes (old).md", "category"),
+            edge("11.01 Returns.md", "11 Taxes.md", "category"),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_johnny_decimal_field_override() {
+    let mut builder_notes = notes(&["10-19 Finance.md", "11 Taxes.md"]);
+    builder_notes.push(BuilderNote::new(
+        "11.01 Returns.md".to_string(),
+        Some("parent".to_string()),
+    ));
+
+    let result = build_johnny_decimal_edges(
+        builder_notes,
+        ".".to_string(),
+        "".to_string(),
+        "up".to_string(),
+    )
+    .unwrap();
+
+    // an empty edge type disables the category edges
+    assert_eq!(
+        result_edges(&result),
+        vec![edge("11.01 Returns.md", "11 Taxes.md", "parent")]
+    );
+}
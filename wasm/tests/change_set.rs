@@ -0,0 +1,241 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use std::{cell::RefCell, rc::Rc};
+
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        rules::TransitiveGraphRule,
+    },
+    graph::NoteGraph,
+    update::{
+        batch::BatchGraphUpdate, change_set::GraphChangeSet, AddEdgeGraphUpdate,
+        AddNoteGraphUpdate, RemoveEdgeGraphUpdate, RemoveNoteGraphUpdate, RenameNoteGraphUpdate,
+        TransitiveRulesGraphUpdate,
+    },
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn get_test_graph() -> NoteGraph {
+    let mut graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let mut batch = BatchGraphUpdate::new();
+    TransitiveRulesGraphUpdate::new(vec![TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["down".to_string()],
+        "up".to_string(),
+        5,
+        false,
+        true,
+    )])
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    graph
+}
+
+/// Registers a change callback that collects all change sets.
+fn collect_change_sets(graph: &mut NoteGraph) -> Rc<RefCell<Vec<GraphChangeSet>>> {
+    let change_sets = Rc::new(RefCell::new(Vec::new()));
+
+    let change_sets_clone = Rc::clone(&change_sets);
+    let closure = Closure::<dyn FnMut(GraphChangeSet)>::new(move |change_set| {
+        change_sets_clone.borrow_mut().push(change_set);
+    });
+    graph.set_change_callback(closure.as_ref().unchecked_ref::<js_sys::Function>().clone());
+    closure.forget();
+
+    change_sets
+}
+
+fn edge_paths(edges: &[GCEdgeData]) -> Vec<(&str, &str, &str)> {
+    edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.source.as_str(),
+                edge.target.as_str(),
+                edge.edge_type.as_str(),
+            )
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn test_change_set_add_node_and_edge() {
+    let mut graph = get_test_graph();
+    let change_sets = collect_change_sets(&mut graph);
+    let revision = graph.get_revision();
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(GCNodeData::new(
+        "new".to_string(),
+        vec![],
+        true,
+        false,
+        false,
+    ))
+    .add_to_batch(&mut batch);
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "0".to_string(),
+        "new".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let change_sets = change_sets.borrow();
+    assert_eq!(change_sets.len(), 1);
+    let change_set = &change_sets[0];
+
+    assert_eq!(change_set.old_revision, revision);
+    assert_eq!(change_set.new_revision, revision + 1);
+    assert_eq!(change_set.added_nodes, vec!["new"]);
+    assert!(change_set.removed_nodes.is_empty());
+    assert!(change_set.renamed_nodes.is_empty());
+    assert!(change_set.changed_nodes.is_empty());
+    assert_eq!(
+        edge_paths(&change_set.added_edges),
+        vec![("0", "new", "down")]
+    );
+    assert!(change_set.removed_edges.is_empty());
+    assert_eq!(
+        edge_paths(&change_set.gained_implied_edges),
+        vec![("new", "0", "up")]
+    );
+    assert!(change_set.lost_implied_edges.is_empty());
+
+    assert!(change_set.touches_node("0"));
+    assert!(change_set.touches_node("new"));
+    assert!(!change_set.touches_node("1"));
+}
+
+#[wasm_bindgen_test]
+fn test_change_set_remove_node() {
+    let mut graph = get_test_graph();
+    let change_sets = collect_change_sets(&mut graph);
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("1".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let change_sets = change_sets.borrow();
+    let change_set = &change_sets[0];
+
+    // 1 still has an incoming edge, so it becomes unresolved
+    assert!(change_set.added_nodes.is_empty());
+    assert!(change_set.removed_nodes.is_empty());
+    assert_eq!(change_set.changed_nodes, vec!["1"]);
+    assert!(change_set.added_edges.is_empty());
+    assert_eq!(
+        edge_paths(&change_set.removed_edges),
+        vec![("1", "10", "down"), ("1", "11", "down")]
+    );
+    assert_eq!(
+        edge_paths(&change_set.lost_implied_edges),
+        vec![("10", "1", "up"), ("11", "1", "up")]
+    );
+    assert!(change_set.gained_implied_edges.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_change_set_rename_node() {
+    let mut graph = get_test_graph();
+    let change_sets = collect_change_sets(&mut graph);
+
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("1".to_string(), "one".to_string()).add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("one".to_string(), "uno".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let change_sets = change_sets.borrow();
+    let change_set = &change_sets[0];
+
+    assert_eq!(change_set.renamed_nodes.len(), 1);
+    assert_eq!(change_set.renamed_nodes[0].old_path, "1");
+    assert_eq!(change_set.renamed_nodes[0].new_path, "uno");
+
+    // the edges of the renamed node did not change
+    assert!(change_set.added_nodes.is_empty());
+    assert!(change_set.removed_nodes.is_empty());
+    assert!(change_set.added_edges.is_empty());
+    assert!(change_set.removed_edges.is_empty());
+    assert!(change_set.gained_implied_edges.is_empty());
+    assert!(change_set.lost_implied_edges.is_empty());
+
+    assert!(change_set.touches_node("uno"));
+    assert!(!change_set.touches_node("10"));
+}
+
+#[wasm_bindgen_test]
+fn test_change_set_removed_orphan_and_readded_edge() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "0".to_string(),
+        "missing".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let change_sets = collect_change_sets(&mut graph);
+
+    let mut batch = BatchGraphUpdate::new();
+    // the edge to the unresolved node is removed with the note, so the unresolved
+    // node is removed as well
+    RemoveNoteGraphUpdate::new("0".to_string()).add_to_batch(&mut batch);
+    // an edge that is removed and added again is unchanged
+    RemoveEdgeGraphUpdate::new("1".to_string(), "10".to_string(), "down".to_string())
+        .add_to_batch(&mut batch);
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "1".to_string(),
+        "10".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let change_sets = change_sets.borrow();
+    let change_set = &change_sets[0];
+
+    assert!(change_set.added_nodes.is_empty());
+    assert_eq!(change_set.removed_nodes, vec!["missing"]);
+    assert_eq!(change_set.changed_nodes, vec!["0"]);
+    assert!(change_set.added_edges.is_empty());
+    assert_eq!(
+        edge_paths(&change_set.removed_edges),
+        vec![
+            ("0", "00", "down"),
+            ("0", "01", "down"),
+            ("0", "missing", "down")
+        ]
+    );
+    assert_eq!(
+        edge_paths(&change_set.lost_implied_edges),
+        vec![("00", "0", "up"), ("01", "0", "up"), ("missing", "0", "up")]
+    );
+    assert!(change_set.gained_implied_edges.is_empty());
+    assert!(!change_set.touches_node("10"));
+}
+
+#[wasm_bindgen_test]
+fn test_change_set_empty_update() {
+    let mut graph = get_test_graph();
+    let change_sets = collect_change_sets(&mut graph);
+
+    graph.apply_update(BatchGraphUpdate::new()).unwrap();
+
+    let change_sets = change_sets.borrow();
+    assert_eq!(change_sets.len(), 1);
+    assert!(change_sets[0].is_empty());
+}
@@ -0,0 +1,282 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::GCEdgeData,
+        rules::{RuleStep, TransitiveGraphRule},
+    },
+    graph::NoteGraph,
+    update::{batch::BatchGraphUpdate, AddEdgeGraphUpdate, TransitiveRulesGraphUpdate},
+};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn reverse_rule() -> TransitiveGraphRule {
+    TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["down".to_string()],
+        "up".to_string(),
+        1,
+        false,
+        true,
+    )
+}
+
+fn ancestor_rule() -> TransitiveGraphRule {
+    TransitiveGraphRule::new_closure(
+        "".to_string(),
+        vec![RuleStep::new("up".to_string(), false)],
+        "ancestor".to_string(),
+        false,
+        false,
+    )
+}
+
+fn build_graph(depth: u32, branches: u32, rules: Vec<TransitiveGraphRule>) -> NoteGraph {
+    let data = common::tdata_generate_tree(depth, branches);
+    let mut graph = NoteGraph::new();
+    graph.build_graph(data.0, data.1, rules).unwrap();
+
+    graph
+}
+
+/// The edges of an edge type as sorted source and target paths.
+fn edges_of_type(graph: &NoteGraph, edge_type: &str) -> Vec<(String, String)> {
+    let mut edges: Vec<(String, String)> = graph
+        .graph
+        .edge_references()
+        .filter(|edge| edge.weight().edge_type.as_ref() == edge_type)
+        .map(|edge| {
+            (
+                graph.graph[edge.source()].path.clone(),
+                graph.graph[edge.target()].path.clone(),
+            )
+        })
+        .collect();
+    edges.sort();
+
+    edges
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_on_deep_chain() {
+    // a chain that is deeper than the maximum number of rounds
+    let depth = 300;
+    let graph = build_graph(depth, 1, vec![reverse_rule(), ancestor_rule()]);
+
+    let deepest = "0".repeat(depth as usize);
+    assert!(graph.int_has_edge_by_name(&deepest, "root", "ancestor"));
+    assert!(graph.int_has_edge_by_name(&deepest, "0", "ancestor"));
+    assert!(!graph.int_has_edge_by_name("0", &deepest, "ancestor"));
+    assert!(!graph.int_has_edge_by_name("root", "root", "ancestor"));
+
+    // every node has an ancestor edge to all nodes above it
+    let depth = depth as usize;
+    assert_eq!(
+        edges_of_type(&graph, "ancestor").len(),
+        depth * (depth + 1) / 2
+    );
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_with_max_rounds() {
+    // closure rules don't count rounds
+    assert_eq!(ancestor_rule().rounds(), 0);
+
+    // the closure still gets its round after a rule with the maximum number of
+    // rounds
+    let max_rounds_rule = TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["down".to_string()],
+        "up".to_string(),
+        u8::MAX,
+        false,
+        true,
+    );
+    let graph = build_graph(3, 2, vec![max_rounds_rule, ancestor_rule()]);
+
+    assert!(graph.int_has_edge_by_name("000", "root", "ancestor"));
+    assert_eq!(edges_of_type(&graph, "ancestor").len(), 34);
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_matches_repeated_rule() {
+    let closure_graph = build_graph(4, 2, vec![reverse_rule(), ancestor_rule()]);
+    let repeated_graph = build_graph(
+        4,
+        2,
+        vec![
+            reverse_rule(),
+            TransitiveGraphRule::new(
+                "".to_string(),
+                vec!["up".to_string()],
+                "ancestor".to_string(),
+                10,
+                false,
+                false,
+            ),
+            TransitiveGraphRule::new(
+                "".to_string(),
+                vec!["ancestor".to_string(), "up".to_string()],
+                "ancestor".to_string(),
+                10,
+                false,
+                false,
+            ),
+        ],
+    );
+
+    assert_eq!(
+        edges_of_type(&closure_graph, "ancestor"),
+        edges_of_type(&repeated_graph, "ancestor")
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_with_longer_path() {
+    // every second node on the chain, starting from the start node
+    let graph = build_graph(
+        6,
+        1,
+        vec![TransitiveGraphRule::new_closure(
+            "".to_string(),
+            vec![
+                RuleStep::new("down".to_string(), false),
+                RuleStep::new("down".to_string(), false),
+            ],
+            "even".to_string(),
+            false,
+            false,
+        )],
+    );
+
+    assert_eq!(
+        edges_of_type(&graph, "even"),
+        vec![
+            ("0".to_string(), "000".to_string()),
+            ("0".to_string(), "00000".to_string()),
+            ("00".to_string(), "0000".to_string()),
+            ("00".to_string(), "000000".to_string()),
+            ("000".to_string(), "00000".to_string()),
+            ("0000".to_string(), "000000".to_string()),
+            ("root".to_string(), "00".to_string()),
+            ("root".to_string(), "0000".to_string()),
+            ("root".to_string(), "000000".to_string()),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_loops() {
+    let mut graph = build_graph(
+        3,
+        1,
+        vec![TransitiveGraphRule::new_closure(
+            "".to_string(),
+            vec![RuleStep::new("down".to_string(), false)],
+            "reachable".to_string(),
+            true,
+            false,
+        )],
+    );
+
+    // without a cycle, no node reaches itself
+    assert!(!graph.int_has_edge_by_name("0", "0", "reachable"));
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "000".to_string(),
+        "0".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    // nodes on the cycle reach themselves, the root is not on the cycle
+    assert!(graph.int_has_edge_by_name("0", "0", "reachable"));
+    assert!(graph.int_has_edge_by_name("000", "000", "reachable"));
+    assert!(graph.int_has_edge_by_name("00", "0", "reachable"));
+    assert!(!graph.int_has_edge_by_name("root", "root", "reachable"));
+    assert!(graph
+        .explain_edge("00".to_string(), "00".to_string(), "reachable".to_string())
+        .is_ok());
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_explain_closure_edge() {
+    let graph = build_graph(20, 1, vec![reverse_rule(), ancestor_rule()]);
+
+    let deepest = "0".repeat(20);
+    let explanation = graph
+        .explain_edge(deepest.clone(), "root".to_string(), "ancestor".to_string())
+        .unwrap();
+
+    // the edge rests on all down edges of the chain
+    let explicit_edges = explanation.explicit_edges();
+    assert_eq!(explicit_edges.len(), 20);
+    assert!(explicit_edges
+        .iter()
+        .all(|edge| edge.edge_type == "down" && edge.explicit));
+    // in the order of the path, from the deepest node up to the root
+    assert_eq!(explicit_edges[0].target, deepest);
+    assert_eq!(explicit_edges[19].source, "root");
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_incremental_update() {
+    let mut graph_1 = build_graph(10, 2, vec![reverse_rule(), ancestor_rule()]);
+    let mut graph_2 = build_graph(10, 2, vec![reverse_rule(), ancestor_rule()]);
+    graph_2.set_incremental_updates(false);
+
+    for graph in [&mut graph_1, &mut graph_2] {
+        let mut batch = BatchGraphUpdate::new();
+        AddEdgeGraphUpdate::new(GCEdgeData::new(
+            "0".repeat(10),
+            "new".to_string(),
+            "down".to_string(),
+            "typed-link".to_string(),
+        ))
+        .add_to_batch(&mut batch);
+        graph.apply_update(batch).unwrap();
+    }
+
+    assert!(graph_1.int_has_edge_by_name("new", "root", "ancestor"));
+    assert_eq!(
+        edges_of_type(&graph_1, "ancestor"),
+        edges_of_type(&graph_2, "ancestor")
+    );
+    graph_1.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_example_graph() {
+    let graph = ancestor_rule().create_example_graph().unwrap();
+
+    assert!(graph.int_has_edge_by_name("1", "2", "ancestor"));
+    assert!(graph.int_has_edge_by_name("1", "3", "ancestor"));
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rule_update() {
+    let mut graph = common::tdata_to_graph(common::tdata_generate_tree(5, 1));
+
+    // there are no up edges yet, so the closure rule adds nothing
+    let mut batch = BatchGraphUpdate::new();
+    TransitiveRulesGraphUpdate::new(vec![ancestor_rule()]).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+    assert!(edges_of_type(&graph, "ancestor").is_empty());
+
+    let mut batch = BatchGraphUpdate::new();
+    TransitiveRulesGraphUpdate::new(vec![reverse_rule(), ancestor_rule()]).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+    assert_eq!(edges_of_type(&graph, "ancestor").len(), 15);
+    assert!(graph.int_has_edge_by_name("00000", "root", "ancestor"));
+    graph.assert_correct_trackers();
+}
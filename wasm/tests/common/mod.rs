@@ -1,7 +1,12 @@
 use breadcrumbs_graph_wasm::{
     data::construction::{GCEdgeData, GCNodeData},
+    edge_sorting::{create_edge_sorter, sort_edges},
     graph::NoteGraph,
 };
+use petgraph::{
+    visit::EdgeRef,
+    Direction::{Incoming, Outgoing},
+};
 
 /// Generate a tree of nodes with explicit down edges for testing purposes.
 pub fn tdata_generate_tree(depth: u32, branches: u32) -> (Vec<GCNodeData>, Vec<GCEdgeData>) {
@@ -51,3 +56,37 @@ pub fn tdata_to_graph(data: (Vec<GCNodeData>, Vec<GCEdgeData>)) -> NoteGraph {
 
     graph
 }
+
+/// The indices of the outgoing and incoming edges of every node, in the order
+/// in which they are iterated.
+#[allow(dead_code)]
+pub fn edge_order(graph: &NoteGraph) -> Vec<(Vec<usize>, Vec<usize>)> {
+    graph
+        .graph
+        .node_indices()
+        .map(|node| {
+            let edges = |direction| {
+                graph
+                    .graph
+                    .edges_directed(node, direction)
+                    .map(|edge| edge.id().index())
+                    .collect()
+            };
+            (edges(Outgoing), edges(Incoming))
+        })
+        .collect()
+}
+
+/// The paths of the children of the root node, sorted by the edge sorter for
+/// `field`.
+#[allow(dead_code)]
+pub fn sorted_children(graph: &NoteGraph, field: &str, reverse: bool) -> Vec<String> {
+    let sorter = create_edge_sorter(field.to_string(), reverse).unwrap();
+    let edges = graph.get_outgoing_edges("root".to_string()).get_edges();
+
+    sort_edges(graph, edges, &sorter)
+        .unwrap()
+        .into_iter()
+        .map(|edge| edge.target_path(graph).unwrap())
+        .collect()
+}
@@ -0,0 +1,268 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        rules::TransitiveGraphRule,
+    },
+    graph::NoteGraph,
+    utils::graph_eq,
+};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn get_rules() -> Vec<TransitiveGraphRule> {
+    vec![TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["down".to_string()],
+        "up".to_string(),
+        5,
+        false,
+        true,
+    )]
+}
+
+fn get_graph(nodes: Vec<GCNodeData>, edges: Vec<GCEdgeData>) -> NoteGraph {
+    let mut graph = NoteGraph::new();
+    graph.build_graph(nodes, edges, get_rules()).unwrap();
+    graph
+}
+
+fn get_changed_data() -> (Vec<GCNodeData>, Vec<GCEdgeData>) {
+    let (mut nodes, mut edges) = common::tdata_generate_tree(2, 2);
+
+    // remove the node 11 and its incoming edge
+    nodes.retain(|node| node.path != "11");
+    edges.retain(|edge| edge.target != "11");
+
+    // change a node
+    let node = nodes.iter_mut().find(|node| node.path == "0").unwrap();
+    node.aliases = vec!["alias".to_string()];
+
+    // add a node with an edge
+    nodes.push(GCNodeData::new(
+        "new".to_string(),
+        vec![],
+        true,
+        false,
+        false,
+    ));
+    edges.push(GCEdgeData::new(
+        "root".to_string(),
+        "new".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ));
+
+    // change the source of an edge
+    let edge = edges
+        .iter_mut()
+        .find(|edge| edge.source == "1" && edge.target == "10")
+        .unwrap();
+    edge.edge_source = "dataview-note".to_string();
+
+    // add an edge to an unresolved node
+    edges.push(GCEdgeData::new(
+        "00".to_string(),
+        "missing".to_string(),
+        "next".to_string(),
+        "typed-link".to_string(),
+    ));
+
+    (nodes, edges)
+}
+
+#[wasm_bindgen_test]
+fn test_diff_equal_graphs() {
+    let graph_1 = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+    let graph_2 = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    assert!(graph_1.diff(&graph_2).is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_diff_summary() {
+    let (nodes, edges) = common::tdata_generate_tree(2, 2);
+    let graph_1 = get_graph(nodes, edges);
+    let (nodes, edges) = get_changed_data();
+    let graph_2 = get_graph(nodes, edges);
+
+    let diff = graph_1.diff(&graph_2);
+
+    let mut added_nodes = diff.get_added_nodes();
+    added_nodes.sort();
+    assert_eq!(added_nodes, vec!["missing", "new"]);
+    assert_eq!(diff.get_removed_nodes(), vec!["11"]);
+    assert_eq!(diff.get_changed_nodes(), vec!["0"]);
+
+    let added_edges = diff.get_added_edges();
+    assert_eq!(added_edges.len(), 3);
+    assert!(added_edges.iter().any(|edge| edge.source == "1"
+        && edge.target == "10"
+        && edge.edge_source == "dataview-note"));
+    assert!(added_edges
+        .iter()
+        .any(|edge| edge.source == "root" && edge.target == "new"));
+    assert!(added_edges
+        .iter()
+        .any(|edge| edge.source == "00" && edge.target == "missing"));
+
+    let removed_edges = diff.get_removed_edges();
+    assert_eq!(removed_edges.len(), 2);
+    assert!(removed_edges
+        .iter()
+        .any(|edge| edge.source == "1" && edge.target == "10" && edge.edge_source == "typed-link"));
+    assert!(removed_edges
+        .iter()
+        .any(|edge| edge.source == "1" && edge.target == "11"));
+}
+
+#[wasm_bindgen_test]
+fn test_diff_edge_attributes() {
+    let (nodes, edges) = common::tdata_generate_tree(2, 2);
+    let graph_1 = get_graph(nodes, edges);
+
+    let (nodes, mut edges) = common::tdata_generate_tree(2, 2);
+    let edge = edges
+        .iter_mut()
+        .find(|edge| edge.source == "root" && edge.target == "1")
+        .unwrap();
+    edge.set_order(Some(3));
+    edge.set_attribute("weight".to_string(), Some("2".to_string()));
+    let graph_2 = get_graph(nodes, edges);
+
+    // an edge with different attributes is replaced
+    let diff = graph_1.diff(&graph_2);
+    assert_eq!(diff.get_added_edges().len(), 1);
+    assert_eq!(diff.get_removed_edges().len(), 1);
+    assert_eq!(diff.get_added_edges()[0].order, Some(3));
+
+    let mut graph_1 = graph_1;
+    graph_1.apply_update(diff.to_batch_update()).unwrap();
+    assert!(graph_1.diff(&graph_2).is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_diff_apply_update() {
+    let (nodes, edges) = common::tdata_generate_tree(2, 2);
+    let mut graph_1 = get_graph(nodes, edges);
+    let (nodes, edges) = get_changed_data();
+    let graph_2 = get_graph(nodes, edges);
+
+    let diff = graph_1.diff(&graph_2);
+    graph_1.apply_update(diff.to_batch_update()).unwrap();
+
+    assert!(graph_1.diff(&graph_2).is_empty());
+    assert_eq!(graph_1.graph.node_count(), graph_2.graph.node_count());
+    assert_eq!(graph_1.graph.edge_count(), graph_2.graph.edge_count());
+    assert!(graph_1.has_node("new".to_string()));
+    assert!(!graph_1.has_node("11".to_string()));
+
+    // and back again
+    let (nodes, edges) = common::tdata_generate_tree(2, 2);
+    let graph_3 = get_graph(nodes, edges);
+
+    let diff = graph_1.diff(&graph_3);
+    graph_1.apply_update(diff.to_batch_update()).unwrap();
+
+    assert!(graph_1.diff(&graph_3).is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_diff_changed_nodes_keep_edges() {
+    let (nodes, edges) = common::tdata_generate_tree(2, 2);
+    let mut graph_1 = get_graph(nodes, edges);
+
+    let (mut nodes, edges) = common::tdata_generate_tree(2, 2);
+    let node = nodes.iter_mut().find(|node| node.path == "0").unwrap();
+    node.aliases = vec!["alias".to_string()];
+    let node = nodes.iter_mut().find(|node| node.path == "1").unwrap();
+    node.set_property("status".to_string(), JsValue::from_str("done"))
+        .unwrap();
+    let graph_2 = get_graph(nodes, edges);
+
+    let diff = graph_1.diff(&graph_2);
+    let mut changed_nodes = diff.get_changed_nodes();
+    changed_nodes.sort();
+    assert_eq!(changed_nodes, vec!["0", "1"]);
+    assert!(diff.get_added_edges().is_empty());
+    assert!(diff.get_removed_edges().is_empty());
+
+    // only the aliases and the property are set, the edges of the nodes are kept
+    let batch = diff.to_batch_update();
+    assert_eq!(batch.len(), 2);
+
+    graph_1.apply_update(batch).unwrap();
+    assert!(graph_1.diff(&graph_2).is_empty());
+    graph_1.assert_correct_trackers();
+}
+
+fn shared_edge_graph(sources: &[(&str, &str, u32)]) -> NoteGraph {
+    let nodes = ["a", "b"]
+        .map(|path| GCNodeData::new(path.to_string(), vec![], true, false, false))
+        .to_vec();
+    let mut edges = Vec::new();
+    for (edge_source, label, count) in sources {
+        let mut edge = GCEdgeData::new(
+            "a".to_string(),
+            "b".to_string(),
+            "down".to_string(),
+            edge_source.to_string(),
+        );
+        edge.set_label(Some(label.to_string()));
+        for _ in 0..*count {
+            edges.push(edge.clone());
+        }
+    }
+
+    get_graph(nodes, edges)
+}
+
+#[wasm_bindgen_test]
+fn test_diff_keeps_edge_source_order() {
+    let mut graph_1 = shared_edge_graph(&[("typed-link", "x", 1), ("list-note", "list", 1)]);
+    let graph_2 = shared_edge_graph(&[("typed-link", "y", 1), ("list-note", "list", 1)]);
+
+    let diff = graph_1.diff(&graph_2);
+    assert!(!diff.is_empty());
+
+    graph_1.apply_update(diff.to_batch_update()).unwrap();
+
+    // the label of the edge is still the one of the first edge source
+    let edge = graph_1.int_get_edge_by_name("a", "b", "down").unwrap();
+    assert_eq!(edge.weight().label, Some("y".to_string()));
+    assert!(graph_1.diff(&graph_2).is_empty());
+    assert!(graph_eq(&graph_1.graph, &graph_2.graph));
+    graph_1.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_diff_edge_source_order_changed() {
+    let mut graph_1 = shared_edge_graph(&[("typed-link", "x", 1), ("list-note", "list", 1)]);
+    let graph_2 = shared_edge_graph(&[("list-note", "list", 1), ("typed-link", "x", 1)]);
+
+    let diff = graph_1.diff(&graph_2);
+    assert!(!diff.is_empty());
+
+    graph_1.apply_update(diff.to_batch_update()).unwrap();
+    assert!(graph_1.diff(&graph_2).is_empty());
+    assert!(graph_eq(&graph_1.graph, &graph_2.graph));
+}
+
+#[wasm_bindgen_test]
+fn test_diff_edge_source_counts() {
+    let mut graph_1 = shared_edge_graph(&[("typed-link", "x", 3), ("list-note", "list", 1)]);
+    let graph_2 = shared_edge_graph(&[("typed-link", "x", 1), ("list-note", "list", 2)]);
+
+    // only the differences in the counts are applied
+    let diff = graph_1.diff(&graph_2);
+    assert_eq!(diff.to_batch_update().len(), 3);
+
+    graph_1.apply_update(diff.to_batch_update()).unwrap();
+    assert!(graph_1.diff(&graph_2).is_empty());
+    assert!(graph_eq(&graph_1.graph, &graph_2.graph));
+}
@@ -0,0 +1,731 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        rules::TransitiveGraphRule,
+    },
+    dynamic_edges::DynamicEdgeSource,
+    graph::NoteGraph,
+    update::{
+        batch::BatchGraphUpdate, AddDynamicEdgeSourceGraphUpdate, AddEdgeGraphUpdate,
+        AddNoteGraphUpdate, RemoveDynamicEdgeSourcesGraphUpdate, RemoveNoteGraphUpdate,
+        RenameNoteGraphUpdate, SetNoteDataGraphUpdate, TransitiveRulesGraphUpdate,
+    },
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn node(path: &str) -> GCNodeData {
+    GCNodeData::new(path.to_string(), vec![], true, false, false)
+}
+
+fn get_test_graph() -> NoteGraph {
+    let nodes = ["index.md", "notes/a.md", "notes/b.md", "other.md"]
+        .into_iter()
+        .map(node)
+        .collect();
+
+    let mut graph = common::tdata_to_graph((nodes, vec![]));
+    graph
+        .apply_update(regex_source_update("index.md", "^notes/", ""))
+        .unwrap();
+
+    graph
+}
+
+fn regex_source_update(source: &str, pattern: &str, flags: &str) -> BatchGraphUpdate {
+    let mut batch = BatchGraphUpdate::new();
+    AddDynamicEdgeSourceGraphUpdate::new(
+        DynamicEdgeSource::regex(
+            source.to_string(),
+            pattern.to_string(),
+            flags.to_string(),
+            "down".to_string(),
+        )
+        .unwrap(),
+    )
+    .add_to_batch(&mut batch);
+    batch
+}
+
+fn down_targets(graph: &NoteGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .get_outgoing_edges(source.to_string())
+        .edges
+        .iter()
+        .filter(|edge| edge.edge_type.as_ref() == "down")
+        .map(|edge| edge.target_path(graph).unwrap())
+        .collect();
+    targets.sort();
+    targets
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source() {
+    let graph = get_test_graph();
+
+    assert_eq!(
+        down_targets(&graph, "index.md"),
+        vec!["notes/a.md", "notes/b.md"]
+    );
+    assert_eq!(
+        graph
+            .get_outgoing_edges("index.md".to_string())
+            .edges
+            .iter()
+            .map(|edge| edge.edge_source(&graph).unwrap())
+            .collect::<Vec<_>>(),
+        vec!["regex_note", "regex_note"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_invalid_regex() {
+    assert!(DynamicEdgeSource::regex(
+        "index.md".to_string(),
+        "notes/(".to_string(),
+        "".to_string(),
+        "down".to_string(),
+    )
+    .is_err());
+
+    let nodes = vec![node("other.md")];
+    let mut graph = common::tdata_to_graph((nodes, vec![]));
+    assert!(graph
+        .apply_update(regex_source_update("index.md", "notes/", ""))
+        .is_err());
+    assert!(graph.dynamic_edge_sources.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_flags() {
+    let nodes = ["index.md", "Notes/a.md", "notes/b.md"]
+        .into_iter()
+        .map(node)
+        .collect();
+    let mut graph = common::tdata_to_graph((nodes, vec![]));
+
+    // the global flag makes the regex stateful, which must not skip matches
+    graph
+        .apply_update(regex_source_update("index.md", "^notes/", "gi"))
+        .unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "index.md"),
+        vec!["Notes/a.md", "notes/b.md"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_add_and_rename() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("notes/a.md".to_string(), "archive/a.md".to_string())
+        .add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("other.md".to_string(), "notes/other.md".to_string())
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "index.md"),
+        vec!["notes/b.md", "notes/c.md", "notes/other.md"]
+    );
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_remove_target() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("notes/b.md".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    // the removed node is no longer matched, so it is removed from the graph
+    assert_eq!(down_targets(&graph, "index.md"), vec!["notes/a.md"]);
+    assert!(!graph.has_node("notes/b.md".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_rename_source() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("index.md".to_string(), "home.md".to_string())
+        .add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "home.md"),
+        vec!["notes/a.md", "notes/b.md", "notes/c.md"]
+    );
+}
+
+fn edge_source_count(
+    graph: &NoteGraph,
+    source: &str,
+    target: &str,
+    edge_source: &str,
+) -> Option<u32> {
+    graph
+        .int_get_edge_by_name(source, target, "down")
+        .and_then(|edge| {
+            edge.weight()
+                .sources
+                .iter()
+                .find(|source| source.edge_source.as_ref() == edge_source)
+                .map(|source| source.count)
+        })
+}
+
+#[wasm_bindgen_test]
+fn test_overlapping_regex_edge_sources() {
+    let mut graph = get_test_graph();
+    graph
+        .apply_update(regex_source_update("index.md", "^notes/a", ""))
+        .unwrap();
+
+    // both edge sources assert the edge to notes/a.md
+    assert_eq!(
+        edge_source_count(&graph, "index.md", "notes/a.md", "regex_note"),
+        Some(2)
+    );
+    assert_eq!(
+        edge_source_count(&graph, "index.md", "notes/b.md", "regex_note"),
+        Some(1)
+    );
+
+    // only the second edge source stops matching, the first one keeps the edge
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("notes/a.md".to_string(), "notes/c.md".to_string())
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "index.md"),
+        vec!["notes/b.md", "notes/c.md"]
+    );
+    assert_eq!(
+        edge_source_count(&graph, "index.md", "notes/c.md", "regex_note"),
+        Some(1)
+    );
+
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("notes/c.md".to_string(), "notes/a.md".to_string())
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        edge_source_count(&graph, "index.md", "notes/a.md", "regex_note"),
+        Some(2)
+    );
+
+    // the ownership survives a snapshot
+    let mut restored = NoteGraph::from_snapshot(&graph.to_snapshot()).unwrap();
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("notes/a.md".to_string(), "other/a.md".to_string())
+        .add_to_batch(&mut batch);
+    restored.apply_update(batch).unwrap();
+
+    assert_eq!(down_targets(&restored, "index.md"), vec!["notes/b.md"]);
+    restored.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_remove_dynamic_edge_sources() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "index.md".to_string(),
+        "other.md".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    RemoveDynamicEdgeSourcesGraphUpdate::new("index.md".to_string()).add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    // explicit edges of other edge sources are kept
+    assert_eq!(down_targets(&graph, "index.md"), vec!["other.md"]);
+    assert!(graph.dynamic_edge_sources.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_dynamic_edge_shared_with_explicit_edge() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "index.md".to_string(),
+        "notes/a.md".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let edge_sources = |graph: &NoteGraph| {
+        graph
+            .get_outgoing_edges("index.md".to_string())
+            .get_edges()
+            .into_iter()
+            .find(|edge| edge.target_path(graph).unwrap() == "notes/a.md")
+            .map(|edge| edge.edge_sources(graph).unwrap())
+    };
+    assert_eq!(
+        edge_sources(&graph),
+        Some(vec!["regex_note".to_string(), "typed-link".to_string()])
+    );
+
+    // the typed link keeps the edge when the dynamic edge source is removed
+    let mut batch = BatchGraphUpdate::new();
+    RemoveDynamicEdgeSourcesGraphUpdate::new("index.md".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(edge_sources(&graph), Some(vec!["typed-link".to_string()]));
+    assert_eq!(down_targets(&graph, "index.md"), vec!["notes/a.md"]);
+}
+
+#[wasm_bindgen_test]
+fn test_remove_source_note_removes_dynamic_edge_sources() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("index.md".to_string()).add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("index.md")).add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert!(down_targets(&graph, "index.md").is_empty());
+    assert!(graph.dynamic_edge_sources.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_remove_note_clears_note_data() {
+    let mut graph = get_test_graph();
+
+    let mut data = tagged_node("notes/c.md", &["#project"]);
+    data.aliases = vec!["C".to_string()];
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(data).add_to_batch(&mut batch);
+    // a link to the note keeps its node in the graph after it is removed
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "other.md".to_string(),
+        "notes/c.md".to_string(),
+        "next".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("notes/c.md".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    // only the path is left, like for any other unresolved node
+    let node = graph.get_node("notes/c.md".to_string()).unwrap();
+    assert!(!node.resolved);
+    assert!(node.aliases.is_empty());
+    assert!(node.tags.is_empty());
+    assert!(node.get_property_keys().is_empty());
+    assert_eq!(
+        down_targets(&graph, "index.md"),
+        vec!["notes/a.md", "notes/b.md"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_implied_edges() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    TransitiveRulesGraphUpdate::new(vec![TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["down".to_string()],
+        "up".to_string(),
+        5,
+        false,
+        true,
+    )])
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert!(graph.int_has_edge_by_name("notes/c.md", "index.md", "up"));
+    assert!(graph.int_has_edge_by_name("notes/a.md", "index.md", "up"));
+}
+
+#[wasm_bindgen_test]
+fn test_failed_update_restores_dynamic_edge_sources() {
+    let mut graph = get_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveDynamicEdgeSourcesGraphUpdate::new("index.md".to_string()).add_to_batch(&mut batch);
+    RemoveNoteGraphUpdate::new("missing.md".to_string()).add_to_batch(&mut batch);
+    assert!(graph.apply_update(batch).is_err());
+
+    assert_eq!(graph.dynamic_edge_sources.len(), 1);
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "index.md"),
+        vec!["notes/a.md", "notes/b.md", "notes/c.md"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_dynamic_edge_sources_in_snapshot() {
+    let graph = get_test_graph();
+
+    let mut restored = NoteGraph::from_snapshot(&graph.to_snapshot()).unwrap();
+    assert_eq!(restored.get_revision(), graph.get_revision());
+    assert_eq!(restored.dynamic_edge_sources.len(), 1);
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(node("notes/c.md")).add_to_batch(&mut batch);
+    restored.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&restored, "index.md"),
+        vec!["notes/a.md", "notes/b.md", "notes/c.md"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_regex_edge_source_existing_edges() {
+    let mut graph = common::tdata_to_graph(common::tdata_generate_tree(3, 2));
+
+    graph
+        .apply_update(regex_source_update("root", "^1", ""))
+        .unwrap();
+
+    // "root" already has an explicit edge to "1", which is not duplicated
+    assert_eq!(
+        down_targets(&graph, "root"),
+        vec!["0", "1", "10", "100", "101", "11", "110", "111"]
+    );
+    assert_eq!(
+        graph
+            .get_outgoing_edges("root".to_string())
+            .edges
+            .iter()
+            .filter(|edge| edge.edge_source(&graph).unwrap() == "regex_note")
+            .count(),
+        6
+    );
+}
+
+fn tagged_node(path: &str, tags: &[&str]) -> GCNodeData {
+    let mut node = node(path);
+    node.tags = tags.iter().map(|tag| tag.to_string()).collect();
+    node
+}
+
+fn get_folder_and_tag_test_graph() -> NoteGraph {
+    let nodes = vec![
+        node("projects/index.md"),
+        tagged_node("projects/a.md", &["#project"]),
+        tagged_node("projects/sub/b.md", &["project/active"]),
+        node("projects/image.png"),
+        tagged_node("other/c.md", &["#projects"]),
+        node("other/index.md"),
+    ];
+
+    common::tdata_to_graph((nodes, vec![]))
+}
+
+fn add_edge_source(graph: &mut NoteGraph, edge_source: DynamicEdgeSource) {
+    let mut batch = BatchGraphUpdate::new();
+    AddDynamicEdgeSourceGraphUpdate::new(edge_source).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+}
+
+#[wasm_bindgen_test]
+fn test_folder_edge_source() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::folder("projects/index.md".to_string(), "down".to_string(), false),
+    );
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::folder("other/index.md".to_string(), "down".to_string(), true),
+    );
+
+    // only markdown notes, and subfolders only when recursing
+    assert_eq!(
+        down_targets(&graph, "projects/index.md"),
+        vec!["projects/a.md"]
+    );
+    assert_eq!(down_targets(&graph, "other/index.md"), vec!["other/c.md"]);
+
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("projects/a.md".to_string(), "other/sub/a.md".to_string())
+        .add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("projects/d.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "projects/index.md"),
+        vec!["projects/d.md"]
+    );
+    assert_eq!(
+        down_targets(&graph, "other/index.md"),
+        vec!["other/c.md", "other/sub/a.md"]
+    );
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_folder_edge_source_move_source() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::folder("projects/index.md".to_string(), "down".to_string(), true),
+    );
+
+    assert_eq!(
+        down_targets(&graph, "projects/index.md"),
+        vec!["projects/a.md", "projects/sub/b.md"]
+    );
+
+    // moving the folder note changes the folder
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new(
+        "projects/index.md".to_string(),
+        "other/projects.md".to_string(),
+    )
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "other/projects.md"),
+        vec!["other/c.md", "other/index.md"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_tag_edge_source() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::tag(
+            "projects/index.md".to_string(),
+            "project".to_string(),
+            "down".to_string(),
+            false,
+        ),
+    );
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::tag(
+            "other/index.md".to_string(),
+            "#project".to_string(),
+            "down".to_string(),
+            true,
+        ),
+    );
+
+    // "#projects" is a different tag, not a nested one
+    assert_eq!(
+        down_targets(&graph, "projects/index.md"),
+        vec!["projects/a.md", "projects/sub/b.md"]
+    );
+    assert_eq!(
+        down_targets(&graph, "other/index.md"),
+        vec!["projects/a.md"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_overlapping_folder_edge_sources() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::folder("projects/index.md".to_string(), "down".to_string(), false),
+    );
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::folder("projects/index.md".to_string(), "down".to_string(), true),
+    );
+
+    let count = |graph: &NoteGraph, source: &str, target: &str| {
+        edge_source_count(graph, source, target, "folder_note")
+    };
+    assert_eq!(count(&graph, "projects/index.md", "projects/a.md"), Some(2));
+    assert_eq!(
+        count(&graph, "projects/index.md", "projects/sub/b.md"),
+        Some(1)
+    );
+
+    // the recursive edge source keeps the edge to the note moved to a subfolder
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new("projects/a.md".to_string(), "projects/sub/a.md".to_string())
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        count(&graph, "projects/index.md", "projects/sub/a.md"),
+        Some(1)
+    );
+
+    // both edge sources move with their note
+    let mut batch = BatchGraphUpdate::new();
+    RenameNoteGraphUpdate::new(
+        "projects/index.md".to_string(),
+        "other/index2.md".to_string(),
+    )
+    .add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("other/d.md")).add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("other/sub/e.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "other/index2.md"),
+        vec![
+            "other/c.md",
+            "other/d.md",
+            "other/index.md",
+            "other/sub/e.md"
+        ]
+    );
+    assert_eq!(count(&graph, "other/index2.md", "other/d.md"), Some(2));
+    assert_eq!(count(&graph, "other/index2.md", "other/sub/e.md"), Some(1));
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_overlapping_tag_edge_sources() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::tag(
+            "other/index.md".to_string(),
+            "project".to_string(),
+            "down".to_string(),
+            false,
+        ),
+    );
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::tag(
+            "other/index.md".to_string(),
+            "#project".to_string(),
+            "down".to_string(),
+            true,
+        ),
+    );
+
+    let count = |graph: &NoteGraph, target: &str| {
+        edge_source_count(graph, "other/index.md", target, "tag_note")
+    };
+    assert_eq!(count(&graph, "projects/a.md"), Some(2));
+    assert_eq!(count(&graph, "projects/sub/b.md"), Some(1));
+
+    // only the exact edge source stops matching the nested tag
+    let mut batch = BatchGraphUpdate::new();
+    SetNoteDataGraphUpdate::new(tagged_node("projects/a.md", &["#project/done"]))
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "other/index.md"),
+        vec!["projects/a.md", "projects/sub/b.md"]
+    );
+    assert_eq!(count(&graph, "projects/a.md"), Some(1));
+
+    // and neither matches once the tag is gone
+    let mut batch = BatchGraphUpdate::new();
+    SetNoteDataGraphUpdate::new(node("projects/a.md")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "other/index.md"),
+        vec!["projects/sub/b.md"]
+    );
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_tag_edge_source_tag_changes() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::tag(
+            "other/index.md".to_string(),
+            "#project".to_string(),
+            "down".to_string(),
+            false,
+        ),
+    );
+
+    // changing the tags of a note is done by removing and adding it again
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("projects/a.md".to_string()).add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(node("projects/a.md")).add_to_batch(&mut batch);
+    RemoveNoteGraphUpdate::new("other/c.md".to_string()).add_to_batch(&mut batch);
+    AddNoteGraphUpdate::new(tagged_node("other/c.md", &["#project/done"])).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&graph, "other/index.md"),
+        vec!["other/c.md", "projects/sub/b.md"]
+    );
+    assert!(graph
+        .get_node("other/c.md".to_string())
+        .unwrap()
+        .has_tag("project", false));
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_folder_and_tag_edge_sources_in_snapshot() {
+    let mut graph = get_folder_and_tag_test_graph();
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::folder("projects/index.md".to_string(), "down".to_string(), false),
+    );
+    add_edge_source(
+        &mut graph,
+        DynamicEdgeSource::tag(
+            "other/index.md".to_string(),
+            "project".to_string(),
+            "down".to_string(),
+            true,
+        ),
+    );
+
+    let mut restored = NoteGraph::from_snapshot(&graph.to_snapshot()).unwrap();
+    assert_eq!(
+        restored.get_node("projects/a.md".to_string()).unwrap().tags,
+        vec!["#project"]
+    );
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(tagged_node("projects/e.md", &["project"])).add_to_batch(&mut batch);
+    restored.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_targets(&restored, "projects/index.md"),
+        vec!["projects/a.md", "projects/e.md"]
+    );
+    assert_eq!(
+        down_targets(&restored, "other/index.md"),
+        vec!["projects/a.md", "projects/e.md"]
+    );
+}
@@ -0,0 +1,203 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        rules::TransitiveGraphRule,
+    },
+    graph::NoteGraph,
+    update::{batch::BatchGraphUpdate, AddEdgeGraphUpdate, RemoveEdgeGraphUpdate},
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+/// A graph where the root has a down edge to each of the nodes `0` to `3`,
+/// with the order index reversed for `0` to `2` and no order index for `3`.
+fn get_test_graph() -> NoteGraph {
+    let (nodes, mut edges) = common::tdata_generate_tree(1, 4);
+    for edge in &mut edges {
+        let position: u32 = edge.target.parse().unwrap();
+        if position < 3 {
+            edge.set_order(Some(2 - position));
+        }
+    }
+
+    common::tdata_to_graph((nodes, edges))
+}
+
+#[wasm_bindgen_test]
+fn test_edge_attributes_are_kept() {
+    let mut graph = common::tdata_to_graph((
+        vec![
+            GCNodeData::new("a".to_string(), vec![], true, false, false),
+            GCNodeData::new("b".to_string(), vec![], true, false, false),
+        ],
+        vec![],
+    ));
+
+    let mut edge = GCEdgeData::new(
+        "a".to_string(),
+        "b".to_string(),
+        "down".to_string(),
+        "list_note".to_string(),
+    );
+    edge.set_order(Some(2));
+    edge.set_label(Some("child".to_string()));
+    edge.set_attribute("weight".to_string(), Some("3".to_string()));
+    edge.set_attribute("removed".to_string(), Some("x".to_string()));
+    edge.set_attribute("removed".to_string(), None);
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(edge).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let edge = graph.get_outgoing_edges("a".to_string()).first().unwrap();
+    assert_eq!(edge.order(&graph).unwrap(), Some(2));
+    assert_eq!(edge.label(&graph).unwrap(), Some("child".to_string()));
+
+    let edge_data = edge.edge_data(&graph).unwrap();
+    assert_eq!(edge_data.get_attribute("weight"), Some("3".to_string()));
+    assert_eq!(edge_data.get_attribute_keys(), vec!["weight"]);
+}
+
+#[wasm_bindgen_test]
+fn test_edge_attributes_per_edge_source() {
+    let mut graph = common::tdata_to_graph((
+        vec![
+            GCNodeData::new("a".to_string(), vec![], true, false, false),
+            GCNodeData::new("b".to_string(), vec![], true, false, false),
+        ],
+        vec![],
+    ));
+
+    let edge = |edge_source: &str, order: u32, label: &str| {
+        let mut edge = GCEdgeData::new(
+            "a".to_string(),
+            "b".to_string(),
+            "down".to_string(),
+            edge_source.to_string(),
+        );
+        edge.set_order(Some(order));
+        edge.set_label(Some(label.to_string()));
+        edge.set_attribute("source".to_string(), Some(edge_source.to_string()));
+        edge
+    };
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(edge("list_note", 1, "first")).add_to_batch(&mut batch);
+    AddEdgeGraphUpdate::new(edge("typed-link", 2, "second")).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    // the edge has the data of its first edge source
+    let edge_data = graph
+        .get_outgoing_edges("a".to_string())
+        .first()
+        .unwrap()
+        .edge_data(&graph)
+        .unwrap();
+    assert_eq!(edge_data.order, Some(1));
+    assert_eq!(edge_data.label, Some("first".to_string()));
+
+    let snapshot = NoteGraph::from_snapshot(&graph.to_snapshot()).unwrap();
+
+    // once the first edge source is removed, the edge has the data of the second
+    // one, the data of both is kept in snapshots
+    for mut graph in [graph, snapshot] {
+        let mut batch = BatchGraphUpdate::new();
+        RemoveEdgeGraphUpdate::with_source(
+            "a".to_string(),
+            "b".to_string(),
+            "down".to_string(),
+            "list_note".to_string(),
+        )
+        .add_to_batch(&mut batch);
+        graph.apply_update(batch).unwrap();
+
+        let edge_data = graph
+            .get_outgoing_edges("a".to_string())
+            .first()
+            .unwrap()
+            .edge_data(&graph)
+            .unwrap();
+        assert_eq!(edge_data.get_edge_source(), "typed-link");
+        assert_eq!(edge_data.order, Some(2));
+        assert_eq!(edge_data.label, Some("second".to_string()));
+        assert_eq!(
+            edge_data.get_attribute("source"),
+            Some("typed-link".to_string())
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_attribute_label() {
+    let mut edge = GCEdgeData::new(
+        "a".to_string(),
+        "b".to_string(),
+        "down".to_string(),
+        "list_note".to_string(),
+    );
+    edge.set_order(Some(2));
+    edge.set_attribute("weight".to_string(), Some("3".to_string()));
+    let edge_data = edge.to_edge_data();
+
+    let attributes = |attributes: &[&str]| {
+        edge_data.attribute_label(&attributes.iter().map(|a| a.to_string()).collect())
+    };
+
+    assert_eq!(attributes(&["weight"]), "3");
+    assert_eq!(
+        attributes(&["field", "order", "weight"]),
+        "field=down order=2 weight=3"
+    );
+    // missing attributes are skipped
+    assert_eq!(attributes(&["label", "missing", "order"]), "2");
+}
+
+#[wasm_bindgen_test]
+fn test_sort_by_order() {
+    let graph = get_test_graph();
+
+    // edges without an order index come last
+    assert_eq!(
+        common::sorted_children(&graph, "order", false),
+        vec!["2", "1", "0", "3"]
+    );
+    assert_eq!(
+        common::sorted_children(&graph, "order", true),
+        vec!["3", "0", "1", "2"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_implied_edges_have_no_order() {
+    let (nodes, mut edges) = common::tdata_generate_tree(1, 2);
+    for edge in &mut edges {
+        edge.set_order(Some(1));
+        edge.set_label(Some("child".to_string()));
+    }
+
+    let mut graph = NoteGraph::new();
+    graph
+        .build_graph(
+            nodes,
+            edges,
+            vec![TransitiveGraphRule::new(
+                "".to_string(),
+                vec!["down".to_string()],
+                "up".to_string(),
+                5,
+                false,
+                true,
+            )],
+        )
+        .unwrap();
+
+    let edge = graph.get_outgoing_edges("0".to_string()).first().unwrap();
+    assert_eq!(edge.edge_type(), "up");
+    assert_eq!(edge.order(&graph).unwrap(), None);
+    assert_eq!(edge.label(&graph).unwrap(), None);
+}
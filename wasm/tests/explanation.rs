@@ -0,0 +1,231 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use breadcrumbs_graph_wasm::{
+    data::{construction::GCEdgeData, rules::TransitiveGraphRule},
+    explanation::EdgeExplanation,
+    graph::NoteGraph,
+    update::{batch::BatchGraphUpdate, AddEdgeGraphUpdate},
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn get_test_graph(depth: u32) -> NoteGraph {
+    let data = common::tdata_generate_tree(depth, 2);
+    let mut graph = NoteGraph::new();
+
+    graph
+        .build_graph(
+            data.0,
+            data.1,
+            vec![
+                TransitiveGraphRule::new(
+                    "reverse".to_string(),
+                    vec!["down".to_string()],
+                    "up".to_string(),
+                    5,
+                    false,
+                    true,
+                ),
+                TransitiveGraphRule::new(
+                    "grandparent".to_string(),
+                    vec!["up".to_string(), "up".to_string()],
+                    "up".to_string(),
+                    5,
+                    false,
+                    false,
+                ),
+                TransitiveGraphRule::new(
+                    "siblings".to_string(),
+                    vec!["up".to_string(), "down".to_string()],
+                    "same".to_string(),
+                    5,
+                    false,
+                    false,
+                ),
+            ],
+        )
+        .unwrap();
+
+    graph
+}
+
+fn edge_key(edge: &EdgeExplanation) -> (String, String, String) {
+    (
+        edge.source.clone(),
+        edge.target.clone(),
+        edge.edge_type.clone(),
+    )
+}
+
+fn key(source: &str, target: &str, edge_type: &str) -> (String, String, String) {
+    (
+        source.to_string(),
+        target.to_string(),
+        edge_type.to_string(),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_explain_explicit_edge() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let explanation = graph
+        .explain_edge("root".to_string(), "0".to_string(), "down".to_string())
+        .unwrap();
+
+    assert!(explanation.explicit);
+    assert_eq!(explanation.round, 0);
+    assert_eq!(explanation.edge_sources, vec!["typed-link"]);
+    assert!(explanation.derived_from.is_empty());
+    assert_eq!(
+        explanation
+            .explicit_edges()
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![key("root", "0", "down")]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_explain_implied_edge() {
+    let graph = get_test_graph(3);
+
+    // 0 -up-> root is the reverse of root -down-> 0
+    let explanation = graph
+        .explain_edge("0".to_string(), "root".to_string(), "up".to_string())
+        .unwrap();
+
+    assert!(!explanation.explicit);
+    assert_eq!(explanation.round, 1);
+    assert_eq!(explanation.edge_sources, vec!["reverse"]);
+    assert_eq!(
+        explanation
+            .derived_from
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![key("root", "0", "down")]
+    );
+    assert!(explanation.derived_from[0].explicit);
+}
+
+#[wasm_bindgen_test]
+fn test_explain_chained_implied_edge() {
+    let graph = get_test_graph(3);
+
+    // 00 -up-> root follows from 00 -up-> 0 and 0 -up-> root, which are the
+    // reverse of explicit down edges
+    let explanation = graph
+        .explain_edge("00".to_string(), "root".to_string(), "up".to_string())
+        .unwrap();
+
+    assert_eq!(explanation.edge_sources, vec!["grandparent"]);
+    assert_eq!(explanation.round, 2);
+    assert_eq!(
+        explanation
+            .derived_from
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![key("00", "0", "up"), key("0", "root", "up")]
+    );
+    assert!(explanation
+        .derived_from
+        .iter()
+        .all(|edge| edge.edge_sources == vec!["reverse"]));
+    assert_eq!(
+        explanation
+            .explicit_edges()
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![key("0", "00", "down"), key("root", "0", "down")]
+    );
+
+    // 000 -up-> root is derived over two levels of grandparent edges
+    let explanation = graph
+        .explain_edge("000".to_string(), "root".to_string(), "up".to_string())
+        .unwrap();
+
+    assert_eq!(
+        explanation
+            .explicit_edges()
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![
+            key("00", "000", "down"),
+            key("0", "00", "down"),
+            key("root", "0", "down")
+        ]
+    );
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_explain_sibling_edge() {
+    let graph = get_test_graph(2);
+
+    let explanation = graph
+        .explain_edge("0".to_string(), "1".to_string(), "same".to_string())
+        .unwrap();
+
+    assert_eq!(explanation.edge_sources, vec!["siblings"]);
+    assert_eq!(
+        explanation
+            .derived_from
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![key("0", "root", "up"), key("root", "1", "down")]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_explain_edge_after_update() {
+    let mut graph = get_test_graph(2);
+
+    let mut batch = BatchGraphUpdate::new();
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        "01".to_string(),
+        "new".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let explanation = graph
+        .explain_edge("new".to_string(), "0".to_string(), "up".to_string())
+        .unwrap();
+    assert_eq!(
+        explanation
+            .explicit_edges()
+            .iter()
+            .map(edge_key)
+            .collect::<Vec<_>>(),
+        vec![key("01", "new", "down"), key("0", "01", "down")]
+    );
+
+    // the explanations of kept implied edges still refer to existing edges
+    for (from, to, edge_type) in [("1", "0", "same"), ("10", "root", "up")] {
+        assert!(graph
+            .explain_edge(from.to_string(), to.to_string(), edge_type.to_string())
+            .is_ok());
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_explain_missing_edge() {
+    let graph = get_test_graph(2);
+
+    assert!(graph
+        .explain_edge("root".to_string(), "0".to_string(), "up".to_string())
+        .is_err());
+    assert!(graph
+        .explain_edge("missing".to_string(), "0".to_string(), "up".to_string())
+        .is_err());
+}
@@ -1,7 +1,15 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_bindgen_test;
-use breadcrumbs_graph_wasm::{data::rules::TransitiveGraphRule, graph::NoteGraph};
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::GCNodeData,
+        rules::{RuleStep, TransitiveGraphRule},
+    },
+    graph::NoteGraph,
+    traversal::options::TraversalOptions,
+    update::{batch::BatchGraphUpdate, AddNoteGraphUpdate},
+};
 use wasm_bindgen_test::*;
 // wasm_bindgen_test_configure!(run_in_browser);
 
@@ -177,3 +185,263 @@ fn test_implied_edge_rules_sibling_can_loop() {
     assert_eq!(same_edge_4.weight().explicit, false);
     graph.assert_correct_trackers();
 }
+
+#[wasm_bindgen_test]
+fn test_implied_edge_rules_reversed_steps() {
+    let data = common::tdata_generate_tree(3, 2);
+    let mut graph = NoteGraph::new();
+
+    // the tree only has down edges, siblings and cousins are found by following
+    // them backwards
+    graph
+        .build_graph(
+            data.0,
+            data.1,
+            vec![
+                TransitiveGraphRule::from_steps(
+                    "".to_string(),
+                    vec![
+                        RuleStep::new("down".to_string(), true),
+                        RuleStep::new("down".to_string(), false),
+                    ],
+                    "same".to_string(),
+                    5,
+                    false,
+                    false,
+                ),
+                TransitiveGraphRule::from_steps(
+                    "".to_string(),
+                    vec![
+                        RuleStep::new("down".to_string(), true),
+                        RuleStep::new("down".to_string(), true),
+                        RuleStep::new("down".to_string(), false),
+                        RuleStep::new("down".to_string(), false),
+                    ],
+                    "cousin".to_string(),
+                    5,
+                    false,
+                    false,
+                ),
+            ],
+        )
+        .unwrap();
+
+    assert!(graph.int_has_edge_by_name("00", "01", "same"));
+    assert!(graph.int_has_edge_by_name("01", "00", "same"));
+    assert!(!graph.int_has_edge_by_name("00", "10", "same"));
+    assert!(!graph.int_has_edge_by_name("00", "00", "same"));
+    assert!(graph.int_has_edge_by_name("000", "010", "cousin"));
+    assert!(!graph.int_has_edge_by_name("000", "100", "cousin"));
+
+    // the derivation contains the edges in their actual direction
+    let explanation = graph
+        .explain_edge("00".to_string(), "01".to_string(), "same".to_string())
+        .unwrap();
+    assert_eq!(
+        explanation
+            .derived_from
+            .iter()
+            .map(|edge| (edge.source.as_str(), edge.target.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("0", "00"), ("0", "01")]
+    );
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_rule_example_graph_reversed_steps() {
+    let rule = TransitiveGraphRule::from_steps(
+        "".to_string(),
+        vec![
+            RuleStep::new("up".to_string(), false),
+            RuleStep::new("up".to_string(), true),
+        ],
+        "same".to_string(),
+        1,
+        false,
+        false,
+    );
+    assert_eq!(rule.name_ref(), "[up, ~up] -> same");
+
+    let graph = rule.create_example_graph().unwrap();
+
+    assert!(graph.int_has_edge_by_name("1", "2", "up"));
+    assert!(graph.int_has_edge_by_name("3", "2", "up"));
+    assert!(graph.int_has_edge_by_name("1", "3", "same"));
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_rule_path_edge_types_are_literal() {
+    let rule = TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["~up".to_string()],
+        "same".to_string(),
+        1,
+        false,
+        false,
+    );
+
+    let graph = rule.create_example_graph().unwrap();
+
+    assert!(graph.int_has_edge_by_name("1", "2", "~up"));
+    assert!(graph.int_has_edge_by_name("1", "2", "same"));
+    assert!(!graph.int_has_edge_by_name("2", "1", "same"));
+    graph.assert_correct_trackers();
+}
+
+/// Generates a tree graph with reversed `up` edges, where the `ignore_in_edges`
+/// and `ignore_out_edges` flags are set for the given nodes.
+fn get_ignore_test_graph(ignore_in: &[&str], ignore_out: &[&str]) -> NoteGraph {
+    let (mut nodes, edges) = common::tdata_generate_tree(3, 2);
+
+    for node in nodes.iter_mut() {
+        node.ignore_in_edges = ignore_in.contains(&node.path.as_str());
+        node.ignore_out_edges = ignore_out.contains(&node.path.as_str());
+    }
+
+    let mut graph = NoteGraph::new();
+    graph
+        .build_graph(
+            nodes,
+            edges,
+            vec![
+                TransitiveGraphRule::new(
+                    "".to_string(),
+                    vec!["down".to_string()],
+                    "up".to_string(),
+                    5,
+                    false,
+                    true,
+                ),
+                TransitiveGraphRule::new(
+                    "".to_string(),
+                    vec!["down".to_string(), "down".to_string()],
+                    "grandchild".to_string(),
+                    5,
+                    false,
+                    false,
+                ),
+            ],
+        )
+        .unwrap();
+
+    graph
+}
+
+#[wasm_bindgen_test]
+fn test_ignore_out_edges_hides_outgoing_edges() {
+    let graph = get_ignore_test_graph(&[], &["0"]);
+
+    assert!(graph.get_outgoing_edges("0".to_string()).edges.is_empty());
+    assert!(graph
+        .get_incoming_edges("00".to_string())
+        .edges
+        .iter()
+        .all(|edge| edge.source_path(&graph).unwrap() != "0"));
+    assert_eq!(
+        graph
+            .get_filtered_outgoing_edges("1".to_string(), Some(vec!["down".to_string()]))
+            .edges
+            .len(),
+        2
+    );
+    // the edges are still in the graph
+    assert!(graph.int_has_edge_by_name("0", "00", "down"));
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_ignore_in_edges_hides_incoming_edges() {
+    let graph = get_ignore_test_graph(&["0"], &[]);
+
+    assert!(graph.get_incoming_edges("0".to_string()).edges.is_empty());
+
+    let root_edges = graph.get_outgoing_edges("root".to_string()).edges;
+    assert_eq!(root_edges.len(), 3);
+    assert!(root_edges
+        .iter()
+        .all(|edge| edge.target_path(&graph).unwrap() != "0"));
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_ignored_edges_are_not_traversed() {
+    let graph = get_ignore_test_graph(&[], &["0"]);
+
+    let result = graph
+        .rec_traverse(TraversalOptions::new(
+            vec!["root".to_string()],
+            Some(vec!["down".to_string()]),
+            5,
+            100,
+            false,
+        ))
+        .unwrap();
+
+    // 0 is visited, but none of its children
+    assert_eq!(result.node_count, 8);
+
+    let (nodes, _) = graph
+        .int_traverse_basic(&TraversalOptions::new(
+            vec!["root".to_string()],
+            Some(vec!["down".to_string()]),
+            5,
+            100,
+            false,
+        ))
+        .unwrap();
+
+    assert_eq!(nodes.len(), 9);
+}
+
+#[wasm_bindgen_test]
+fn test_rules_respect_ignored_edges() {
+    let graph = get_ignore_test_graph(&["1"], &["0"]);
+
+    // 0 does not have outgoing edges, so nothing is derived through or from it
+    assert!(!graph.int_has_edge_by_name("00", "0", "up"));
+    assert!(!graph.int_has_edge_by_name("0", "000", "grandchild"));
+    assert!(!graph.int_has_edge_by_name("0", "root", "up"));
+    // 1 does not have incoming edges, so nothing is derived through or onto it
+    assert!(!graph.int_has_edge_by_name("10", "1", "up"));
+    assert!(!graph.int_has_edge_by_name("1", "root", "up"));
+    assert!(!graph.int_has_edge_by_name("root", "10", "grandchild"));
+    // other edges are unaffected
+    assert!(graph.int_has_edge_by_name("100", "10", "up"));
+    assert!(graph.int_has_edge_by_name("1", "100", "grandchild"));
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_changing_ignore_flags_updates_implied_edges() {
+    // 0 starts out unresolved, so that it can be added with different flags
+    let (mut nodes, edges) = common::tdata_generate_tree(3, 2);
+    nodes.retain(|node| node.path != "0");
+
+    let mut graph = NoteGraph::new();
+    graph
+        .build_graph(
+            nodes,
+            edges,
+            vec![TransitiveGraphRule::new(
+                "".to_string(),
+                vec!["down".to_string()],
+                "up".to_string(),
+                5,
+                false,
+                true,
+            )],
+        )
+        .unwrap();
+    assert!(graph.int_has_edge_by_name("00", "0", "up"));
+
+    let mut batch = BatchGraphUpdate::new();
+    AddNoteGraphUpdate::new(GCNodeData::new("0".to_string(), vec![], true, true, false))
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert!(!graph.int_has_edge_by_name("00", "0", "up"));
+    assert!(graph.int_has_edge_by_name("000", "00", "up"));
+    graph.assert_correct_trackers();
+}
@@ -9,7 +9,10 @@ use breadcrumbs_graph_wasm::{
     },
     graph::NoteGraph,
     mermaid::MermaidGraphOptions,
-    traversal::options::TraversalOptions,
+    traversal::{
+        edge_pattern::EdgeTypePattern,
+        options::{TraversalDirection, TraversalOptions},
+    },
 };
 use indoc::indoc;
 use wasm_bindgen_test::*;
@@ -154,3 +157,111 @@ fn test_collapse_edges() {
         .trim()
     );
 }
+
+#[wasm_bindgen_test]
+fn test_incoming_direction() {
+    let graph = get_test_graph();
+
+    let mut traversal_options = TraversalOptions::new(
+        vec!["c.md".to_string()],
+        Some(vec!["up".to_string(), "down".to_string()]),
+        5,
+        100,
+        false,
+    );
+    traversal_options.direction = TraversalDirection::Incoming;
+
+    let mermaid = graph
+        .generate_mermaid_graph(traversal_options, MermaidGraphOptions::default())
+        .unwrap();
+
+    // the edges keep their direction
+    assert_eq!(
+        mermaid.mermaid.trim(),
+        indoc! {
+            r#"
+            %%{ init: { "flowchart": {} } }%%
+            graph LR
+                2("c.md")
+                1("b.md")
+                0("a.md")
+                0 -->|"up"| 1
+                1 -->|"down"| 2
+            "#
+        }
+        .trim()
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_custom_edge_attributes() {
+    let graph = get_test_graph();
+
+    let mut edges = vec![
+        GCEdgeData::new(
+            "a.md".to_string(),
+            "b.md".to_string(),
+            "up".to_string(),
+            "".to_string(),
+        ),
+        GCEdgeData::new(
+            "b.md".to_string(),
+            "c.md".to_string(),
+            "down".to_string(),
+            "".to_string(),
+        ),
+    ];
+    edges[0].set_label(Some("parent".to_string()));
+    edges[0].set_attribute("importance".to_string(), Some("high".to_string()));
+
+    let mut graph_with_attributes = NoteGraph::new();
+    graph_with_attributes
+        .build_graph(
+            graph
+                .graph
+                .node_weights()
+                .cloned()
+                .map(Into::into)
+                .collect(),
+            edges,
+            vec![],
+        )
+        .unwrap();
+
+    let options = MermaidGraphOptions {
+        edge_label_attributes: vec!["label".to_string(), "importance".to_string()],
+        ..Default::default()
+    };
+
+    let mermaid = graph_with_attributes
+        .generate_mermaid_graph(get_traversal_options(), options)
+        .unwrap();
+
+    assert_eq!(
+        mermaid.mermaid.trim(),
+        indoc! {
+            r#"
+            %%{ init: { "flowchart": {} } }%%
+            graph LR
+                0("a.md")
+                1("b.md")
+                2("c.md")
+                0 -->|"label=parent importance=high"| 1
+                1 --> 2
+            "#
+        }
+        .trim()
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_edge_pattern_is_rejected() {
+    let graph = get_test_graph();
+
+    let mut traversal_options = get_traversal_options();
+    traversal_options.edge_pattern = Some(EdgeTypePattern::new("up down".to_string()).unwrap());
+
+    assert!(graph
+        .generate_mermaid_graph(traversal_options, MermaidGraphOptions::default())
+        .is_err());
+}
@@ -0,0 +1,195 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use breadcrumbs_graph_wasm::{
+    data::{construction::GCNodeData, property::PropertyValue},
+    graph::NoteGraph,
+    update::{batch::BatchGraphUpdate, RemoveNoteGraphUpdate, SetNotePropertyGraphUpdate},
+};
+use js_sys::{Array, Date, Object};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn set_properties(graph: &mut NoteGraph, properties: Vec<(&str, &str, JsValue)>) {
+    let mut batch = BatchGraphUpdate::new();
+    for (node, key, value) in properties {
+        SetNotePropertyGraphUpdate::new(node.to_string(), key.to_string(), value)
+            .unwrap()
+            .add_to_batch(&mut batch);
+    }
+    graph.apply_update(batch).unwrap();
+}
+
+#[wasm_bindgen_test]
+fn test_property_conversion() {
+    let mut node = GCNodeData::new("a".to_string(), vec![], true, false, false);
+
+    node.set_property("title".to_string(), JsValue::from_str("A"))
+        .unwrap();
+    node.set_property("priority".to_string(), JsValue::from_f64(3.0))
+        .unwrap();
+    node.set_property("draft".to_string(), JsValue::FALSE)
+        .unwrap();
+    node.set_property(
+        "created".to_string(),
+        Date::new(&JsValue::from_f64(1e12)).into(),
+    )
+    .unwrap();
+    node.set_property(
+        "status".to_string(),
+        Array::of2(&JsValue::from_str("open"), &JsValue::from_f64(1.0)).into(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        node.properties.get("title"),
+        Some(&PropertyValue::String("A".to_string()))
+    );
+    assert_eq!(
+        node.properties.get("priority"),
+        Some(&PropertyValue::Number(3.0))
+    );
+    assert_eq!(
+        node.properties.get("draft"),
+        Some(&PropertyValue::Bool(false))
+    );
+    assert_eq!(
+        node.properties.get("created"),
+        Some(&PropertyValue::Date(1e12))
+    );
+    assert_eq!(
+        node.properties.get("status"),
+        Some(&PropertyValue::List(vec![
+            PropertyValue::String("open".to_string()),
+            PropertyValue::Number(1.0)
+        ]))
+    );
+
+    // dates are converted back into JS dates
+    let created = node.get_property("created");
+    assert!(created.is_instance_of::<Date>());
+    assert_eq!(created.unchecked_into::<Date>().get_time(), 1e12);
+
+    // null removes a property
+    node.set_property("title".to_string(), JsValue::NULL)
+        .unwrap();
+    assert!(node.get_property("title").is_undefined());
+
+    // objects are not supported
+    assert!(node
+        .set_property("nested".to_string(), Object::new().into())
+        .is_err());
+    assert!(node
+        .set_property("list".to_string(), Array::of1(&JsValue::UNDEFINED).into())
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_set_property_update() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph = common::tdata_to_graph(data);
+
+    set_properties(
+        &mut graph,
+        vec![
+            ("0", "priority", JsValue::from_f64(2.0)),
+            ("1", "priority", JsValue::from_f64(1.0)),
+        ],
+    );
+
+    let node = graph.get_node("0".to_string()).unwrap();
+    assert_eq!(node.get_property("priority"), JsValue::from_f64(2.0));
+    assert_eq!(node.get_property_keys(), vec!["priority"]);
+
+    set_properties(&mut graph, vec![("0", "priority", JsValue::UNDEFINED)]);
+    let node = graph.get_node("0".to_string()).unwrap();
+    assert!(node.get_property("priority").is_undefined());
+
+    // setting a property on a missing node fails
+    let mut batch = BatchGraphUpdate::new();
+    SetNotePropertyGraphUpdate::new(
+        "missing".to_string(),
+        "priority".to_string(),
+        JsValue::from_f64(1.0),
+    )
+    .unwrap()
+    .add_to_batch(&mut batch);
+    assert!(graph.apply_update(batch).is_err());
+
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_remove_node_clears_properties() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph = common::tdata_to_graph(data);
+
+    set_properties(&mut graph, vec![("1", "priority", JsValue::from_f64(1.0))]);
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("1".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    let node = graph.get_node("1".to_string()).unwrap();
+    assert!(node.properties.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_sort_by_property() {
+    let data = common::tdata_generate_tree(1, 4);
+    let mut graph = common::tdata_to_graph(data);
+
+    set_properties(
+        &mut graph,
+        vec![
+            ("0", "priority", JsValue::from_f64(3.0)),
+            ("1", "priority", JsValue::from_f64(1.0)),
+            ("3", "priority", JsValue::from_f64(1.0)),
+        ],
+    );
+
+    // nodes without the property come last, ties are broken by path
+    assert_eq!(
+        common::sorted_children(&graph, "property:priority", false),
+        vec!["1", "3", "0", "2"]
+    );
+    assert_eq!(
+        common::sorted_children(&graph, "property:priority", true),
+        vec!["2", "0", "3", "1"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_sort_by_date_property() {
+    let data = common::tdata_generate_tree(1, 3);
+    let mut graph = common::tdata_to_graph(data);
+
+    set_properties(
+        &mut graph,
+        vec![
+            (
+                "0",
+                "created",
+                Date::new(&JsValue::from_str("2024-03-01")).into(),
+            ),
+            (
+                "1",
+                "created",
+                Date::new(&JsValue::from_str("2023-12-24")).into(),
+            ),
+            (
+                "2",
+                "created",
+                Date::new(&JsValue::from_str("2024-01-15")).into(),
+            ),
+        ],
+    );
+
+    assert_eq!(
+        common::sorted_children(&graph, "property:created", false),
+        vec!["1", "2", "0"]
+    );
+}
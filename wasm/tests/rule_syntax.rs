@@ -0,0 +1,178 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use breadcrumbs_graph_wasm::data::{
+    rule_syntax::{parse_transitive_rules, stringify_transitive_rules},
+    rules::{RuleStep, TransitiveGraphRule},
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn parse(text: &str) -> TransitiveGraphRule {
+    TransitiveGraphRule::parse(text.to_string()).unwrap()
+}
+
+fn parse_error(text: &str) -> String {
+    TransitiveGraphRule::parse(text.to_string())
+        .unwrap_err()
+        .message()
+}
+
+#[wasm_bindgen_test]
+fn test_parse_rule() {
+    let rule = parse("[up, same] -> up (rounds=5, loop)");
+
+    assert_eq!(
+        rule.iter_path().map(|t| t.as_ref()).collect::<Vec<_>>(),
+        vec!["up", "same"]
+    );
+    assert_eq!(rule.edge_type_ref(), "up");
+    assert_eq!(rule.rounds(), 5);
+    assert!(rule.can_loop());
+    assert!(!rule.close_reversed());
+    assert_eq!(rule.name_ref(), "[up, same] -> up");
+
+    let rule = parse("  [down]<-up  ");
+    assert!(rule.close_reversed());
+    assert_eq!(rule.rounds(), 1);
+    assert!(!rule.can_loop());
+    assert_eq!(rule.name_ref(), "[down] <- up");
+
+    let rule = parse("[up]+ -> ancestor");
+    assert!(rule.closure());
+    assert_eq!(rule.name_ref(), "[up]+ -> ancestor");
+
+    let rule = parse(r#"["next note", "a \"b\""] -> "is-a" (name="Sequence \\ rule")"#);
+    assert_eq!(
+        rule.iter_path().map(|t| t.as_ref()).collect::<Vec<_>>(),
+        vec!["next note", "a \"b\""]
+    );
+    assert_eq!(rule.edge_type_ref(), "is-a");
+    assert_eq!(rule.name_ref(), "Sequence \\ rule");
+}
+
+#[wasm_bindgen_test]
+fn test_stringify_rule() {
+    let texts = vec![
+        "[up, same] -> up (rounds=5, loop)",
+        "[down] <- up",
+        "[] -> self (loop)",
+        "[up, ~up] -> same",
+        "[~\"next note\"] <- prev",
+        "[\"~up\", ~\"~up\"] -> \"~same\"",
+        "[up]+ -> ancestor",
+        "[down, ~down]+ -> related (loop)",
+        r#"["next note", "a \"b\""] -> is-a (rounds=0, name="Sequence \\ rule")"#,
+    ];
+
+    for text in texts {
+        let rule = parse(text);
+        assert_eq!(rule.stringify(), text);
+
+        let reparsed = parse(&rule.stringify());
+        assert_eq!(reparsed.name_ref(), rule.name_ref());
+        assert_eq!(reparsed.stringify(), rule.stringify());
+    }
+
+    // rules created with the constructor print the same way
+    let rule = TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["up".to_string()],
+        "down".to_string(),
+        1,
+        false,
+        true,
+    );
+    assert_eq!(rule.stringify(), "[up] <- down");
+}
+
+#[wasm_bindgen_test]
+fn test_parse_rule_quoted_tilde() {
+    // only a bare `~` reverses a step, a quoted one is part of the edge type
+    let rule = parse(r#"["~up", ~up] -> same"#);
+    assert_eq!(
+        rule.iter_steps().cloned().collect::<Vec<_>>(),
+        vec![
+            RuleStep::new("~up".to_string(), false),
+            RuleStep::new("up".to_string(), true),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_rule_errors() {
+    assert_eq!(
+        parse_error("up -> down"),
+        "failed to parse rule at column 1, expected \"[\", found \"u\""
+    );
+    assert_eq!(
+        parse_error("[up] => down"),
+        "failed to parse rule at column 6, expected \"->\" or \"<-\", found \"=\""
+    );
+    assert_eq!(
+        parse_error("[up, ] -> down"),
+        "failed to parse rule at column 6, expected an edge type, found \"]\""
+    );
+    assert_eq!(
+        parse_error("[up] -> down (rounds=300)"),
+        "failed to parse rule at column 22, invalid number of rounds \"300\", expected at most 255"
+    );
+    assert_eq!(
+        parse_error("[up] -> down (loop, depth=2)"),
+        "failed to parse rule at column 21, unknown option \"depth\", expected \"rounds\", \"loop\" or \"name\""
+    );
+    assert_eq!(
+        parse_error("[up] -> down (loop, loop)"),
+        "failed to parse rule at column 21, duplicate option \"loop\""
+    );
+    assert_eq!(
+        parse_error("[up]+ -> ancestor (rounds=2)"),
+        "failed to parse rule at column 20, closure rules don't have rounds, they find paths of any length"
+    );
+    assert_eq!(
+        parse_error("[\"up] -> down"),
+        "failed to parse rule at column 14, expected a closing quote, found the end of the rule"
+    );
+    assert_eq!(
+        parse_error("[up] -> down (loop) x"),
+        "failed to parse rule at column 21, expected the end of the rule, found \"x\""
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_rule_set() {
+    let text = "# hierarchy\n[down] <- up\n\n[up] <- down\n[up, down] -> same (name=siblings)";
+    let rules = parse_transitive_rules(text.to_string()).unwrap();
+
+    assert_eq!(
+        rules.iter().map(|rule| rule.name_ref()).collect::<Vec<_>>(),
+        vec!["[down] <- up", "[up] <- down", "siblings"]
+    );
+    assert_eq!(
+        stringify_transitive_rules(rules),
+        "[down] <- up\n[up] <- down\n[up, down] -> same (name=\"siblings\")"
+    );
+
+    assert_eq!(
+        parse_transitive_rules("[down] <- up\n\n  [up] <- \n".to_string())
+            .unwrap_err()
+            .message(),
+        "failed to parse rule at line 3, column 11, expected an edge type, found the end of the rule"
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parsed_rules_build_graph() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph = common::tdata_to_graph(data);
+
+    let rules =
+        parse_transitive_rules("[down] <- up\n[up, down] -> same (rounds=2)".to_string()).unwrap();
+    let data = common::tdata_generate_tree(2, 2);
+    graph.build_graph(data.0, data.1, rules).unwrap();
+
+    assert!(graph.int_has_edge_by_name("0", "root", "up"));
+    assert!(graph.int_has_edge_by_name("0", "1", "same"));
+    graph.assert_correct_trackers();
+}
@@ -0,0 +1,200 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use breadcrumbs_graph_wasm::{
+    data::rules::{RuleStep, TransitiveGraphRule},
+    rule_validation::{validate_rules, RuleDiagnostic, RuleDiagnosticKind, RuleDiagnosticSeverity},
+};
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn rule(name: &str, path: Vec<&str>, edge_type: &str, rounds: u8) -> TransitiveGraphRule {
+    TransitiveGraphRule::new(
+        name.to_string(),
+        path.into_iter().map(|s| s.to_string()).collect(),
+        edge_type.to_string(),
+        rounds,
+        false,
+        false,
+    )
+}
+
+fn kinds(diagnostics: &[RuleDiagnostic]) -> Vec<(u32, RuleDiagnosticKind)> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.rule_index, diagnostic.kind))
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn test_valid_rules() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let diagnostics = validate_rules(
+        vec![
+            rule("up", vec!["down"], "up", 1),
+            rule("same", vec!["up", "down"], "same", 1),
+        ],
+        &graph,
+    );
+
+    assert!(diagnostics.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_empty_path_and_zero_rounds() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let diagnostics = validate_rules(
+        vec![
+            rule("empty", vec![], "up", 1),
+            rule("up", vec!["down"], "up", 0),
+        ],
+        &graph,
+    );
+
+    assert_eq!(
+        kinds(&diagnostics),
+        vec![
+            (0, RuleDiagnosticKind::EmptyPath),
+            (1, RuleDiagnosticKind::ZeroRounds)
+        ]
+    );
+    assert_eq!(diagnostics[0].severity, RuleDiagnosticSeverity::Error);
+    assert_eq!(diagnostics[0].rule_name, "empty");
+    assert_eq!(diagnostics[1].severity, RuleDiagnosticSeverity::Warning);
+}
+
+#[wasm_bindgen_test]
+fn test_unreachable_edge_types() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let diagnostics = validate_rules(
+        vec![
+            // "next" doesn't exist in the graph
+            rule("after", vec!["next", "down"], "after", 1),
+            // "up" is created by the next rule
+            rule("same", vec!["up", "down"], "same", 1),
+            rule("up", vec!["down"], "up", 1),
+            // "after" is only created by a rule that never applies
+            rule("later", vec!["after"], "later", 1),
+        ],
+        &graph,
+    );
+
+    assert_eq!(
+        kinds(&diagnostics),
+        vec![
+            (0, RuleDiagnosticKind::UnreachableEdgeType),
+            (3, RuleDiagnosticKind::UnreachableEdgeType)
+        ]
+    );
+    assert_eq!(diagnostics[0].edge_type, Some("next".to_string()));
+    assert_eq!(diagnostics[1].edge_type, Some("after".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn test_self_feeding_rules() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let diagnostics = validate_rules(
+        vec![
+            rule("up", vec!["down"], "up", 1),
+            rule("ancestor", vec!["up", "up"], "up", 5),
+            // "a" and "b" feed each other
+            rule("a", vec!["b"], "a", 5),
+            rule("b", vec!["a"], "b", 5),
+            rule("seed", vec!["down"], "a", 1),
+        ],
+        &graph,
+    );
+
+    assert_eq!(
+        kinds(&diagnostics),
+        vec![
+            (1, RuleDiagnosticKind::SelfFeeding),
+            (2, RuleDiagnosticKind::SelfFeeding),
+            (3, RuleDiagnosticKind::SelfFeeding)
+        ]
+    );
+    assert_eq!(diagnostics[0].edge_type, Some("up".to_string()));
+    assert!(diagnostics[0].related_rules.is_empty());
+    assert_eq!(diagnostics[1].related_rules, vec![3]);
+    assert_eq!(diagnostics[2].related_rules, vec![2]);
+}
+
+#[wasm_bindgen_test]
+fn test_shadowed_rules() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let looping = TransitiveGraphRule::new(
+        "same with loops".to_string(),
+        vec!["up".to_string(), "down".to_string()],
+        "same".to_string(),
+        1,
+        true,
+        false,
+    );
+
+    let diagnostics = validate_rules(
+        vec![
+            rule("up", vec!["down"], "up", 1),
+            rule("siblings", vec!["up", "down"], "same", 1),
+            // a duplicate under a different name
+            rule("", vec!["down"], "up", 3),
+            // can loop, so it adds more edges than "siblings"
+            looping,
+        ],
+        &graph,
+    );
+
+    assert_eq!(
+        kinds(&diagnostics),
+        vec![
+            (1, RuleDiagnosticKind::Shadowed),
+            (2, RuleDiagnosticKind::Shadowed)
+        ]
+    );
+    assert_eq!(diagnostics[0].related_rules, vec![3]);
+    assert_eq!(diagnostics[1].related_rules, vec![0]);
+    assert_eq!(diagnostics[1].rule_name, "[down] -> up");
+}
+
+#[wasm_bindgen_test]
+fn test_closure_rules() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let closure = |can_loop: bool| {
+        TransitiveGraphRule::new_closure(
+            "".to_string(),
+            vec![RuleStep::new("up".to_string(), false)],
+            "up".to_string(),
+            can_loop,
+            false,
+        )
+    };
+
+    let diagnostics = validate_rules(
+        vec![
+            rule("up", vec!["down"], "up", 1),
+            // reads its own edges, but stops once it finds no new paths
+            closure(false),
+            // a regular rule with the same path adds a subset of the edges
+            rule("", vec!["up"], "up", 1),
+            closure(true),
+        ],
+        &graph,
+    );
+
+    assert_eq!(
+        kinds(&diagnostics),
+        vec![
+            (1, RuleDiagnosticKind::Shadowed),
+            (2, RuleDiagnosticKind::SelfFeeding),
+            (2, RuleDiagnosticKind::Shadowed)
+        ]
+    );
+    assert_eq!(diagnostics[0].related_rules, vec![3]);
+    assert_eq!(diagnostics[2].related_rules, vec![1]);
+}
@@ -0,0 +1,217 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use std::rc::Rc;
+
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::GCNodeData,
+        edge::EdgeData,
+        node::NodeData,
+        rules::{RuleStep, TransitiveGraphRule},
+        NGNodeIndex,
+    },
+    graph::NoteGraph,
+    snapshot::SNAPSHOT_VERSION,
+    update::{batch::BatchGraphUpdate, RemoveNoteGraphUpdate},
+    utils::graph_eq,
+};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn get_test_graph() -> NoteGraph {
+    let (mut nodes, mut edges) = common::tdata_generate_tree(3, 2);
+    nodes[1].aliases = vec!["alias".to_string(), "other alias".to_string()];
+    nodes[2].ignore_in_edges = true;
+    edges[0].set_order(Some(1));
+    edges[1].set_label(Some("label".to_string()));
+    edges[1].set_attribute("weight".to_string(), Some("2".to_string()));
+    // an edge asserted by two edge sources, one of them twice
+    let mut shared_edge = edges[2].clone();
+    shared_edge.edge_source = "list_note".to_string();
+    edges.push(shared_edge.clone());
+    edges.push(shared_edge);
+    nodes[1]
+        .set_property("priority".to_string(), JsValue::from_f64(2.5))
+        .unwrap();
+    nodes[1]
+        .set_property(
+            "status".to_string(),
+            js_sys::Array::of3(
+                &JsValue::from_str("done"),
+                &JsValue::TRUE,
+                &js_sys::Date::new(&JsValue::from_f64(1e12)),
+            )
+            .into(),
+        )
+        .unwrap();
+
+    let mut graph = NoteGraph::new();
+    graph
+        .build_graph(
+            nodes,
+            edges,
+            vec![
+                TransitiveGraphRule::new(
+                    "".to_string(),
+                    vec!["down".to_string()],
+                    "up".to_string(),
+                    5,
+                    false,
+                    true,
+                ),
+                TransitiveGraphRule::from_steps(
+                    "siblings".to_string(),
+                    vec![
+                        RuleStep::new("up".to_string(), false),
+                        RuleStep::new("up".to_string(), true),
+                    ],
+                    "same".to_string(),
+                    3,
+                    true,
+                    false,
+                ),
+                TransitiveGraphRule::new_closure(
+                    "".to_string(),
+                    vec![RuleStep::new("up".to_string(), false)],
+                    "ancestor".to_string(),
+                    false,
+                    false,
+                ),
+            ],
+        )
+        .unwrap();
+
+    // remove a node to get an unresolved node and gaps in the node indices
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("0".to_string()).add_to_batch(&mut batch);
+    RemoveNoteGraphUpdate::new("111".to_string()).add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    graph
+}
+
+/// An edge identified by the paths of its nodes, with its data and the edges
+/// it was derived from.
+type EdgeByPath = (String, String, EdgeData, Vec<(String, String, Rc<str>)>);
+
+/// The nodes and edges of a graph, sorted by path, with edges identified by the
+/// paths of their nodes. This allows comparing graphs with different node
+/// indices.
+fn graph_by_path(graph: &NoteGraph) -> (Vec<NodeData>, Vec<EdgeByPath>) {
+    let path = |node: NGNodeIndex| graph.graph[node].path.clone();
+
+    let mut nodes: Vec<NodeData> = graph.graph.node_weights().cloned().collect();
+    nodes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut edges: Vec<EdgeByPath> = graph
+        .graph
+        .edge_references()
+        .map(|edge| {
+            let mut edge_data = edge.weight().clone();
+            let derived_from = edge_data
+                .derived_from
+                .drain(..)
+                .map(|(source, target, edge_type)| (path(source), path(target), edge_type))
+                .collect();
+
+            (
+                path(edge.source()),
+                path(edge.target()),
+                edge_data,
+                derived_from,
+            )
+        })
+        .collect();
+    edges.sort_by(|a, b| (&a.0, &a.1, &a.2.edge_type).cmp(&(&b.0, &b.1, &b.2.edge_type)));
+
+    (nodes, edges)
+}
+
+#[wasm_bindgen_test]
+fn test_snapshot_round_trip() {
+    let graph = get_test_graph();
+
+    let snapshot = graph.to_snapshot();
+    let restored = NoteGraph::from_snapshot(&snapshot).unwrap();
+
+    assert_eq!(restored.get_revision(), graph.get_revision());
+    assert_eq!(restored.int_node_count(), graph.int_node_count());
+    assert_eq!(restored.int_edge_count(), graph.int_edge_count());
+    assert_eq!(restored.transitive_rules.len(), 3);
+    assert_eq!(restored.transitive_rules[1].name_ref(), "siblings");
+    assert!(restored.transitive_rules[1]
+        .iter_steps()
+        .eq(graph.transitive_rules[1].iter_steps()));
+    assert!(restored.transitive_rules[2].closure());
+    assert!(!restored.get_node("0".to_string()).unwrap().resolved);
+    assert_eq!(
+        restored.get_node("root".to_string()),
+        graph.get_node("root".to_string())
+    );
+    // the node indices differ, since the original graph has gaps, but the nodes
+    // and edges are the same, including the data of every edge source
+    assert_eq!(graph_by_path(&restored), graph_by_path(&graph));
+
+    // the restored graph can be snapshotted again without changes
+    let restored_again = NoteGraph::from_snapshot(&restored.to_snapshot()).unwrap();
+    assert!(graph_eq(&restored.graph, &restored_again.graph));
+    assert_eq!(restored.to_snapshot(), restored_again.to_snapshot());
+
+    restored.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_snapshot_of_fresh_graph_is_equal() {
+    let data = common::tdata_generate_tree(3, 2);
+    let graph = common::tdata_to_graph(data);
+
+    let restored = NoteGraph::from_snapshot(&graph.to_snapshot()).unwrap();
+
+    assert!(graph_eq(&graph.graph, &restored.graph));
+}
+
+#[wasm_bindgen_test]
+fn test_snapshot_rejects_invalid_data() {
+    let snapshot = get_test_graph().to_snapshot();
+
+    assert!(NoteGraph::from_snapshot(&[]).is_err());
+    assert!(NoteGraph::from_snapshot(b"not a snapshot").is_err());
+    assert!(NoteGraph::from_snapshot(&snapshot[..snapshot.len() - 1]).is_err());
+
+    let mut extended = snapshot.clone();
+    extended.push(0);
+    assert!(NoteGraph::from_snapshot(&extended).is_err());
+
+    // a string length that would overflow the read position
+    let mut long_string = snapshot.clone();
+    long_string[14..18].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(NoteGraph::from_snapshot(&long_string).is_err());
+
+    // property lists that are nested too deeply
+    let mut nested = JsValue::from_str("deep");
+    for _ in 0..40 {
+        nested = js_sys::Array::of1(&nested).into();
+    }
+    let mut node = GCNodeData::new("note".to_string(), vec![], true, false, false);
+    node.set_property("nested".to_string(), nested).unwrap();
+    let mut nested_graph = NoteGraph::new();
+    nested_graph
+        .build_graph(vec![node], vec![], vec![])
+        .unwrap();
+    match NoteGraph::from_snapshot(&nested_graph.to_snapshot()) {
+        Ok(_) => panic!("snapshot with deeply nested properties should not be read"),
+        Err(error) => assert!(error.message().contains("nested")),
+    }
+
+    let mut other_version = snapshot.clone();
+    other_version[4..6].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+    match NoteGraph::from_snapshot(&other_version) {
+        Ok(_) => panic!("snapshot with a different version should not be read"),
+        Err(error) => assert!(error.message().contains("version")),
+    }
+}
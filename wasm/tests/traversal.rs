@@ -0,0 +1,925 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+use std::rc::Rc;
+
+use breadcrumbs_graph_wasm::{
+    data::{
+        construction::{GCEdgeData, GCNodeData},
+        rules::{RuleStep, TransitiveGraphRule},
+        traversal::TraversalData,
+    },
+    graph::NoteGraph,
+    traversal::{
+        edge_pattern::EdgeTypePattern,
+        node_filter::{path_matches, NodeFilter},
+        options::{TraversalDirection, TraversalOptions, DEFAULT_MAX_TRAVERSAL_COUNT},
+        path::Path,
+    },
+    update::{batch::BatchGraphUpdate, SetNotePropertyGraphUpdate},
+};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+mod common;
+
+fn get_test_graph() -> NoteGraph {
+    let (nodes, edges) = common::tdata_generate_tree(3, 2);
+
+    let mut graph = NoteGraph::new();
+    graph
+        .build_graph(
+            nodes,
+            edges,
+            vec![
+                TransitiveGraphRule::new(
+                    "".to_string(),
+                    vec!["down".to_string()],
+                    "up".to_string(),
+                    5,
+                    false,
+                    true,
+                ),
+                TransitiveGraphRule::new(
+                    "".to_string(),
+                    vec!["up".to_string(), "down".to_string()],
+                    "same".to_string(),
+                    5,
+                    false,
+                    false,
+                ),
+            ],
+        )
+        .unwrap();
+
+    graph
+}
+
+fn path_nodes(graph: &NoteGraph, path: &Path) -> Vec<String> {
+    path.edges
+        .iter()
+        .map(|edge| edge.target_path(graph).unwrap())
+        .collect()
+}
+
+fn edge_types(edge_types: &[&str]) -> Option<Vec<String>> {
+    Some(edge_types.iter().map(|t| t.to_string()).collect())
+}
+
+#[wasm_bindgen_test]
+fn test_shortest_path() {
+    let graph = get_test_graph();
+
+    let path = graph
+        .shortest_path(
+            "000".to_string(),
+            "111".to_string(),
+            edge_types(&["up", "down"]),
+            10,
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        path_nodes(&graph, &path),
+        vec!["00", "0", "root", "1", "11", "111"]
+    );
+    assert_eq!(path.edges[0].edge_type.as_ref(), "up");
+    assert_eq!(path.edges[5].edge_type.as_ref(), "down");
+}
+
+#[wasm_bindgen_test]
+fn test_shortest_path_uses_all_edge_types() {
+    let graph = get_test_graph();
+
+    let path = graph
+        .shortest_path("000".to_string(), "111".to_string(), None, 10)
+        .unwrap()
+        .unwrap();
+
+    // 000 -same-> 001 is no shortcut, but 00 -same-> 01 and 0 -same-> 1 are
+    assert_eq!(path_nodes(&graph, &path), vec!["00", "0", "1", "11", "111"]);
+}
+
+#[wasm_bindgen_test]
+fn test_shortest_path_not_found() {
+    let graph = get_test_graph();
+
+    // only following down edges, there is no way up the tree
+    assert!(graph
+        .shortest_path(
+            "000".to_string(),
+            "1".to_string(),
+            edge_types(&["down"]),
+            10
+        )
+        .unwrap()
+        .is_none());
+
+    // the path is longer than the max depth
+    assert!(graph
+        .shortest_path(
+            "root".to_string(),
+            "111".to_string(),
+            edge_types(&["down"]),
+            2
+        )
+        .unwrap()
+        .is_none());
+
+    assert!(graph
+        .shortest_path(
+            "root".to_string(),
+            "missing".to_string(),
+            edge_types(&["down"]),
+            2
+        )
+        .is_err());
+    assert!(graph
+        .shortest_path(
+            "missing".to_string(),
+            "root".to_string(),
+            edge_types(&["down"]),
+            2
+        )
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_shortest_path_follows_outgoing_edges() {
+    let graph = get_test_graph();
+
+    // the down edges lead away from the root, the up edges towards it
+    assert!(graph
+        .shortest_path(
+            "111".to_string(),
+            "root".to_string(),
+            edge_types(&["down"]),
+            10
+        )
+        .unwrap()
+        .is_none());
+
+    let path = graph
+        .shortest_path(
+            "111".to_string(),
+            "root".to_string(),
+            edge_types(&["up"]),
+            10,
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(path_nodes(&graph, &path), vec!["11", "1", "root"]);
+}
+
+#[wasm_bindgen_test]
+fn test_k_shortest_paths() {
+    let graph = get_test_graph();
+
+    let paths = graph
+        .k_shortest_paths(
+            "000".to_string(),
+            "001".to_string(),
+            edge_types(&["up", "down", "same"]),
+            10,
+            3,
+        )
+        .unwrap()
+        .to_paths();
+
+    // there are only two paths that don't visit a node twice
+    assert_eq!(paths.len(), 2);
+    assert_eq!(path_nodes(&graph, &paths[0]), vec!["001"]);
+    assert_eq!(path_nodes(&graph, &paths[1]), vec!["00", "001"]);
+}
+
+#[wasm_bindgen_test]
+fn test_k_shortest_paths_respects_max_depth() {
+    let graph = get_test_graph();
+
+    let paths = graph
+        .k_shortest_paths(
+            "000".to_string(),
+            "001".to_string(),
+            edge_types(&["up", "down", "same"]),
+            2,
+            100,
+        )
+        .unwrap()
+        .to_paths();
+
+    assert!(!paths.is_empty());
+    assert!(paths.iter().all(|path| path.length() <= 2));
+
+    let paths = graph
+        .k_shortest_paths(
+            "000".to_string(),
+            "111".to_string(),
+            edge_types(&["up", "down", "same"]),
+            10,
+            5,
+        )
+        .unwrap()
+        .to_paths();
+
+    assert_eq!(paths.len(), 5);
+    assert_eq!(paths[0].length(), 5);
+    // the paths are sorted by length and unique
+    for i in 1..paths.len() {
+        assert!(paths[i - 1].length() <= paths[i].length());
+        assert!(!paths[i - 1].equals(&paths[i]));
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_k_shortest_paths_truncated() {
+    let graph = get_test_graph();
+    let from = graph.int_get_node_index("000").unwrap();
+    let edge_types: Vec<Rc<str>> = vec!["up".into(), "down".into(), "same".into()];
+
+    let paths = graph.int_k_shortest_paths(
+        from,
+        graph.int_get_node_index("111").unwrap(),
+        Some(&edge_types),
+        10,
+        100,
+        20,
+    );
+
+    // the search stopped early, but the paths found so far are returned
+    assert!(paths.truncated());
+    assert!(paths.to_paths().len() < 100);
+
+    let paths = graph.int_k_shortest_paths(
+        from,
+        graph.int_get_node_index("001").unwrap(),
+        Some(&edge_types),
+        10,
+        100,
+        DEFAULT_MAX_TRAVERSAL_COUNT,
+    );
+    assert!(!paths.truncated());
+}
+
+#[wasm_bindgen_test]
+fn test_shortest_path_to_self() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let path = graph
+        .shortest_path("root".to_string(), "root".to_string(), None, 5)
+        .unwrap()
+        .unwrap();
+    assert_eq!(path.length(), 0);
+
+    let paths = graph
+        .k_shortest_paths("root".to_string(), "10".to_string(), None, 5, 5)
+        .unwrap()
+        .to_paths();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(path_nodes(&graph, &paths[0]), vec!["1", "10"]);
+}
+
+fn get_cycle_test_graph() -> NoteGraph {
+    let (nodes, mut edges) = common::tdata_generate_tree(2, 2);
+    edges.push(GCEdgeData::new(
+        "00".to_string(),
+        "root".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ));
+    edges.push(GCEdgeData::new(
+        "1".to_string(),
+        "1".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ));
+
+    let mut graph = NoteGraph::new();
+    graph
+        .build_graph(
+            nodes,
+            edges,
+            vec![TransitiveGraphRule::new(
+                "".to_string(),
+                vec!["down".to_string()],
+                "up".to_string(),
+                5,
+                false,
+                true,
+            )],
+        )
+        .unwrap();
+
+    graph
+}
+
+fn cycle_nodes(graph: &NoteGraph, path: &Path) -> Vec<String> {
+    let mut nodes = vec![path.edges[0].source_path(graph).unwrap()];
+    nodes.extend(path_nodes(graph, path));
+    nodes
+}
+
+#[wasm_bindgen_test]
+fn test_find_cycles() {
+    let graph = get_cycle_test_graph();
+
+    let cycles = graph
+        .find_cycles(edge_types(&["down"]), false, 10_000)
+        .to_paths();
+
+    assert_eq!(cycles.len(), 2);
+    assert_eq!(cycle_nodes(&graph, &cycles[0]), vec!["1", "1"]);
+    assert_eq!(
+        cycle_nodes(&graph, &cycles[1]),
+        vec!["root", "0", "00", "root"]
+    );
+
+    let components = graph.find_cycle_components(edge_types(&["down"]), false, 10_000);
+    assert_eq!(components.len(), 2);
+    assert!(components
+        .iter()
+        .all(|component| component.to_paths().len() == 1));
+}
+
+#[wasm_bindgen_test]
+fn test_find_cycles_implied_edges() {
+    let graph = get_cycle_test_graph();
+
+    // the up edges are all implied
+    assert!(graph
+        .find_cycles(edge_types(&["up"]), false, 10_000)
+        .to_paths()
+        .is_empty());
+
+    let cycles = graph
+        .find_cycles(edge_types(&["up"]), true, 10_000)
+        .to_paths();
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(
+        cycle_nodes(&graph, &cycles[0]),
+        vec!["root", "00", "0", "root"]
+    );
+
+    // every down edge together with its up edge forms a cycle
+    let components = graph.find_cycle_components(None, true, 10_000);
+    assert_eq!(components.len(), 1);
+    let cycles = components[0].to_paths();
+    assert!(cycles.len() > 7);
+    for i in 1..cycles.len() {
+        assert!(cycles[i - 1].length() <= cycles[i].length());
+        assert!(!cycles[i - 1].equals(&cycles[i]));
+    }
+}
+
+fn get_complete_test_graph(size: u32) -> NoteGraph {
+    let names: Vec<String> = (0..size).map(|i| i.to_string()).collect();
+    let nodes = names
+        .iter()
+        .map(|name| GCNodeData::new(name.clone(), vec![], true, false, false))
+        .collect();
+    let mut edges = vec![];
+    for from in &names {
+        for to in &names {
+            if from != to {
+                edges.push(GCEdgeData::new(
+                    from.clone(),
+                    to.clone(),
+                    "next".to_string(),
+                    "typed-link".to_string(),
+                ));
+            }
+        }
+    }
+
+    common::tdata_to_graph((nodes, edges))
+}
+
+#[wasm_bindgen_test]
+fn test_find_cycles_complete_graph() {
+    let graph = get_complete_test_graph(5);
+
+    // a complete graph with 5 nodes has 10 cycles of length 2, 20 of length 3,
+    // 30 of length 4 and 24 of length 5
+    let cycles = graph.find_cycles(None, false, 10_000);
+    assert!(!cycles.truncated());
+
+    let cycles = cycles.to_paths();
+    assert_eq!(cycles.len(), 84);
+    for length in 2..=5 {
+        let count = cycles
+            .iter()
+            .filter(|cycle| cycle.length() == length)
+            .count();
+        assert_eq!(count, [10, 20, 30, 24][length - 2]);
+    }
+    for i in 1..cycles.len() {
+        assert!(!cycles[i - 1].equals(&cycles[i]));
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_find_cycles_truncated() {
+    let graph = get_complete_test_graph(5);
+
+    let cycles = graph.find_cycles(None, false, 20);
+    assert!(cycles.truncated());
+    assert!(!cycles.to_paths().is_empty());
+    assert!(cycles.to_paths().len() < 84);
+
+    // the components are searched in order, the first one needs exactly 3 edges,
+    // so the second one is not searched at all
+    let graph = get_cycle_test_graph();
+    let components = graph.find_cycle_components(edge_types(&["down"]), false, 3);
+    assert_eq!(components.len(), 2);
+    assert!(!components[0].truncated());
+    assert!(components[1].truncated());
+    assert!(components[1].to_paths().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_find_cycles_without_cycles() {
+    let graph = get_test_graph();
+
+    assert!(graph
+        .find_cycle_components(edge_types(&["down"]), true, 10_000)
+        .is_empty());
+}
+
+fn find_back_references(graph: &NoteGraph, data: &[TraversalData], result: &mut Vec<String>) {
+    for datum in data {
+        if datum.is_cycle_back_reference {
+            assert!(datum.children.is_empty());
+            result.push(format!(
+                "{} -> {}",
+                datum.edge.source_path(graph).unwrap(),
+                datum.edge.target_path(graph).unwrap()
+            ));
+        }
+
+        find_back_references(graph, &datum.children, result);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_rec_traverse_detect_cycles() {
+    let graph = get_cycle_test_graph();
+
+    let mut options = TraversalOptions::new(
+        vec!["root".to_string()],
+        edge_types(&["down"]),
+        20,
+        1000,
+        false,
+    );
+    options.detect_cycles = true;
+
+    let result = graph.rec_traverse(options).unwrap();
+
+    // 0, 00, root, 01, 1, 1, 10, 11
+    assert_eq!(result.node_count, 8);
+    assert!(!result.hit_depth_limit);
+
+    let mut back_references = Vec::new();
+    find_back_references(&graph, &result.data, &mut back_references);
+    back_references.sort();
+    assert_eq!(back_references, vec!["00 -> root", "1 -> 1"]);
+
+    let flat = graph
+        .rec_traverse(TraversalOptions::new(
+            vec!["1".to_string()],
+            edge_types(&["down"]),
+            20,
+            1000,
+            false,
+        ))
+        .unwrap();
+
+    // without cycle detection, the self loop is expanded until the depth limit
+    assert!(flat.node_count > 20);
+}
+
+#[wasm_bindgen_test]
+fn test_rec_traverse_truncated() {
+    let graph = get_test_graph();
+
+    let options = TraversalOptions::new(
+        vec!["root".to_string()],
+        edge_types(&["down"]),
+        10,
+        100,
+        false,
+    );
+    let result = graph.rec_traverse(options.clone()).unwrap();
+    assert_eq!(result.node_count, 14);
+    assert!(!result.truncated);
+
+    let mut options = options;
+    options.max_traversal_count = 5;
+    let result = graph.rec_traverse(options).unwrap();
+    assert_eq!(result.node_count, 5);
+    assert!(result.truncated);
+
+    // the traversal is depth first and stops in the middle of a level, so the
+    // second child of the root and the children of the last visited node are
+    // missing, even though they are less deep than other visited nodes
+    let first_child = &result.data[0];
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(first_child.children.len(), 2);
+    assert_eq!(first_child.children[0].children.len(), 2);
+    assert!(first_child.children[1].children.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_traversal_options_with_defaults() {
+    let options = TraversalOptions::with_defaults(vec!["root".to_string()], None, 5, false);
+
+    assert_eq!(options.max_traversal_count, DEFAULT_MAX_TRAVERSAL_COUNT);
+}
+
+#[wasm_bindgen_test]
+fn test_rec_traverse_cycle_is_truncated() {
+    let graph = get_cycle_test_graph();
+
+    // the self loop is expanded until the max traversal count is reached
+    let options = TraversalOptions::new(
+        vec!["1".to_string()],
+        edge_types(&["down"]),
+        1000,
+        500,
+        false,
+    );
+    let result = graph.rec_traverse(options).unwrap();
+
+    assert!(result.truncated);
+    assert_eq!(result.node_count, 500);
+    assert!(result.flatten().truncated);
+}
+
+#[wasm_bindgen_test]
+fn test_rec_traverse_incoming() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let mut options =
+        TraversalOptions::new(vec!["10".to_string()], edge_types(&["down"]), 5, 100, false);
+    options.direction = TraversalDirection::Incoming;
+
+    let result = graph.rec_traverse(options).unwrap();
+    assert_eq!(result.node_count, 2);
+
+    let paths = result.to_paths().to_paths();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(path_nodes(&graph, &paths[0]), vec!["1", "root"]);
+    // the edge structs point away from the entry node
+    assert_eq!(paths[0].edges[0].source_path(&graph).unwrap(), "10");
+}
+
+#[wasm_bindgen_test]
+fn test_rec_traverse_both_directions() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let mut options =
+        TraversalOptions::new(vec!["1".to_string()], edge_types(&["down"]), 2, 100, false);
+    options.direction = TraversalDirection::Both;
+    options.detect_cycles = true;
+
+    let result = graph.rec_traverse(options).unwrap();
+
+    // 10, 11, root and from root 0, each with a back reference to 1
+    assert_eq!(result.node_count, 7);
+
+    let mut back_references = Vec::new();
+    find_back_references(&graph, &result.data, &mut back_references);
+    back_references.sort();
+    assert_eq!(back_references, vec!["10 -> 1", "11 -> 1", "root -> 1"]);
+}
+
+#[wasm_bindgen_test]
+fn test_traverse_basic_both_directions() {
+    let graph = common::tdata_to_graph(common::tdata_generate_tree(2, 2));
+
+    let mut options =
+        TraversalOptions::new(vec!["1".to_string()], edge_types(&["down"]), 5, 100, false);
+    options.direction = TraversalDirection::Both;
+
+    let (nodes, edges) = graph.int_traverse_basic(&options).unwrap();
+
+    // the whole tree is reachable and every edge is only visited once
+    assert_eq!(nodes.len(), 7);
+    assert_eq!(edges.len(), 6);
+}
+
+fn collect_nodes(graph: &NoteGraph, data: &[TraversalData], result: &mut Vec<String>) {
+    for datum in data {
+        result.push(datum.edge.target_path(graph).unwrap());
+        collect_nodes(graph, &datum.children, result);
+    }
+}
+
+fn strings(strings: &[&str]) -> Vec<String> {
+    strings.iter().map(|s| s.to_string()).collect()
+}
+
+#[wasm_bindgen_test]
+fn test_edge_pattern_matches() {
+    let pattern = EdgeTypePattern::new("up+ same".to_string()).unwrap();
+
+    assert!(pattern.matches(strings(&["up", "same"])));
+    assert!(pattern.matches(strings(&["up", "up", "up", "same"])));
+    assert!(!pattern.matches(strings(&["same"])));
+    assert!(!pattern.matches(strings(&["up", "same", "same"])));
+    assert!(!pattern.matches(strings(&["up"])));
+
+    let pattern = EdgeTypePattern::new("(up | \"next note\")*, .?".to_string()).unwrap();
+
+    assert!(pattern.matches(strings(&[])));
+    assert!(pattern.matches(strings(&["next note", "up", "down"])));
+    assert!(!pattern.matches(strings(&["down", "up"])));
+}
+
+#[wasm_bindgen_test]
+fn test_edge_pattern_from_sequence_round_trips() {
+    let edge_types = strings(&["up", "next note", "a.b", "say \"hi\"", "back\\slash", ""]);
+    let pattern = EdgeTypePattern::from_sequence(edge_types.clone());
+
+    assert_eq!(
+        pattern.to_fancy_string(),
+        r#"up "next note" "a.b" "say \"hi\"" back\slash """#
+    );
+
+    let parsed = EdgeTypePattern::new(pattern.to_fancy_string()).unwrap();
+    assert!(parsed.matches(edge_types.clone()));
+    assert!(!parsed.matches(edge_types[..5].to_vec()));
+}
+
+#[wasm_bindgen_test]
+fn test_edge_pattern_invalid() {
+    for pattern in ["", "up |", "(up", "up)", "*", "\"up"] {
+        assert!(
+            EdgeTypePattern::new(pattern.to_string()).is_err(),
+            "{pattern}"
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_rule_path_pattern() {
+    let rule = TransitiveGraphRule::new(
+        "".to_string(),
+        vec!["up".to_string(), "down".to_string()],
+        "same".to_string(),
+        5,
+        false,
+        false,
+    );
+    let pattern = rule.path_pattern().unwrap();
+
+    assert!(pattern.matches(strings(&["up", "down"])));
+    assert!(!pattern.matches(strings(&["up"])));
+    assert!(!pattern.matches(strings(&["up", "down", "down"])));
+
+    // edge type patterns can't follow edges backwards
+    let reversed = TransitiveGraphRule::from_steps(
+        "".to_string(),
+        vec![
+            RuleStep::new("up".to_string(), false),
+            RuleStep::new("up".to_string(), true),
+        ],
+        "same".to_string(),
+        5,
+        false,
+        false,
+    );
+    assert!(reversed.path_pattern().is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_rec_traverse_edge_pattern() {
+    let graph = get_test_graph();
+
+    let mut options = TraversalOptions::new(vec!["000".to_string()], None, 10, 100, false);
+    options.edge_pattern = Some(EdgeTypePattern::new("up+ same".to_string()).unwrap());
+
+    let result = graph.rec_traverse(options).unwrap();
+
+    let mut nodes = Vec::new();
+    collect_nodes(&graph, &result.data, &mut nodes);
+    nodes.sort();
+
+    // the branch to the root is dropped, since the root has no siblings
+    assert_eq!(nodes, vec!["0", "00", "01", "1"]);
+}
+
+#[wasm_bindgen_test]
+fn test_basic_traversal_rejects_edge_pattern() {
+    let graph = get_test_graph();
+
+    let mut options = TraversalOptions::new(vec!["000".to_string()], None, 10, 100, false);
+    options.edge_pattern = Some(EdgeTypePattern::new("up+ same".to_string()).unwrap());
+
+    assert!(graph.int_traverse_basic(&options).is_err());
+}
+
+fn get_node_filter_test_graph() -> NoteGraph {
+    let nodes = vec![
+        GCNodeData::new("root".to_string(), vec![], true, false, false),
+        GCNodeData::new("a/x".to_string(), vec![], true, false, false),
+        GCNodeData::new("a/x/deep".to_string(), vec![], true, false, false),
+        GCNodeData::new(
+            "a/y".to_string(),
+            vec!["why".to_string()],
+            true,
+            false,
+            false,
+        ),
+        GCNodeData::new(
+            "b/z".to_string(),
+            vec!["zed".to_string()],
+            true,
+            false,
+            false,
+        ),
+    ];
+    let edges = [
+        ("root", "a/x"),
+        ("a/x", "a/x/deep"),
+        ("root", "a/y"),
+        ("root", "b/z"),
+        ("root", "missing"),
+    ]
+    .into_iter()
+    .map(|(source, target)| {
+        GCEdgeData::new(
+            source.to_string(),
+            target.to_string(),
+            "down".to_string(),
+            "typed-link".to_string(),
+        )
+    })
+    .collect();
+
+    common::tdata_to_graph((nodes, edges))
+}
+
+fn rec_traverse_with_filter(graph: &NoteGraph, node_filter: NodeFilter) -> Vec<String> {
+    let mut options = TraversalOptions::new(vec!["root".to_string()], None, 10, 100, false);
+    options.node_filter = Some(node_filter);
+
+    let result = graph.rec_traverse(options).unwrap();
+
+    let mut nodes = Vec::new();
+    collect_nodes(graph, &result.data, &mut nodes);
+    nodes.sort();
+    nodes
+}
+
+#[wasm_bindgen_test]
+fn test_node_filter_unresolved_and_folders() {
+    let graph = get_node_filter_test_graph();
+
+    let mut node_filter = NodeFilter::new();
+    node_filter.skip_unresolved = true;
+    node_filter.exclude_paths = vec!["b/".to_string()];
+
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/x", "a/x/deep", "a/y"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_node_filter_path_glob() {
+    let graph = get_node_filter_test_graph();
+
+    // a single star doesn't match across folders, so "a/x/deep" is skipped
+    let mut node_filter = NodeFilter::new();
+    node_filter.include_paths = Some(vec!["a/*".to_string()]);
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/x", "a/y"]
+    );
+
+    let mut node_filter = NodeFilter::new();
+    node_filter.include_paths = Some(vec!["a/**".to_string()]);
+    node_filter.exclude_paths = vec!["?/y".to_string()];
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/x", "a/x/deep"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_path_glob_with_many_stars() {
+    // without memoization, this takes exponential time in the number of stars
+    let path = "a".repeat(200);
+    assert!(!path_matches(&format!("{}b", "**a".repeat(30)), &path));
+    assert!(!path_matches(&format!("{}b", "*a".repeat(30)), &path));
+    assert!(path_matches(&format!("{}**", "**a".repeat(30)), &path));
+
+    assert!(path_matches("a/**/c?.md", "a/b/b/cé.md"));
+    assert!(!path_matches("a/*/c?.md", "a/b/b/cé.md"));
+}
+
+#[wasm_bindgen_test]
+fn test_node_filter_aliases() {
+    let graph = get_node_filter_test_graph();
+
+    let mut node_filter = NodeFilter::new();
+    node_filter.include_aliases = Some(vec!["zed".to_string(), "why".to_string()]);
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/y", "b/z"]
+    );
+
+    let mut node_filter = NodeFilter::new();
+    node_filter.exclude_aliases = vec!["zed".to_string()];
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/x", "a/x/deep", "a/y", "missing"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_node_filter_predicate() {
+    let graph = get_node_filter_test_graph();
+
+    let mut node_filter = NodeFilter::new();
+    node_filter.predicate = Some(js_sys::Function::new_with_args(
+        "node",
+        "return node.path !== 'a/x'",
+    ));
+
+    // the children of a skipped node are not visited either
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/y", "b/z", "missing"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_traverse_basic_node_filter() {
+    let graph = get_node_filter_test_graph();
+
+    let mut options = TraversalOptions::new(vec!["root".to_string()], None, 10, 100, false);
+    let mut node_filter = NodeFilter::new();
+    node_filter.skip_unresolved = true;
+    node_filter.exclude_paths = vec!["a/x".to_string()];
+    options.node_filter = Some(node_filter);
+
+    let (nodes, edges) = graph.int_traverse_basic(&options).unwrap();
+
+    let mut nodes: Vec<String> = nodes
+        .into_iter()
+        .map(|(node, _)| graph.int_get_node_weight(node).unwrap().path.clone())
+        .collect();
+    nodes.sort();
+
+    assert_eq!(nodes, vec!["a/y", "b/z", "root"]);
+    assert_eq!(edges.len(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_node_filter_properties() {
+    let mut graph = get_node_filter_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    for (node, status) in [("a/x", "done"), ("a/x/deep", "open"), ("a/y", "open")] {
+        SetNotePropertyGraphUpdate::new(
+            node.to_string(),
+            "status".to_string(),
+            JsValue::from_str(status),
+        )
+        .unwrap()
+        .add_to_batch(&mut batch);
+    }
+    SetNotePropertyGraphUpdate::new(
+        "b/z".to_string(),
+        "status".to_string(),
+        js_sys::Array::of2(&JsValue::from_str("open"), &JsValue::from_str("blocked")).into(),
+    )
+    .unwrap()
+    .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    // lists match if they contain the value
+    let mut node_filter = NodeFilter::new();
+    node_filter
+        .require_property("status".to_string(), JsValue::from_str("open"))
+        .unwrap();
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/y", "b/z"]
+    );
+
+    let mut node_filter = NodeFilter::new();
+    node_filter
+        .require_property("status".to_string(), JsValue::UNDEFINED)
+        .unwrap();
+    node_filter
+        .exclude_property("status".to_string(), JsValue::from_str("blocked"))
+        .unwrap();
+    assert_eq!(
+        rec_traverse_with_filter(&graph, node_filter),
+        vec!["a/x", "a/x/deep", "a/y"]
+    );
+}
@@ -9,7 +9,8 @@ use breadcrumbs_graph_wasm::{
     },
     graph::NoteGraph,
     update::{
-        batch::BatchGraphUpdate, AddEdgeGraphUpdate, AddNoteGraphUpdate, RemoveNoteGraphUpdate,
+        batch::BatchGraphUpdate, AddEdgeGraphUpdate, AddNoteGraphUpdate, RemoveEdgeGraphUpdate,
+        RemoveNoteGraphUpdate, RenameNoteGraphUpdate,
     },
     utils::graph_eq,
 };
@@ -269,3 +270,352 @@ fn test_unresolved_node_can_have_outgoing_edges() {
     assert_eq!(graph.graph.node_count(), 0);
     assert_eq!(graph.graph.edge_count(), 0);
 }
+
+#[wasm_bindgen_test]
+fn test_failed_update_rolls_back() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph_1 = common::tdata_to_graph(data.clone());
+    let graph_2 = common::tdata_to_graph(data);
+
+    let mut batch = BatchGraphUpdate::new();
+    RemoveNoteGraphUpdate::new("0".to_string()).add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("1".to_string(), "2".to_string()).add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("0".to_string(), "3".to_string()).add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("missing".to_string(), "4".to_string()).add_to_batch(&mut batch);
+
+    let error = graph_1.apply_update(batch).unwrap_err();
+
+    assert_eq!(error.update_index(), Some(3));
+    assert!(graph_eq(&graph_1.graph, &graph_2.graph));
+    assert_eq!(graph_1.get_revision(), graph_2.get_revision());
+    assert!(graph_1.has_node("0".to_string()));
+    assert!(!graph_1.has_node("3".to_string()));
+    graph_1.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_failed_build_keeps_previous_graph() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph_1 = common::tdata_to_graph(data.clone());
+    let graph_2 = common::tdata_to_graph(data);
+
+    let result = graph_1.build_graph(
+        vec![
+            GCNodeData::new("a".to_string(), vec![], true, false, false),
+            GCNodeData::new("a".to_string(), vec![], true, false, false),
+        ],
+        vec![],
+        vec![],
+    );
+
+    assert_eq!(result.unwrap_err().update_index(), Some(1));
+    assert!(graph_eq(&graph_1.graph, &graph_2.graph));
+    graph_1.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_failed_update_undoes_edge_changes() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph_1 = common::tdata_to_graph(data.clone());
+    let graph_2 = common::tdata_to_graph(data);
+    let graph_before = graph_1.graph.clone();
+    let edge_order_before = common::edge_order(&graph_1);
+
+    let mut batch = BatchGraphUpdate::new();
+    add_edge_update("root", "0", "list_note").add_to_batch(&mut batch);
+    add_edge_update("0", "new", "typed-link").add_to_batch(&mut batch);
+    remove_edge_source_update("0", "00", "typed-link").add_to_batch(&mut batch);
+    RenameNoteGraphUpdate::new("missing".to_string(), "4".to_string()).add_to_batch(&mut batch);
+
+    let error = graph_1.apply_update(batch).unwrap_err();
+
+    assert_eq!(error.update_index(), Some(3));
+    assert!(!graph_1.has_node("new".to_string()));
+    assert_eq!(
+        down_edge_sources(&graph_1, "root", "0"),
+        Some(vec!["typed-link".to_string()])
+    );
+    assert_eq!(
+        down_edge_sources(&graph_1, "0", "00"),
+        Some(vec!["typed-link".to_string()])
+    );
+    assert_eq!(graph_1.graph.node_count(), graph_2.graph.node_count());
+    assert_eq!(graph_1.graph.edge_count(), graph_2.graph.edge_count());
+    // the removed edge is restored at its old position, so neighbours are iterated
+    // in the same order as before
+    assert!(graph_eq(&graph_1.graph, &graph_before));
+    assert_eq!(common::edge_order(&graph_1), edge_order_before);
+    graph_1.assert_correct_trackers();
+}
+
+fn get_incremental_test_graph() -> NoteGraph {
+    let (mut nodes, mut edges) = common::tdata_generate_tree(3, 2);
+
+    // a second component that is not connected to the tree
+    nodes.push(GCNodeData::new("x".to_string(), vec![], true, false, false));
+    nodes.push(GCNodeData::new("y".to_string(), vec![], true, false, false));
+    edges.push(GCEdgeData::new(
+        "x".to_string(),
+        "y".to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ));
+
+    let rules = vec![
+        TransitiveGraphRule::new(
+            "".to_string(),
+            vec!["down".to_string()],
+            "up".to_string(),
+            5,
+            false,
+            true,
+        ),
+        TransitiveGraphRule::new(
+            "".to_string(),
+            vec!["up".to_string(), "down".to_string()],
+            "same".to_string(),
+            5,
+            false,
+            false,
+        ),
+        TransitiveGraphRule::new(
+            "".to_string(),
+            vec!["up".to_string(), "up".to_string()],
+            "ancestor".to_string(),
+            5,
+            false,
+            false,
+        ),
+        TransitiveGraphRule::new(
+            "".to_string(),
+            vec!["ancestor".to_string(), "up".to_string()],
+            "ancestor".to_string(),
+            5,
+            false,
+            false,
+        ),
+        TransitiveGraphRule::new(
+            "".to_string(),
+            vec!["next".to_string(), "up".to_string()],
+            "next-parent".to_string(),
+            5,
+            false,
+            false,
+        ),
+    ];
+
+    let mut graph = NoteGraph::new();
+    graph.build_graph(nodes, edges, rules).unwrap();
+    graph
+}
+
+fn new_edge(from: &str, to: &str) -> AddEdgeGraphUpdate {
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        from.to_string(),
+        to.to_string(),
+        "down".to_string(),
+        "typed-link".to_string(),
+    ))
+}
+
+#[wasm_bindgen_test]
+fn test_incremental_update_matches_full_rebuild() {
+    let mut graph_1 = get_incremental_test_graph();
+    let mut graph_2 = get_incremental_test_graph();
+    graph_2.set_incremental_updates(false);
+
+    let batches: Vec<fn() -> BatchGraphUpdate> = vec![
+        || {
+            // the up edges are not affected by this, but the next-parent edges
+            // are built from them
+            let mut batch = BatchGraphUpdate::new();
+            AddEdgeGraphUpdate::new(GCEdgeData::new(
+                "00".to_string(),
+                "01".to_string(),
+                "next".to_string(),
+                "typed-link".to_string(),
+            ))
+            .add_to_batch(&mut batch);
+            batch
+        },
+        || {
+            let mut batch = BatchGraphUpdate::new();
+            RemoveNoteGraphUpdate::new("0".to_string()).add_to_batch(&mut batch);
+            batch
+        },
+        || {
+            let mut batch = BatchGraphUpdate::new();
+            new_edge("11", "x").add_to_batch(&mut batch);
+            batch
+        },
+        || {
+            let mut batch = BatchGraphUpdate::new();
+            AddNoteGraphUpdate::new(GCNodeData::new("z".to_string(), vec![], true, false, false))
+                .add_to_batch(&mut batch);
+            new_edge("y", "z").add_to_batch(&mut batch);
+            new_edge("z", "zz").add_to_batch(&mut batch);
+            batch
+        },
+        || {
+            let mut batch = BatchGraphUpdate::new();
+            RemoveEdgeGraphUpdate::new("root".to_string(), "1".to_string(), "down".to_string())
+                .add_to_batch(&mut batch);
+            RenameNoteGraphUpdate::new("10".to_string(), "ten".to_string())
+                .add_to_batch(&mut batch);
+            batch
+        },
+        || {
+            let mut batch = BatchGraphUpdate::new();
+            AddNoteGraphUpdate::new(GCNodeData::new("0".to_string(), vec![], true, false, false))
+                .add_to_batch(&mut batch);
+            new_edge("0", "00").add_to_batch(&mut batch);
+            new_edge("0", "01").add_to_batch(&mut batch);
+            batch
+        },
+        || {
+            // the unresolved node "zz" is left without edges and is removed
+            let mut batch = BatchGraphUpdate::new();
+            RemoveEdgeGraphUpdate::new("z".to_string(), "zz".to_string(), "down".to_string())
+                .add_to_batch(&mut batch);
+            RemoveNoteGraphUpdate::new("x".to_string()).add_to_batch(&mut batch);
+            batch
+        },
+    ];
+
+    for batch in batches {
+        graph_1.apply_update(batch()).unwrap();
+        graph_2.apply_update(batch()).unwrap();
+
+        assert!(graph_eq(&graph_1.graph, &graph_2.graph));
+        graph_1.assert_correct_trackers();
+    }
+
+    assert!(!graph_1.has_node("zz".to_string()));
+}
+
+#[wasm_bindgen_test]
+fn test_incremental_update_keeps_unaffected_edges() {
+    let mut graph = get_incremental_test_graph();
+
+    let mut batch = BatchGraphUpdate::new();
+    new_edge("000", "0000").add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert!(graph.int_has_edge_by_name("y", "x", "up"));
+    assert!(graph.int_has_edge_by_name("0000", "000", "up"));
+    assert!(graph.int_has_edge_by_name("0000", "root", "ancestor"));
+
+    let ancestor = graph
+        .int_get_edge_by_name("0000", "root", "ancestor")
+        .unwrap();
+    assert_eq!(ancestor.weight().round, 4);
+    graph.assert_correct_trackers();
+}
+
+fn add_edge_update(from: &str, to: &str, edge_source: &str) -> AddEdgeGraphUpdate {
+    AddEdgeGraphUpdate::new(GCEdgeData::new(
+        from.to_string(),
+        to.to_string(),
+        "down".to_string(),
+        edge_source.to_string(),
+    ))
+}
+
+fn remove_edge_source_update(from: &str, to: &str, edge_source: &str) -> RemoveEdgeGraphUpdate {
+    RemoveEdgeGraphUpdate::with_source(
+        from.to_string(),
+        to.to_string(),
+        "down".to_string(),
+        edge_source.to_string(),
+    )
+}
+
+fn down_edge_sources(graph: &NoteGraph, from: &str, to: &str) -> Option<Vec<String>> {
+    graph
+        .get_outgoing_edges(from.to_string())
+        .get_edges()
+        .into_iter()
+        .find(|edge| edge.edge_type() == "down" && edge.target_path(graph).unwrap() == to)
+        .map(|edge| edge.edge_sources(graph).unwrap())
+}
+
+#[wasm_bindgen_test]
+fn test_edge_with_multiple_sources() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph = common::tdata_to_graph(data);
+
+    let mut batch = BatchGraphUpdate::new();
+    add_edge_update("root", "0", "list_note").add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_edge_sources(&graph, "root", "0"),
+        Some(vec!["typed-link".to_string(), "list_note".to_string()])
+    );
+
+    // removing one source keeps the edge
+    let mut batch = BatchGraphUpdate::new();
+    remove_edge_source_update("root", "0", "typed-link").add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_edge_sources(&graph, "root", "0"),
+        Some(vec!["list_note".to_string()])
+    );
+    // the remaining source becomes the primary edge source
+    let edge = graph
+        .get_outgoing_edges("root".to_string())
+        .get_edges()
+        .into_iter()
+        .find(|edge| edge.target_path(&graph).unwrap() == "0")
+        .unwrap();
+    assert_eq!(edge.edge_source(&graph).unwrap(), "list_note");
+    graph.assert_correct_trackers();
+
+    // removing the last source removes the edge
+    let mut batch = BatchGraphUpdate::new();
+    remove_edge_source_update("root", "0", "list_note").add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(down_edge_sources(&graph, "root", "0"), None);
+    graph.assert_correct_trackers();
+}
+
+#[wasm_bindgen_test]
+fn test_edge_source_reference_count() {
+    let data = common::tdata_generate_tree(2, 2);
+    let mut graph = common::tdata_to_graph(data);
+
+    // the same source asserts the edge twice, e.g. two links in the same note
+    let mut batch = BatchGraphUpdate::new();
+    add_edge_update("root", "0", "typed-link").add_to_batch(&mut batch);
+    add_edge_update("root", "0", "list_note").add_to_batch(&mut batch);
+    remove_edge_source_update("root", "0", "typed-link").add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(
+        down_edge_sources(&graph, "root", "0"),
+        Some(vec!["typed-link".to_string(), "list_note".to_string()])
+    );
+
+    // removing a source that doesn't assert the edge fails
+    let mut batch = BatchGraphUpdate::new();
+    remove_edge_source_update("root", "0", "dendron_note").add_to_batch(&mut batch);
+    assert!(graph.apply_update(batch).is_err());
+
+    // removing without a source fails while multiple sources assert the edge
+    let mut batch = BatchGraphUpdate::new();
+    RemoveEdgeGraphUpdate::new("root".to_string(), "0".to_string(), "down".to_string())
+        .add_to_batch(&mut batch);
+    assert!(graph.apply_update(batch).is_err());
+
+    // with a single source left, it removes that source
+    let mut batch = BatchGraphUpdate::new();
+    remove_edge_source_update("root", "0", "list_note").add_to_batch(&mut batch);
+    RemoveEdgeGraphUpdate::new("root".to_string(), "0".to_string(), "down".to_string())
+        .add_to_batch(&mut batch);
+    graph.apply_update(batch).unwrap();
+
+    assert_eq!(down_edge_sources(&graph, "root", "0"), None);
+    graph.assert_correct_trackers();
+}